@@ -0,0 +1,84 @@
+use ArchT3::perception::signature32::Signature32;
+use ArchT3::perception::universal_transducer::UniversalTransducer;
+use ArchT3::perception::universal_vector::Signature;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn random_signature(channels: usize, seed: u64) -> Signature {
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    (0..16).fold(Signature::zero(channels + 1), |acc, _| {
+        let dx: Vec<f64> = (0..channels).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        acc.combine(&Signature::from_segment(rng.gen_range(0.01..0.1), &dx))
+    })
+}
+
+fn bench_distance_precision(c: &mut Criterion) {
+    let prototypes: Vec<Signature> = (0..1000).map(|i| random_signature(3, i)).collect();
+    let prototypes32: Vec<Signature32> = prototypes.iter().map(Signature32::from).collect();
+    let query = random_signature(3, 10_000);
+    let query32 = Signature32::from(&query);
+
+    c.bench_function("distance_f64_x1000", |b| {
+        b.iter(|| prototypes.iter().map(|p| black_box(&query).distance(p)).sum::<f64>())
+    });
+    c.bench_function("distance_f32_x1000", |b| {
+        b.iter(|| prototypes32.iter().map(|p| black_box(&query32).distance(p)).sum::<f32>())
+    });
+}
+
+fn bench_batch_resonance(c: &mut Criterion) {
+    let concepts: Vec<Signature> = (0..5000).map(|i| random_signature(3, i)).collect();
+    let query = random_signature(3, 10_000);
+
+    c.bench_function("resonance_loop_x5000", |b| {
+        b.iter(|| {
+            concepts.iter().map(|o| {
+                let q = black_box(&query);
+                q.dot(o) / (q.magnitude() * o.magnitude())
+            }).collect::<Vec<f64>>()
+        })
+    });
+    c.bench_function("resonances_to_x5000", |b| {
+        b.iter(|| black_box(&query).resonances_to(&concepts))
+    });
+    c.bench_function("distances_to_x5000", |b| {
+        b.iter(|| black_box(&query).distances_to(&concepts))
+    });
+}
+
+fn bench_chen_accumulation(c: &mut Criterion) {
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+    let steps: Vec<Signature> = (0..1000)
+        .map(|_| {
+            let dx: Vec<f64> = (0..3).map(|_| rng.gen_range(-0.1..0.1)).collect();
+            Signature::from_segment(0.01, &dx)
+        })
+        .collect();
+
+    c.bench_function("combine_chain_x1000", |b| {
+        b.iter(|| steps.iter().fold(Signature::zero(4), |acc, s| acc.combine(black_box(s))))
+    });
+    c.bench_function("combine_assign_chain_x1000", |b| {
+        b.iter(|| {
+            let mut acc = Signature::zero(4);
+            for s in &steps {
+                acc.combine_assign(black_box(s));
+            }
+            acc
+        })
+    });
+}
+
+fn bench_transducer(c: &mut Criterion) {
+    let times: Vec<f64> = (0..20_000).map(|i| i as f64 * 0.001).collect();
+    let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * 3.0).sin(), (t * 7.0).cos(), *t]).collect();
+
+    c.bench_function("segment_and_process_20k", |b| {
+        b.iter(|| UniversalTransducer::segment_and_process(black_box(&raw), &times).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_distance_precision, bench_batch_resonance, bench_chen_accumulation, bench_transducer);
+criterion_main!(benches);
@@ -0,0 +1,112 @@
+use ArchT3::cortex::prototypical_neural_unit::{
+    wire_swarm_topology, wire_swarm_topology_exhaustive, PnuBuilder, PrototypicalNeuralUnit, TopologyConfig,
+};
+use ArchT3::cortex::swarm::Swarm;
+use ArchT3::perception::universal_vector::SignatureMetric;
+
+use criterion::{black_box, BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::{Rng, SeedableRng};
+
+fn topology() -> TopologyConfig {
+    TopologyConfig {
+        sigma_excitation: 0.3,
+        sigma_inhibition: 0.9,
+        amp_excitation: 2.0,
+        amp_inhibition: 1.0,
+        connection_cutoff: 0.05,
+        max_neighbors: 16,
+        metric: SignatureMetric::L2,
+    }
+}
+
+fn random_swarm(n: usize, seed: u64) -> Swarm {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let mut swarm = Swarm::default();
+    for id in 0..n {
+        let weights = (0..4).map(|_| rng.gen_range(-2.0..2.0)).collect();
+        swarm.push(PnuBuilder::new(id).weights(weights).build().unwrap()).unwrap();
+    }
+    swarm
+}
+
+fn bench_wiring(c: &mut Criterion) {
+    let config = topology();
+
+    // Cost of one insertion should grow linearly with the swarm size
+    let mut group = c.benchmark_group("wire_incremental_one_unit");
+    for n in [1_000, 2_000, 4_000, 8_000] {
+        let mut swarm = random_swarm(n, 1);
+        swarm.wire(&config);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter_batched(
+                || {
+                    let mut s = swarm.clone();
+                    s.push(PnuBuilder::new(n).weights(vec![0.1, -0.2, 0.3, 0.0]).build().unwrap()).unwrap();
+                    s
+                },
+                // The swarm is returned so that dropping it is not timed
+                |mut s| {
+                    s.wire_incremental(black_box(&[n]), &config);
+                    s
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("wire_full");
+    group.sample_size(10);
+    for n in [1_000, 2_000] {
+        let swarm = random_swarm(n, 1);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter_batched(
+                || swarm.clone(),
+                |mut s| {
+                    s.wire(black_box(&config));
+                    s
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_spatial_index(c: &mut Criterion) {
+    let config = TopologyConfig { sigma_excitation: 0.2, sigma_inhibition: 0.5, connection_cutoff: 0.01, ..topology() };
+    let mut rng = rand::rngs::StdRng::seed_from_u64(2);
+    let units: Vec<PrototypicalNeuralUnit> = (0..10_000)
+        .map(|id| {
+            let weights = (0..3).map(|_| rng.gen_range(-10.0..10.0)).collect();
+            PnuBuilder::new(id).weights(weights).build().unwrap()
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("wire_10k");
+    group.sample_size(10);
+    group.bench_function("grid", |b| {
+        b.iter_batched(
+            || units.clone(),
+            |mut u| {
+                wire_swarm_topology(&mut u, black_box(&config));
+                u
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("exhaustive", |b| {
+        b.iter_batched(
+            || units.clone(),
+            |mut u| {
+                wire_swarm_topology_exhaustive(&mut u, black_box(&config));
+                u
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_wiring, bench_spatial_index);
+criterion_main!(benches);
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::VecDeque;
+
+/// FIFO of at most `capacity` items: pushing into a full buffer evicts the
+/// oldest item. Storage is allocated once, up to the capacity.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoundedVec<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> BoundedVec<T> {
+    pub fn new(capacity: usize) -> Self {
+        BoundedVec { items: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    /// Appends `item`; returns the evicted oldest item when full. With a zero
+    /// capacity nothing is kept (`item` itself is returned).
+    pub fn push(&mut self, item: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(item);
+        }
+        let evicted = if self.items.len() == self.capacity { self.items.pop_front() } else { None };
+        self.items.push_back(item);
+        evicted
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the capacity, evicting the oldest items that no longer fit.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        let excess = self.items.len().saturating_sub(capacity);
+        self.items.drain(..excess);
+        self.items.reserve(capacity - self.items.len());
+        self.capacity = capacity;
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() == self.capacity
+    }
+
+    /// Most recent item.
+    pub fn last(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// Oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifo_eviction() {
+        let mut buffer = BoundedVec::new(3);
+        assert_eq!(buffer.push(1), None);
+        assert_eq!(buffer.push(2), None);
+        assert_eq!(buffer.push(3), None);
+        assert!(buffer.is_full());
+        assert_eq!(buffer.push(4), Some(1));
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(buffer.last(), Some(&4));
+
+        buffer.set_capacity(2);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(buffer.push(5), Some(3));
+
+        let mut none = BoundedVec::new(0);
+        assert_eq!(none.push('a'), Some('a'));
+        assert!(none.is_empty());
+    }
+}
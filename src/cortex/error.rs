@@ -0,0 +1,37 @@
+use std::fmt;
+
+/// Errors raised by the cortex (PNUs and swarms).
+#[derive(Debug, Clone, PartialEq)]
+pub enum CortexError {
+    /// Input length differs from the prototype (`weight_vector`) length.
+    DimensionMismatch { expected: usize, got: usize },
+    /// A unit with this id is already in the swarm.
+    DuplicateId(usize),
+    /// A prototype needs at least one term.
+    EmptyPrototype,
+    /// Negative (or NaN) value for a rate, bound or time constant.
+    InvalidParameter { name: &'static str, value: f32 },
+    /// A lateral link of unit `source` targets an id absent from the swarm.
+    DanglingLink { source: usize, target: usize },
+    /// NaN or infinite term in a presented input.
+    NonFiniteInput { index: usize },
+}
+
+impl fmt::Display for CortexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CortexError::DimensionMismatch { expected, got } => {
+                write!(f, "Dimension mismatch: prototype has {} terms, input has {}", expected, got)
+            }
+            CortexError::DuplicateId(id) => write!(f, "A unit with id {} is already in the swarm", id),
+            CortexError::EmptyPrototype => write!(f, "Prototype weight vector is empty"),
+            CortexError::InvalidParameter { name, value } => write!(f, "Invalid {}: {}", name, value),
+            CortexError::DanglingLink { source, target } => {
+                write!(f, "Unit {} has a lateral link to unknown unit {}", source, target)
+            }
+            CortexError::NonFiniteInput { index } => write!(f, "Non-finite input term at index {}", index),
+        }
+    }
+}
+
+impl std::error::Error for CortexError {}
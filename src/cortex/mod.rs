@@ -1 +1,6 @@
-pub mod prototypical_neural_unit;
\ No newline at end of file
+pub mod bounded_vec;
+pub mod error;
+pub mod label;
+pub mod prototypical_neural_unit;
+pub mod spatial_index;
+pub mod swarm;
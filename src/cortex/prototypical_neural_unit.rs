@@ -1,15 +1,29 @@
 use crate::perception::universal_vector::UniversalVector;
-
+use crate::perception::universal_vector::SignatureMetric;
+use crate::perception::universal_vector::Signature;
+use crate::perception::quant::{self, QuantScheme, QuantizedSignature};
+use crate::perception::projection::Pca2D;
+use crate::cortex::bounded_vec::BoundedVec;
+use crate::cortex::error::CortexError;
+use crate::cortex::label::Label;
+use crate::cortex::spatial_index::PrototypeGrid;
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
 use std::f32::consts::E;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LateralLink {
     pub target_id: usize,
     pub weight: f32,
     pub plasticity_rate: f32,
+    /// Weight given by the topology at wiring time (after the Gershgorin
+    /// rescaling), toward which `Swarm::decay_links` relaxes idle links.
+    pub prior_weight: f32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TemporalCorrelation {
     pub pnu_id: usize,
     pub correlation_strength: f32,
@@ -17,23 +31,86 @@ pub struct TemporalCorrelation {
 }
 
 /// Handle to raw signature (episodic memory)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SignatureHandle {
     pub signature_segment: Vec<f32>,
+    /// Compact i8 form; when present, `signature_segment` is left empty.
+    pub quantized: Option<QuantizedSignature>,
     pub timestamp: f64,
     pub scene_context_id: u64,
 }
 
-#[derive(Clone, Debug)]
+impl SignatureHandle {
+    pub fn new(signature: &Signature, timestamp: f64, scene_context_id: u64) -> Self {
+        SignatureHandle {
+            signature_segment: signature.flatten().iter().map(|&x| x as f32).collect(),
+            quantized: None,
+            timestamp,
+            scene_context_id,
+        }
+    }
+
+    /// Stores the episode quantized (1 byte per term instead of 4).
+    pub fn new_quantized(signature: &Signature, timestamp: f64, scene_context_id: u64, scheme: QuantScheme) -> Self {
+        SignatureHandle {
+            signature_segment: Vec::new(),
+            quantized: Some(quant::quantize(signature, scheme)),
+            timestamp,
+            scene_context_id,
+        }
+    }
+
+    /// Flattened terms, dequantized if needed.
+    pub fn segment(&self) -> Vec<f32> {
+        match &self.quantized {
+            Some(q) => q.flatten(),
+            None => self.signature_segment.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PNUState {
     pub activation: f32, // x_i
     pub derivative: f32, // dx_i/dt
+    pub time: f64,       // Integration time, advanced by `step`
+    pub resting: bool,   // Silent after exhaustion, until the budget is healthy again
+    pub exhausted_since: Option<f64>, // Time the unit became exhausted (None while it is not)
 }
 
-#[derive(Clone, Debug)]
+/// Outcome of `PrototypicalNeuralUnit::enforce_gershgorin_stability`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StabilityReport {
+    /// The lateral sum reached the auto-inhibition A: the links were rescaled.
+    pub violated: bool,
+    /// Factor applied to every link weight (1 when not violated).
+    pub scaling_applied: f32,
+    /// Sum(|w|) before and after the rescaling.
+    pub lateral_sum_before: f32,
+    pub lateral_sum_after: f32,
+}
+
+/// Budget level, as read by the strategist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetabolicState {
+    /// Above `STRAINED_BUDGET_FRACTION` of the maximum budget.
+    Healthy,
+    Strained,
+    /// Budget spent, or resting until it is healthy again: the unit is silent.
+    Exhausted,
+}
+
+/// Episodes a unit remembers unless configured otherwise.
+pub const DEFAULT_EPISODE_CAPACITY: usize = 8;
+
+/// Budget fraction under which a unit is `Strained` (and a resting unit wakes up
+/// above it).
+pub const STRAINED_BUDGET_FRACTION: f32 = 0.5;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PrototypicalNeuralUnit {
     pub id: usize,
-    pub symbolic_label: &'static str,
+    pub symbolic_label: Label,
 
     pub state: PNUState,
 
@@ -45,11 +122,14 @@ pub struct PrototypicalNeuralUnit {
     pub theta_base: f32,             
     pub theta_homeostatic: f32,      
     pub theta_semantic_fatigue: f32, 
+    pub fatigue_time_constant: f32, // Recovery time constant of the semantic fatigue
     // Pas besoin de "effective_threshold" : calculé à la volée dans la méthode `update()`
 
     // Metabolic Budget
     pub activation_budget: f32,
-    pub activation_consumption: f32,
+    pub activation_consumption: f32, // Budget spent per unit of positive activation and time
+    pub max_activation_budget: f32,
+    pub budget_recovery_rate: f32,   // Budget regained per unit of time while silent
 
     // Gain Control & Shunting
     pub auto_inhibition_a: f32,      // The "Leak" term A in Shunting Eq
@@ -63,7 +143,8 @@ pub struct PrototypicalNeuralUnit {
     pub lateral_links: Vec<LateralLink>, 
     pub temporal_correlations: Vec<TemporalCorrelation>,
 
-    pub signature_handle: SignatureHandle,
+    // Episodic memory: most recent instances of the concept, oldest evicted first
+    pub episodes: BoundedVec<SignatureHandle>,
 
     // Logic Interface
     pub truth_value: f32,            
@@ -72,16 +153,155 @@ pub struct PrototypicalNeuralUnit {
     pub surprise_sensitivity: f32,
     pub vigilance_contribution: f32,
 
-    pub last_spike_time: f64,
+    pub last_spike_time: Option<f64>, // None until the first spike
     pub last_surprise_time: f64,
     pub birth_timestamp: f64,
 }
 
+impl Default for PrototypicalNeuralUnit {
+    /// Standard parameters (A = 1, B = 1, C = 0.2, budget 100, theta 0.5) and an
+    /// empty prototype; see `PnuBuilder`.
+    fn default() -> Self {
+        PrototypicalNeuralUnit {
+            id: 0,
+            symbolic_label: Label::default(),
+            state: PNUState::default(),
+            weight_vector: Box::new([]),
+            learning_rate_eta: 0.01,
+            theta_base: 0.5,
+            theta_homeostatic: 0.0,
+            theta_semantic_fatigue: 0.0,
+            fatigue_time_constant: 1.0,
+            activation_budget: 100.0,
+            activation_consumption: 0.0,
+            max_activation_budget: 100.0,
+            budget_recovery_rate: 0.0,
+            auto_inhibition_a: 1.0,
+            a_base: 1.0,
+            gain_modulation_phi: 0.1,
+            shunting_b: 1.0,
+            shunting_c: 0.2,
+            decay_rate: 0.1,
+            lateral_links: Vec::new(),
+            temporal_correlations: Vec::new(),
+            episodes: BoundedVec::new(DEFAULT_EPISODE_CAPACITY),
+            truth_value: 0.0,
+            injection_threshold: 0.8,
+            surprise_sensitivity: 0.1,
+            vigilance_contribution: 0.0,
+            last_spike_time: None,
+            last_surprise_time: 0.0,
+            birth_timestamp: 0.0,
+        }
+    }
+}
+
+/// Validated construction of a PNU; every parameter not set keeps its
+/// `Default` value.
+#[derive(Clone, Debug)]
+pub struct PnuBuilder {
+    unit: PrototypicalNeuralUnit,
+}
+
+impl PnuBuilder {
+    pub fn new(id: usize) -> Self {
+        PnuBuilder { unit: PrototypicalNeuralUnit { id, ..PrototypicalNeuralUnit::default() } }
+    }
+
+    pub fn weights(mut self, weights: Vec<f32>) -> Self {
+        self.unit.weight_vector = weights.into_boxed_slice();
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<Label>) -> Self {
+        self.unit.symbolic_label = label.into();
+        self
+    }
+
+    pub fn learning_rate(mut self, eta: f32) -> Self {
+        self.unit.learning_rate_eta = eta;
+        self
+    }
+
+    pub fn threshold(mut self, theta_base: f32) -> Self {
+        self.unit.theta_base = theta_base;
+        self
+    }
+
+    /// Leak A and saturation bounds B (excitatory) and C (inhibitory).
+    pub fn shunting(mut self, a: f32, b: f32, c: f32) -> Self {
+        self.unit.auto_inhibition_a = a;
+        self.unit.a_base = a;
+        self.unit.shunting_b = b;
+        self.unit.shunting_c = c;
+        self
+    }
+
+    /// Initial and maximum budget, consumption and recovery rates.
+    pub fn budget(mut self, budget: f32, consumption: f32, recovery_rate: f32) -> Self {
+        self.unit.activation_budget = budget;
+        self.unit.max_activation_budget = budget;
+        self.unit.activation_consumption = consumption;
+        self.unit.budget_recovery_rate = recovery_rate;
+        self
+    }
+
+    pub fn fatigue_time_constant(mut self, tau: f32) -> Self {
+        self.unit.fatigue_time_constant = tau;
+        self
+    }
+
+    pub fn surprise_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.unit.surprise_sensitivity = sensitivity;
+        self
+    }
+
+    /// Extra resonance required to learn from a presentation (on top of the
+    /// swarm vigilance); raise it to protect a concept.
+    pub fn vigilance_contribution(mut self, contribution: f32) -> Self {
+        self.unit.vigilance_contribution = contribution;
+        self
+    }
+
+    pub fn injection_threshold(mut self, threshold: f32) -> Self {
+        self.unit.injection_threshold = threshold;
+        self
+    }
+
+    pub fn birth(mut self, timestamp: f64) -> Self {
+        self.unit.birth_timestamp = timestamp;
+        self
+    }
+
+    pub fn build(self) -> Result<PrototypicalNeuralUnit, CortexError> {
+        let unit = self.unit;
+        if unit.weight_vector.is_empty() {
+            return Err(CortexError::EmptyPrototype);
+        }
+        let non_negative = [
+            ("learning_rate_eta", unit.learning_rate_eta),
+            ("auto_inhibition_a", unit.auto_inhibition_a),
+            ("shunting_b", unit.shunting_b),
+            ("shunting_c", unit.shunting_c),
+            ("activation_budget", unit.activation_budget),
+            ("activation_consumption", unit.activation_consumption),
+            ("budget_recovery_rate", unit.budget_recovery_rate),
+            ("fatigue_time_constant", unit.fatigue_time_constant),
+            ("surprise_sensitivity", unit.surprise_sensitivity),
+        ];
+        if let Some(&(name, value)) = non_negative.iter().find(|(_, v)| v.is_nan() || *v < 0.0) {
+            return Err(CortexError::InvalidParameter { name, value });
+        }
+        Ok(unit)
+    }
+}
+
 // =============================================================================
 // 2. Topology Logic: Mexican Hat & Small World
 // =============================================================================
 
 /// Configuration for the Topological Generation
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TopologyConfig {
     pub sigma_excitation: f32, // Width of excitatory peak
     pub sigma_inhibition: f32, // Width of inhibitory crown
@@ -89,9 +309,33 @@ pub struct TopologyConfig {
     pub amp_inhibition: f32,   // Depth of inhibition
     pub connection_cutoff: f32,// Sparsity threshold (min absolute weight to keep link)
     pub max_neighbors: usize,  // Enforce O(sqrt(N)) sparsity
+    pub metric: SignatureMetric, // Distance used between prototypes (L2 = scale-sensitive Euclidean)
+}
+
+impl TopologyConfig {
+    /// Distance beyond which no Mexican-hat weight exceeds `connection_cutoff`:
+    /// |w(d)| <= (A_e + A_i) * exp(-d^2/2s^2) with s the wider sigma.
+    /// Infinite without a positive cutoff.
+    pub fn interaction_radius(&self) -> f32 {
+        if self.connection_cutoff <= 0.0 {
+            return f32::INFINITY;
+        }
+        let amplitude = self.amp_excitation.abs() + self.amp_inhibition.abs();
+        if amplitude <= self.connection_cutoff {
+            return 0.0;
+        }
+        let sigma = self.sigma_excitation.abs().max(self.sigma_inhibition.abs());
+        sigma * (2.0 * (amplitude / self.connection_cutoff).ln()).sqrt()
+    }
 }
 
 impl PrototypicalNeuralUnit {
+    /// Unit with default parameters around `weights`.
+    /// Panics if `weights` is empty.
+    pub fn with_prototype(id: usize, weights: Vec<f32>) -> Self {
+        PnuBuilder::new(id).weights(weights).build().expect("PNU prototype must not be empty")
+    }
+
     /// Calculates Euclidean distance between this PNU's prototype and another's.
     /// In Rough Paths space, this represents semantic distance.
     pub fn semantic_distance(&self, other: &PrototypicalNeuralUnit) -> f32 {
@@ -102,13 +346,200 @@ impl PrototypicalNeuralUnit {
             .sqrt()
     }
 
+    /// Distance between prototypes under the given metric.
+    /// `SignatureMetric::L2` is identical to `semantic_distance`.
+    pub fn distance_with(&self, other: &PrototypicalNeuralUnit, metric: SignatureMetric) -> f32 {
+        match metric {
+            SignatureMetric::L2 => self.semantic_distance(other),
+            _ => metric.between_f32(&self.weight_vector, &other.weight_vector),
+        }
+    }
+
+    /// Firing threshold: base + homeostatic + semantic fatigue terms.
+    pub fn effective_threshold(&self) -> f32 {
+        self.theta_base + self.theta_homeostatic + self.theta_semantic_fatigue
+    }
+
+    /// One explicit Euler step of the shunting equation
+    /// dx/dt = -Ax + (B-x)E - (x+C)I, with E = external + lateral excitation and
+    /// I = lateral inhibition. The activation is clamped to [-C, B].
+    /// Returns true when the activation crosses the effective threshold upward
+    /// (`last_spike_time` is then set to the new `state.time`).
+    pub fn step(&mut self, external_excitation: f32, lateral: (f32, f32), dt: f32) -> bool {
+        self.step_with(external_excitation, lateral, dt, Integrator::Euler)
+    }
+
+    /// `step` with the given integration scheme; the inputs stay frozen over
+    /// `dt`. `state.derivative` is dx/dt at the start of the step.
+    pub fn step_with(&mut self, external_excitation: f32, lateral: (f32, f32), dt: f32, integrator: Integrator) -> bool {
+        let (lateral_exc, lateral_inh) = lateral;
+        let x = self.state.activation;
+        let excitation = external_excitation + lateral_exc;
+        let (low, high) = (-self.shunting_c, self.shunting_b);
+        let f = |x: f32| {
+            -self.auto_inhibition_a * x
+                + (self.shunting_b - x) * excitation
+                - (x + self.shunting_c) * lateral_inh
+        };
+
+        let dx = f(x);
+        let mut next = match integrator {
+            Integrator::Euler => x + dt * dx,
+            Integrator::Rk4 => {
+                let k2 = f(x + 0.5 * dt * dx);
+                let k3 = f(x + 0.5 * dt * k2);
+                let k4 = f(x + dt * k3);
+                x + dt / 6.0 * (dx + 2.0 * k2 + 2.0 * k3 + k4)
+            }
+            Integrator::AdaptiveEuler { max_dx } => {
+                let mut substeps = 1;
+                while (dt / substeps as f32 * dx).abs() > max_dx && substeps < MAX_SUBSTEPS {
+                    substeps *= 2;
+                }
+                let h = dt / substeps as f32;
+                (0..substeps).fold(x, |y, _| (y + h * f(y)).clamp(low, high))
+            }
+        }
+        .clamp(low, high);
+        if self.is_exhausted() {
+            // Silent rather than saturating
+            next = next.min(0.0);
+        }
+        self.state.derivative = dx;
+        self.state.activation = next;
+        self.state.time += dt as f64;
+        if self.fatigue_time_constant > 0.0 {
+            self.theta_semantic_fatigue *= (-dt / self.fatigue_time_constant).exp();
+        }
+        self.update_budget(dt);
+
+        let threshold = self.effective_threshold();
+        let spiked = x < threshold && next >= threshold;
+        if spiked {
+            self.last_spike_time = Some(self.state.time);
+        }
+        spiked
+    }
+
+    /// Remembers an instance of the concept, forgetting the oldest one when the
+    /// episode buffer is full.
+    pub fn record_episode(&mut self, handle: SignatureHandle) {
+        self.episodes.push(handle);
+    }
+
+    /// Remembered episodes, oldest first.
+    pub fn episodes(&self) -> impl DoubleEndedIterator<Item = &SignatureHandle> {
+        self.episodes.iter()
+    }
+
+    pub fn most_recent_episode(&self) -> Option<&SignatureHandle> {
+        self.episodes.last()
+    }
+
+    /// Raises the semantic fatigue by `surprise_sensitivity * magnitude`; it then
+    /// decays exponentially during `step` (time constant `fatigue_time_constant`).
+    pub fn register_surprise(&mut self, magnitude: f32, now: f64) {
+        self.theta_semantic_fatigue += self.surprise_sensitivity * magnitude;
+        self.last_surprise_time = now;
+    }
+
+    /// Current semantic fatigue (threshold increase).
+    pub fn fatigue(&self) -> f32 {
+        self.theta_semantic_fatigue
+    }
+
+    /// The metabolic budget is spent (or the unit is still resting after having
+    /// spent it): the unit neither fires nor learns.
+    pub fn is_exhausted(&self) -> bool {
+        self.activation_budget <= 0.0 || self.state.resting
+    }
+
+    pub fn metabolic_state(&self) -> MetabolicState {
+        if self.is_exhausted() {
+            MetabolicState::Exhausted
+        } else if self.activation_budget < STRAINED_BUDGET_FRACTION * self.max_activation_budget {
+            MetabolicState::Strained
+        } else {
+            MetabolicState::Healthy
+        }
+    }
+
+    /// Consumption proportional to the positive activation; recovery toward
+    /// `max_activation_budget` while silent. An exhausted unit rests until its
+    /// budget is healthy again.
+    fn update_budget(&mut self, dt: f32) {
+        let activation = self.state.activation;
+        if activation > 0.0 {
+            self.activation_budget = (self.activation_budget - self.activation_consumption * activation * dt).max(0.0);
+        } else {
+            self.activation_budget = (self.activation_budget + self.budget_recovery_rate * dt)
+                .min(self.max_activation_budget.max(self.activation_budget));
+        }
+
+        if self.activation_budget <= 0.0 && self.activation_consumption > 0.0 {
+            self.state.resting = true;
+        } else if self.activation_budget >= STRAINED_BUDGET_FRACTION * self.max_activation_budget {
+            self.state.resting = false;
+        }
+
+        if !self.is_exhausted() {
+            self.state.exhausted_since = None;
+        } else if self.state.exhausted_since.is_none() {
+            self.state.exhausted_since = Some(self.state.time);
+        }
+    }
+
+    /// Oja's rule: dW = eta * y * (x - y * W), with y the current activation,
+    /// then renormalization onto the unit sphere.
+    /// Returns the magnitude of the weight change (0 when nothing was learned:
+    /// zero activation or exhausted budget).
+    pub fn learn_oja(&mut self, input: &[f32]) -> Result<f32, CortexError> {
+        self.learn_oja_at_rate(input, self.learning_rate_eta)
+    }
+
+    /// `learn_oja` with a rate raised by surprise: eta * (1 + kappa * surprise).
+    pub fn learn_oja_surprised(&mut self, input: &[f32], surprise: f32, kappa: f32) -> Result<f32, CortexError> {
+        self.learn_oja_at_rate(input, self.learning_rate_eta * (1.0 + kappa * surprise.max(0.0)))
+    }
+
+    fn learn_oja_at_rate(&mut self, input: &[f32], eta: f32) -> Result<f32, CortexError> {
+        if input.len() != self.weight_vector.len() {
+            return Err(CortexError::DimensionMismatch { expected: self.weight_vector.len(), got: input.len() });
+        }
+        let y = self.state.activation;
+        if y == 0.0 || self.is_exhausted() {
+            return Ok(0.0);
+        }
+
+        let previous = self.weight_vector.clone();
+        for (w, x) in self.weight_vector.iter_mut().zip(input) {
+            *w += eta * y * (x - y * *w);
+        }
+        let norm = self.weight_vector.iter().map(|w| w * w).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            self.weight_vector.iter_mut().for_each(|w| *w /= norm);
+        }
+
+        Ok(self.weight_vector.iter()
+            .zip(previous.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt())
+    }
+
     /// Checks the Gershgorin Circle Theorem condition for local stability.
     /// Condition: A_i > Sum(|z_ij|)
     /// If violated, it triggers Short-Term Plasticity (STP) to reduce lateral gain.
-    pub fn enforce_gershgorin_stability(&mut self) {
+    pub fn enforce_gershgorin_stability(&mut self) -> StabilityReport {
         let lateral_influx_sum: f32 = self.lateral_links.iter()
             .map(|link| link.weight.abs())
             .sum();
+        let mut report = StabilityReport {
+            violated: false,
+            scaling_applied: 1.0,
+            lateral_sum_before: lateral_influx_sum,
+            lateral_sum_after: lateral_influx_sum,
+        };
 
         // Check dominance diagonal condition
         if lateral_influx_sum >= self.auto_inhibition_a {
@@ -123,41 +554,74 @@ impl PrototypicalNeuralUnit {
                 link.weight *= scaling_factor;
             }
 
-            // Optionally: Log this event as "High Energy Stress"
+            report.violated = true;
+            report.scaling_applied = scaling_factor;
+            report.lateral_sum_after = self.lateral_links.iter().map(|link| link.weight.abs()).sum();
         }
+        report
     }
 }
 
+/// Plasticity rate of freshly wired links.
+pub(crate) const BASE_PLASTICITY: f32 = 0.01;
+
+/// Difference of Gaussians at semantic distance `dist`:
+/// w = A_e * exp(-d^2/2s_e^2) - A_i * exp(-d^2/2s_i^2)
+pub(crate) fn mexican_hat(dist: f32, config: &TopologyConfig) -> f32 {
+    let excitation = config.amp_excitation * E.powf(-(dist.powi(2)) / (2.0 * config.sigma_excitation.powi(2)));
+    let inhibition = config.amp_inhibition * E.powf(-(dist.powi(2)) / (2.0 * config.sigma_inhibition.powi(2)));
+    excitation - inhibition
+}
+
 /// Generates the "Mexican Hat" topology.
 /// This connects semantically similar neurons (positive weights) and 
 /// inhibits the semantic "crown" (negative weights).
-/// 
+/// Only pairs within `interaction_radius` are examined, through a
+/// `PrototypeGrid` (L2 and L1 metrics); the links are the same as
+/// `wire_swarm_topology_exhaustive`'s.
 pub fn wire_swarm_topology(swarm: &mut [PrototypicalNeuralUnit], config: &TopologyConfig) {
+    // An L1 ball lies inside the L2 ball of the same radius
+    let grid = match config.metric {
+        SignatureMetric::L2 | SignatureMetric::L1 => {
+            PrototypeGrid::build(swarm.iter().map(|pnu| &*pnu.weight_vector), config.interaction_radius())
+        }
+        _ => None,
+    };
+    wire_with(swarm, config, grid.as_ref());
+}
+
+/// `wire_swarm_topology` examining every pair (O(N²)).
+pub fn wire_swarm_topology_exhaustive(swarm: &mut [PrototypicalNeuralUnit], config: &TopologyConfig) {
+    wire_with(swarm, config, None);
+}
+
+fn wire_with(swarm: &mut [PrototypicalNeuralUnit], config: &TopologyConfig, grid: Option<&PrototypeGrid>) {
     let n = swarm.len();
 
     // Using indices to avoid borrowing conflicts
     for i in 0..n {
         let mut potential_links = Vec::with_capacity(n);
+        let candidates = match grid {
+            Some(grid) => grid.candidates(&swarm[i].weight_vector),
+            None => (0..n).collect(),
+        };
 
-        for j in 0..n {
+        for j in candidates {
             if i == j { continue; }
 
             // 1. Calculate Semantic Distance (Distance in Signature Space)
-            let dist = swarm[i].semantic_distance(&swarm[j]);
+            let dist = swarm[i].distance_with(&swarm[j], config.metric);
 
             // 2. Apply Mexican Hat Function (Difference of Gaussians)
-            // w = A_e * exp(-d^2/s_e^2) - A_i * exp(-d^2/s_i^2)
-            let excitation = config.amp_excitation * E.powf(-(dist.powi(2)) / (2.0 * config.sigma_excitation.powi(2)));
-            let inhibition = config.amp_inhibition * E.powf(-(dist.powi(2)) / (2.0 * config.sigma_inhibition.powi(2)));
-
-            let weight = excitation - inhibition;
+            let weight = mexican_hat(dist, config);
 
             // 3. Sparsity Filter (Cutoff)
             if weight.abs() > config.connection_cutoff {
                 potential_links.push(LateralLink {
                     target_id: swarm[j].id,
                     weight,
-                    plasticity_rate: 0.01, // Base plasticity
+                    plasticity_rate: BASE_PLASTICITY,
+                    prior_weight: weight,
                 });
             }
         }
@@ -174,27 +638,91 @@ pub fn wire_swarm_topology(swarm: &mut [PrototypicalNeuralUnit], config: &Topolo
         swarm[i].lateral_links = potential_links;
 
         // 5. Pre-emptive Stability Check
-        // Ensure initial wiring respects Gershgorin disks
-        swarm[i].enforce_gershgorin_stability();
+        // Ensure initial wiring respects Gershgorin disks (priors included)
+        let report = swarm[i].enforce_gershgorin_stability();
+        for link in &mut swarm[i].lateral_links {
+            link.prior_weight *= report.scaling_applied;
+        }
     }
 }
 
+/// Projects every prototype (`weight_vector`) to 2D for plotting.
+/// Returns (pnu id, coordinates).
+pub fn project_all(swarm: &[PrototypicalNeuralUnit], pca: &Pca2D) -> Vec<(usize, (f64, f64))> {
+    swarm.iter()
+        .map(|pnu| {
+            let flat: Vec<f64> = pnu.weight_vector.iter().map(|&w| w as f64).collect();
+            (pnu.id, pca.project_flat(&flat))
+        })
+        .collect()
+}
+
 // =============================================================================
 // 3. Diffusion Dynamics (Runtime)
 // =============================================================================
 
+/// Upper bound on the substeps of `Integrator::AdaptiveEuler`.
+pub const MAX_SUBSTEPS: usize = 1024;
+
+/// Integration scheme of `PrototypicalNeuralUnit::step_with`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Integrator {
+    /// Explicit Euler: one derivative evaluation.
+    #[default]
+    Euler,
+    /// Classic 4th-order Runge-Kutta: four derivative evaluations.
+    Rk4,
+    /// Euler on dt halved until a substep changes the activation by at most
+    /// `max_dx` (at most `MAX_SUBSTEPS` substeps).
+    AdaptiveEuler { max_dx: f32 },
+}
+
+/// Signal function f(x) turning an activation into the output seen by the
+/// neighbors (and by readouts). Faster-than-linear signals (`Power` with an
+/// exponent above 1, `Sigmoid`) enhance contrast in recurrent competition.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TransferFn {
+    /// max(0, x)
+    #[default]
+    Relu,
+    /// 1 / (1 + exp(-gain * (x - midpoint)))
+    Sigmoid { gain: f32, midpoint: f32 },
+    /// max(0, x)^exponent
+    Power { exponent: f32 },
+    /// 1 at or above `threshold`, 0 below
+    Binary { threshold: f32 },
+}
+
+impl TransferFn {
+    pub fn apply(&self, x: f32) -> f32 {
+        match *self {
+            TransferFn::Relu => x.max(0.0),
+            TransferFn::Sigmoid { gain, midpoint } => 1.0 / (1.0 + (-gain * (x - midpoint)).exp()),
+            TransferFn::Power { exponent } => x.max(0.0).powf(exponent),
+            TransferFn::Binary { threshold } => if x >= threshold { 1.0 } else { 0.0 },
+        }
+    }
+}
+
 /// Calculates the lateral input term for the Shunting Equation.
+/// Link targets are unit ids, resolved to positions in `swarm` through `index`;
+/// links to ids absent from `index` are skipped (see `Swarm::check_links`).
+/// Neighbors contribute their output `transfer.apply(activation)`.
 /// Returns (Excitatory_Sum, Inhibitory_Sum)
 /// Used in: dx/dt = -Ax + (B-x)E - (x+C)I
-pub fn calculate_lateral_input(pnu: &PrototypicalNeuralUnit, swarm: &[PrototypicalNeuralUnit]) -> (f32, f32) {
+pub fn calculate_lateral_input(
+    pnu: &PrototypicalNeuralUnit,
+    swarm: &[PrototypicalNeuralUnit],
+    index: &HashMap<usize, usize>,
+    transfer: TransferFn,
+) -> (f32, f32) {
     let mut exc_sum = 0.0;
     let mut inh_sum = 0.0;
 
     for link in &pnu.lateral_links {
-        let neighbor = &swarm[link.target_id];
+        let Some(neighbor) = index.get(&link.target_id).map(|&i| &swarm[i]) else { continue };
 
-        // Assuming f(x) is sigmoid or ReLU. Here using simple max(0, x) for signal
-        let signal = neighbor.state.activation.max(0.0); 
+        let signal = transfer.apply(neighbor.state.activation);
 
         if link.weight > 0.0 {
             // Excitation Voisine (Coopération)
@@ -207,4 +735,231 @@ pub fn calculate_lateral_input(pnu: &PrototypicalNeuralUnit, swarm: &[Prototypic
     }
 
     (exc_sum, inh_sum)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn pnu(id: usize, weights: Vec<f32>) -> PrototypicalNeuralUnit {
+        PnuBuilder::new(id).weights(weights).label("Test").build().unwrap()
+    }
+
+    #[test]
+    fn test_step_converges_to_fixed_point() {
+        let mut unit = pnu(0, vec![1.0]);
+        let excitation = 2.0;
+        let mut spikes = 0;
+        for _ in 0..2000 {
+            spikes += unit.step(excitation, (0.0, 0.0), 0.01) as usize;
+        }
+        // B·E / (A + E)
+        let expected = unit.shunting_b * excitation / (unit.auto_inhibition_a + excitation);
+        assert!((unit.state.activation - expected).abs() < 1e-4);
+        assert!(unit.state.derivative.abs() < 1e-3);
+        assert_eq!(spikes, 1);
+        assert!(unit.last_spike_time.is_some_and(|t| t > 0.0 && t < unit.state.time));
+    }
+
+    #[test]
+    fn test_step_stays_within_shunting_bounds() {
+        let mut unit = pnu(0, vec![1.0]);
+        for i in 0..500 {
+            // Alternating huge drives with a coarse step
+            let lateral = if i % 2 == 0 { (50.0, 0.0) } else { (0.0, 80.0) };
+            unit.step(10.0 * (i % 3) as f32, lateral, 0.5);
+            assert!(unit.state.activation <= unit.shunting_b);
+            assert!(unit.state.activation >= -unit.shunting_c);
+        }
+    }
+
+    #[test]
+    fn test_oja_rotates_toward_input() {
+        let mut unit = pnu(0, vec![1.0, 0.0, 0.0]);
+        unit.learning_rate_eta = 0.1;
+        unit.state.activation = 0.8;
+        let target = [0.0, 0.6, 0.8];
+
+        let cosine = |w: &[f32]| w.iter().zip(&target).map(|(a, b)| a * b).sum::<f32>();
+        let mut previous = cosine(&unit.weight_vector);
+        for _ in 0..200 {
+            unit.learn_oja(&target).unwrap();
+            let norm = unit.weight_vector.iter().map(|w| w * w).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-5);
+            let current = cosine(&unit.weight_vector);
+            assert!(current >= previous - 1e-6);
+            previous = current;
+        }
+        assert!(previous > 0.999);
+
+        // No activation, no learning; a wrong length is an error
+        unit.state.activation = 0.0;
+        let before = unit.weight_vector.clone();
+        assert_eq!(unit.learn_oja(&[1.0, 0.0, 0.0]), Ok(0.0));
+        assert_eq!(unit.weight_vector, before);
+        assert_eq!(unit.learn_oja(&[1.0]), Err(CortexError::DimensionMismatch { expected: 3, got: 1 }));
+
+        unit.state.activation = 0.8;
+        unit.activation_budget = 0.0;
+        assert_eq!(unit.learn_oja(&[1.0, 0.0, 0.0]), Ok(0.0));
+    }
+
+    #[test]
+    fn test_fatigue_raises_threshold_then_recovers() {
+        let mut unit = pnu(0, vec![1.0]);
+        unit.fatigue_time_constant = 2.0;
+        unit.register_surprise(3.0, 4.0);
+        let peak = unit.fatigue();
+        assert!((peak - 0.3).abs() < 1e-6);
+        assert_eq!(unit.last_surprise_time, 4.0);
+        assert!(unit.effective_threshold() > unit.theta_base);
+
+        // 5 time constants with no input
+        for _ in 0..1000 {
+            unit.step(0.0, (0.0, 0.0), 0.01);
+        }
+        assert!(unit.fatigue() < 0.01 * peak);
+
+        // Fixed point B·E/(A+E) = 0.6 > θ_base = 0.5, but < 0.5 + fatigue
+        let drive = |unit: &mut PrototypicalNeuralUnit| (0..300).any(|_| unit.step(1.5, (0.0, 0.0), 0.01));
+        let mut fresh = pnu(1, vec![1.0]);
+        let mut tired = pnu(2, vec![1.0]);
+        tired.fatigue_time_constant = 100.0;
+        tired.register_surprise(3.0, 0.0);
+        assert!(drive(&mut fresh));
+        assert!(!drive(&mut tired));
+    }
+
+    #[test]
+    fn test_exhaustion_silence_and_recovery() {
+        // Length of the first silent gap under continuous strong input
+        let silent_gap = |recovery_rate: f32| {
+            let mut unit = pnu(0, vec![1.0]);
+            unit.activation_budget = 1.0;
+            unit.max_activation_budget = 1.0;
+            unit.activation_consumption = 1.0;
+            unit.budget_recovery_rate = recovery_rate;
+
+            let mut states = Vec::new();
+            let mut spikes = Vec::new();
+            for i in 0..5000 {
+                if unit.step(5.0, (0.0, 0.0), 0.01) {
+                    spikes.push(i);
+                }
+                states.push(unit.metabolic_state());
+            }
+            assert_eq!(states[0], MetabolicState::Healthy);
+            assert!(states.contains(&MetabolicState::Strained));
+            let silent_from = states.iter().position(|s| *s == MetabolicState::Exhausted).unwrap();
+            let silent_to = silent_from + states[silent_from..].iter().position(|s| *s != MetabolicState::Exhausted).unwrap();
+            // Silent in between, fires again afterwards
+            assert!(spikes.iter().all(|&i| i < silent_from || i >= silent_to));
+            assert!(spikes.iter().any(|&i| i >= silent_to));
+            silent_to - silent_from
+        };
+
+        let slow = silent_gap(0.5);
+        let fast = silent_gap(1.0);
+        assert!(fast > 0);
+        let ratio = slow as f32 / fast as f32;
+        assert!((ratio - 2.0).abs() < 0.1, "gaps {} vs {}", slow, fast);
+    }
+
+    #[test]
+    fn test_gershgorin_report() {
+        let mut unit = pnu(0, vec![1.0]);
+        unit.auto_inhibition_a = 1.0;
+        unit.lateral_links = vec![
+            LateralLink { target_id: 1, weight: 0.6, plasticity_rate: 0.0, prior_weight: 0.6 },
+            LateralLink { target_id: 2, weight: -0.8, plasticity_rate: 0.0, prior_weight: -0.8 },
+        ];
+
+        let report = unit.enforce_gershgorin_stability();
+        assert!(report.violated);
+        assert!((report.lateral_sum_before - 1.4).abs() < 1e-6);
+        assert!((report.scaling_applied - 0.99 / 1.4).abs() < 1e-6);
+        assert!((report.lateral_sum_after - 0.99).abs() < 1e-5);
+        assert!((unit.lateral_links[1].weight + 0.8 * report.scaling_applied).abs() < 1e-6);
+
+        // Within the bound: nothing to do
+        let report = unit.enforce_gershgorin_stability();
+        assert!(!report.violated);
+        assert_eq!(report.scaling_applied, 1.0);
+        assert_eq!(report.lateral_sum_before, report.lateral_sum_after);
+    }
+
+    #[test]
+    fn test_builder_validation() {
+        let unit = PnuBuilder::new(4)
+            .weights(vec![0.6, 0.8])
+            .label("X")
+            .learning_rate(0.05)
+            .shunting(2.0, 1.5, 0.3)
+            .build()
+            .unwrap();
+        assert_eq!((unit.id, unit.symbolic_label.as_str(), unit.learning_rate_eta), (4, "X", 0.05));
+        assert_eq!((unit.auto_inhibition_a, unit.shunting_b, unit.shunting_c), (2.0, 1.5, 0.3));
+        assert_eq!(unit.activation_budget, 100.0);
+        assert_eq!(PrototypicalNeuralUnit::with_prototype(1, vec![1.0]).weight_vector.len(), 1);
+
+        assert_eq!(PnuBuilder::new(0).build().unwrap_err(), CortexError::EmptyPrototype);
+        assert_eq!(
+            PnuBuilder::new(0).weights(vec![1.0]).learning_rate(-0.1).build().unwrap_err(),
+            CortexError::InvalidParameter { name: "learning_rate_eta", value: -0.1 }
+        );
+        assert_eq!(
+            PnuBuilder::new(0).weights(vec![1.0]).budget(10.0, -1.0, 0.0).build().unwrap_err(),
+            CortexError::InvalidParameter { name: "activation_consumption", value: -1.0 }
+        );
+        assert!(PnuBuilder::new(0).weights(vec![1.0]).fatigue_time_constant(f32::NAN).build().is_err());
+    }
+
+    #[test]
+    fn test_grid_wiring_matches_brute_force() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let mut grid: Vec<PrototypicalNeuralUnit> = (0..1000)
+            .map(|id| pnu(id, (0..3).map(|_| rng.gen_range(-5.0..5.0)).collect()))
+            .collect();
+        let mut brute = grid.clone();
+        let config = TopologyConfig {
+            sigma_excitation: 0.2,
+            sigma_inhibition: 0.5,
+            amp_excitation: 2.0,
+            amp_inhibition: 1.0,
+            connection_cutoff: 0.01,
+            max_neighbors: 12,
+            metric: SignatureMetric::L2,
+        };
+        assert!(config.interaction_radius() < 2.0);
+
+        wire_swarm_topology(&mut grid, &config);
+        wire_swarm_topology_exhaustive(&mut brute, &config);
+        let mut links = 0;
+        for (g, b) in grid.iter().zip(&brute) {
+            let key = |l: &LateralLink| (l.target_id, l.weight);
+            assert_eq!(g.lateral_links.iter().map(key).collect::<Vec<_>>(), b.lateral_links.iter().map(key).collect::<Vec<_>>());
+            links += g.lateral_links.len();
+        }
+        assert!(links > 1000);
+    }
+
+    #[test]
+    fn test_transfer_fn_shapes() {
+        let relu = TransferFn::Relu;
+        assert_eq!((relu.apply(-0.5), relu.apply(0.0), relu.apply(0.7)), (0.0, 0.0, 0.7));
+
+        let sigmoid = TransferFn::Sigmoid { gain: 10.0, midpoint: 0.5 };
+        assert!((sigmoid.apply(0.5) - 0.5).abs() < 1e-6);
+        assert!(sigmoid.apply(0.0) < 0.01 && sigmoid.apply(1.0) > 0.99);
+        assert!(sigmoid.apply(0.4) < sigmoid.apply(0.6));
+
+        let square = TransferFn::Power { exponent: 2.0 };
+        assert_eq!((square.apply(-0.5), square.apply(0.5), square.apply(1.0)), (0.0, 0.25, 1.0));
+        // Faster than linear: ratios are amplified
+        assert!(square.apply(0.8) / square.apply(0.4) > 0.8 / 0.4);
+
+        let binary = TransferFn::Binary { threshold: 0.3 };
+        assert_eq!((binary.apply(0.29), binary.apply(0.3), binary.apply(0.9)), (0.0, 1.0, 1.0));
+    }
+}
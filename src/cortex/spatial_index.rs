@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+/// Below this many points a full scan is cheaper than building a grid.
+pub const MIN_GRID_POINTS: usize = 64;
+/// Above this dimension the 3^D neighboring cells outnumber the points a query
+/// would skip: use a full scan instead.
+pub const MAX_GRID_DIMENSION: usize = 6;
+
+/// Uniform grid over points of a common dimension, for radius queries. With a
+/// cell side equal to the radius, every point within that (Euclidean) radius
+/// of a query lies in one of the 3^D cells around it.
+#[derive(Clone, Debug)]
+pub struct PrototypeGrid {
+    cell: f32,
+    dimension: usize,
+    cells: HashMap<Vec<i32>, Vec<usize>>,
+}
+
+impl PrototypeGrid {
+    /// Grid of cell side `radius` over `points` (positions are their order).
+    /// `None` when a full scan is the better choice (few points, high or mixed
+    /// dimension, non-positive or infinite radius).
+    pub fn build<'a, I>(points: I, radius: f32) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a [f32]>,
+    {
+        if !(radius > 0.0 && radius.is_finite()) {
+            return None;
+        }
+        let points: Vec<&[f32]> = points.into_iter().collect();
+        let dimension = points.first()?.len();
+        if points.len() < MIN_GRID_POINTS
+            || dimension == 0
+            || dimension > MAX_GRID_DIMENSION
+            || points.iter().any(|p| p.len() != dimension)
+        {
+            return None;
+        }
+
+        let mut grid = PrototypeGrid { cell: radius, dimension, cells: HashMap::new() };
+        for (i, p) in points.into_iter().enumerate() {
+            let key = grid.key(p);
+            grid.cells.entry(key).or_default().push(i);
+        }
+        Some(grid)
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Positions of the points in the cells around `point`, in increasing
+    /// order: a superset of the points within the grid radius.
+    pub fn candidates(&self, point: &[f32]) -> Vec<usize> {
+        let center = self.key(point);
+        let mut key = center.clone();
+        let mut found = Vec::new();
+        // Odometer over the offsets {-1, 0, 1}^D
+        let mut offsets = vec![-1i32; self.dimension];
+        loop {
+            for ((k, c), o) in key.iter_mut().zip(&center).zip(&offsets) {
+                *k = c.saturating_add(*o);
+            }
+            if let Some(cell) = self.cells.get(&key) {
+                found.extend_from_slice(cell);
+            }
+
+            let Some(d) = offsets.iter().position(|&o| o < 1) else { break };
+            offsets[d] += 1;
+            offsets[..d].iter_mut().for_each(|o| *o = -1);
+        }
+        found.sort_unstable();
+        found
+    }
+
+    fn key(&self, point: &[f32]) -> Vec<i32> {
+        point.iter().map(|&x| (x / self.cell).floor() as i32).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_cover_radius() {
+        let points: Vec<Vec<f32>> = (0..400)
+            .map(|i| vec![(i as f32 * 0.37).sin() * 5.0, (i as f32 * 0.91).cos() * 5.0])
+            .collect();
+        let grid = PrototypeGrid::build(points.iter().map(Vec::as_slice), 1.0).unwrap();
+
+        for query in &points {
+            let candidates = grid.candidates(query);
+            assert!(candidates.windows(2).all(|w| w[0] < w[1]));
+            for (j, p) in points.iter().enumerate() {
+                let dist = query.iter().zip(p).map(|(a, b)| (a - b).powi(2)).sum::<f32>().sqrt();
+                if dist < 1.0 {
+                    assert!(candidates.contains(&j));
+                }
+            }
+            assert!(candidates.len() < points.len() / 2);
+        }
+
+        // Full scan preferred
+        assert!(PrototypeGrid::build(points[..10].iter().map(Vec::as_slice), 1.0).is_none());
+        assert!(PrototypeGrid::build(points.iter().map(Vec::as_slice), f32::INFINITY).is_none());
+        let wide = vec![vec![0.0; MAX_GRID_DIMENSION + 1]; 100];
+        assert!(PrototypeGrid::build(wide.iter().map(Vec::as_slice), 1.0).is_none());
+    }
+}
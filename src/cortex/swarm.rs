@@ -0,0 +1,1986 @@
+use crate::cortex::bounded_vec::BoundedVec;
+use crate::cortex::error::CortexError;
+use crate::cortex::label::Label;
+use crate::perception::universal_vector::UniversalVector;
+use crate::cortex::prototypical_neural_unit::{
+    calculate_lateral_input, mexican_hat, wire_swarm_topology, Integrator, LateralLink, PnuBuilder,
+    PrototypicalNeuralUnit, SignatureHandle, TemporalCorrelation, TopologyConfig, TransferFn, BASE_PLASTICITY,
+    DEFAULT_EPISODE_CAPACITY,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Magic bytes opening a swarm file.
+const SWARM_MAGIC: &[u8; 4] = b"ASWM";
+/// Version of the swarm file layout, bumped whenever a serialized type changes.
+pub const SWARM_FORMAT_VERSION: u32 = 12;
+
+/// Swarm-wide parameters.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwarmConfig {
+    /// Smoothing factor of temporal correlations: a co-activation moves the
+    /// strength this fraction of the way to 1.
+    pub correlation_smoothing: f32,
+    /// Time constant of the exponential decay of correlations between co-activations.
+    pub correlation_time_constant: f64,
+    /// Correlations weaker than this are dropped.
+    pub correlation_floor: f32,
+    /// Correlation entries kept per unit (strongest first).
+    pub max_correlations: usize,
+    /// Hebbian learning of lateral links (`update_lateral_plasticity`).
+    pub plasticity_enabled: bool,
+    /// Weight decay λ of the Hebbian rule.
+    pub plasticity_decay: f32,
+    /// `decay_links` spares the links whose endpoints both spiked within this
+    /// window (of each other and of the swarm clock).
+    pub coactivity_window: f64,
+    /// Minimum resonance for `present_input` to match (and train) an existing
+    /// unit, raised per unit by its `vigilance_contribution`.
+    pub vigilance: f32,
+    /// Inputs failing the vigilance spawn a new unit; otherwise they are rejected.
+    pub neurogenesis: bool,
+    /// Output signal of the units, for lateral input, plasticity and readouts.
+    pub transfer: TransferFn,
+    /// Integration scheme of `step` (lateral inputs frozen over a step).
+    pub integrator: Integrator,
+    /// κ: learning-rate increase per unit of surprise (η_eff = η·(1 + κ·surprise)).
+    pub surprise_gain: f32,
+    /// Episodes remembered per unit (applied to the units on `push`).
+    pub episode_capacity: usize,
+    /// A match records its input as an episode only if its surprise reaches
+    /// `episode_surprise_threshold` (a spawn always records).
+    pub record_on_surprise_only: bool,
+    pub episode_surprise_threshold: f32,
+    /// Spikes kept by the swarm's `SpikeLog` (oldest evicted first).
+    pub spike_log_capacity: usize,
+}
+
+impl Default for SwarmConfig {
+    fn default() -> Self {
+        SwarmConfig {
+            correlation_smoothing: 0.3,
+            correlation_time_constant: 10.0,
+            correlation_floor: 0.01,
+            max_correlations: 16,
+            plasticity_enabled: true,
+            plasticity_decay: 0.1,
+            coactivity_window: 1.0,
+            vigilance: 0.75,
+            neurogenesis: true,
+            transfer: TransferFn::Relu,
+            integrator: Integrator::Euler,
+            surprise_gain: 1.0,
+            episode_capacity: DEFAULT_EPISODE_CAPACITY,
+            record_on_surprise_only: false,
+            episode_surprise_threshold: 0.2,
+            spike_log_capacity: 4096,
+        }
+    }
+}
+
+/// External drive for one `Swarm::step`.
+#[derive(Clone, Debug, Default)]
+pub enum SwarmInput {
+    #[default]
+    None,
+    /// Same excitation for every unit.
+    Uniform(f32),
+    /// Excitation per unit id; absent units get 0.
+    ById(HashMap<usize, f32>),
+    /// A point in prototype space: each unit is excited by its cosine similarity
+    /// to it (negative similarities and length mismatches give 0).
+    Pattern(Box<[f32]>),
+}
+
+impl SwarmInput {
+    fn excitation(&self, pnu: &PrototypicalNeuralUnit) -> f32 {
+        match self {
+            SwarmInput::None => 0.0,
+            SwarmInput::Uniform(e) => *e,
+            SwarmInput::ById(map) => map.get(&pnu.id).copied().unwrap_or(0.0),
+            SwarmInput::Pattern(x) => resonance(pnu, x).max(0.0),
+        }
+    }
+}
+
+/// Cosine similarity between a unit's prototype and `input` (0 for a length
+/// mismatch or a zero vector).
+fn resonance(pnu: &PrototypicalNeuralUnit, input: &[f32]) -> f32 {
+    if pnu.weight_vector.len() != input.len() {
+        return 0.0;
+    }
+    let dot: f32 = pnu.weight_vector.iter().zip(input).map(|(w, x)| w * x).sum();
+    let norm = pnu.weight_vector.iter().map(|w| w * w).sum::<f32>().sqrt()
+        * input.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 { dot / norm } else { 0.0 }
+}
+
+/// What `Swarm::present_input` did with an input. `surprise` is 1 minus the
+/// best resonance, in [0, 1] (1 for an empty swarm).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PresentOutcome {
+    /// An existing unit resonated above the vigilance.
+    Matched { id: usize, resonance: f32, surprise: f32 },
+    /// No unit did: a new one was created around the input.
+    Spawned { id: usize, surprise: f32 },
+    /// No unit did and neurogenesis is off: the input was ignored.
+    Rejected { surprise: f32 },
+}
+
+impl PresentOutcome {
+    pub fn surprise(&self) -> f32 {
+        match *self {
+            PresentOutcome::Matched { surprise, .. }
+            | PresentOutcome::Spawned { surprise, .. }
+            | PresentOutcome::Rejected { surprise } => surprise,
+        }
+    }
+}
+
+/// Outcome of `Swarm::step_until_settled`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SettleResult {
+    pub steps_taken: usize,
+    /// The largest |dx/dt| fell below epsilon within `max_steps`.
+    pub converged: bool,
+    /// Largest |dx/dt| after the last step.
+    pub max_derivative: f32,
+}
+
+/// Counters of `Swarm::present_input` outcomes and of pruning.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwarmStats {
+    pub presented: usize,
+    pub matched: usize,
+    pub spawned: usize,
+    pub rejected: usize,
+    /// Presentations whose best unit resonated below its vigilance (then
+    /// spawned or rejected).
+    pub vigilance_failures: usize,
+    /// Units removed (or merged away) by `prune`.
+    pub pruned: usize,
+    /// Units whose links were rescaled to their Gershgorin bound by plasticity
+    /// or incremental wiring.
+    pub stability_violations: usize,
+}
+
+/// Snapshot of the swarm state for the strategist (`Swarm::metrics`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SwarmMetrics {
+    pub n_units: usize,
+    pub mean_activation: f32,
+    /// Fraction of units with a positive activation.
+    pub active_fraction: f32,
+    /// Shannon entropy (nats) of the rectified activations taken as a
+    /// distribution: 0 when a single unit is active, ln(n) when n are equally so.
+    pub activation_entropy: f32,
+    pub mean_budget: f32,
+    pub exhausted_count: usize,
+    pub total_links: usize,
+    pub mean_abs_link_weight: f32,
+    /// Units spawned since the last `reset_metrics`.
+    pub spawned_since_last: usize,
+    /// Units pruned since the last `reset_metrics`.
+    pub pruned_since_last: usize,
+    /// Gershgorin rescalings since the last `reset_metrics`.
+    pub stability_violations_since_last: usize,
+}
+
+/// Outcome of `Swarm::global_stability_check`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlobalStability {
+    /// Power-iteration estimate of the spectral radius of the lateral weight
+    /// matrix W (W_ij = weight of the link from unit i toward unit j).
+    pub spectral_radius: f32,
+    /// Smallest auto-inhibition A of the swarm (0 when empty).
+    pub min_auto_inhibition: f32,
+    /// Power iterations run.
+    pub iterations: usize,
+    /// The estimate settled within `POWER_ITERATIONS` iterations.
+    pub converged: bool,
+}
+
+impl GlobalStability {
+    /// Leak dominates every lateral mode: ρ(W) < min A.
+    pub fn is_stable(&self) -> bool {
+        self.spectral_radius < self.min_auto_inhibition
+    }
+}
+
+/// Iteration cap of `Swarm::global_stability_check`.
+pub const POWER_ITERATIONS: usize = 1000;
+
+/// An active unit, as read out by `Swarm::winners`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Winner {
+    pub id: usize,
+    pub label: Label,
+    pub activation: f32,
+    /// Output signal f(activation) (`SwarmConfig::transfer`).
+    pub output: f32,
+    /// The unit's `truth_value`, copied as is: it belongs to the logic
+    /// interface, not to the activation dynamics, so `transfer` does not apply.
+    pub truth_value: f32,
+}
+
+/// Upward threshold crossing recorded by `Swarm::step`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpikeEvent {
+    pub unit_id: usize,
+    /// Swarm clock at the end of the step.
+    pub time: f64,
+    pub activation: f32,
+}
+
+/// Most recent spikes of a swarm, oldest first.
+pub type SpikeLog = BoundedVec<SpikeEvent>;
+
+/// Criteria of `Swarm::prune`; `None` disables a criterion.
+#[derive(Clone, Debug, Default)]
+pub struct PrunePolicy {
+    /// Removes units silent for longer than this: no spike (or, for units
+    /// that never spiked, no birth) within the last `stale_after` seconds.
+    pub stale_after: Option<f64>,
+    /// Removes units exhausted for longer than this.
+    pub exhausted_for: Option<f64>,
+    /// Merges units whose prototypes are closer than this (semantic distance).
+    pub merge_distance: Option<f32>,
+}
+
+/// Owns the units of a swarm with the state shared between them: global time
+/// and the id -> position map used to resolve lateral links.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Swarm {
+    units: Vec<PrototypicalNeuralUnit>,
+    time: f64,
+    // Rebuilt from `units` on load
+    #[serde(skip)]
+    index: HashMap<usize, usize>,
+    config: SwarmConfig,
+    // Time of the previous `update_temporal_correlations` pass
+    correlations_updated: Option<f64>,
+    // Last `wire` configuration, reused to wire spawned units
+    topology: Option<TopologyConfig>,
+    // Smallest id above every id ever pushed
+    next_id: usize,
+    // Scene id attached to the episodes of `present_vector`
+    scene_context_id: u64,
+    // Session counters, not saved
+    #[serde(skip)]
+    stats: SwarmStats,
+    // `stats` at the last `reset_metrics`
+    #[serde(skip)]
+    metrics_mark: SwarmStats,
+    // Session history, not saved (sized from `config` on load)
+    #[serde(skip)]
+    spikes: SpikeLog,
+}
+
+impl Default for Swarm {
+    fn default() -> Self {
+        Swarm::new(SwarmConfig::default())
+    }
+}
+
+impl Swarm {
+    pub fn new(config: SwarmConfig) -> Self {
+        Swarm {
+            units: Vec::new(),
+            time: 0.0,
+            index: HashMap::new(),
+            correlations_updated: None,
+            topology: None,
+            next_id: 0,
+            scene_context_id: 0,
+            stats: SwarmStats::default(),
+            metrics_mark: SwarmStats::default(),
+            spikes: SpikeLog::new(config.spike_log_capacity),
+            config,
+        }
+    }
+
+    /// Adds a unit, synchronized on the swarm clock and holding
+    /// `episode_capacity` episodes. Ids must be unique.
+    pub fn push(&mut self, mut unit: PrototypicalNeuralUnit) -> Result<(), CortexError> {
+        if self.index.contains_key(&unit.id) {
+            return Err(CortexError::DuplicateId(unit.id));
+        }
+        unit.state.time = self.time;
+        unit.episodes.set_capacity(self.config.episode_capacity);
+        self.next_id = self.next_id.max(unit.id + 1);
+        self.index.insert(unit.id, self.units.len());
+        self.units.push(unit);
+        Ok(())
+    }
+
+    pub fn get(&self, id: usize) -> Option<&PrototypicalNeuralUnit> {
+        self.index.get(&id).map(|&i| &self.units[i])
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut PrototypicalNeuralUnit> {
+        self.index.get(&id).map(|&i| &mut self.units[i])
+    }
+
+    pub fn label_of(&self, id: usize) -> Option<&str> {
+        self.get(id).map(|pnu| pnu.symbolic_label.as_str())
+    }
+
+    /// Ids of the units carrying `label`, in insertion order.
+    pub fn find_by_label(&self, label: &str) -> Vec<usize> {
+        self.units.iter().filter(|pnu| pnu.symbolic_label == label).map(|pnu| pnu.id).collect()
+    }
+
+    pub fn units(&self) -> &[PrototypicalNeuralUnit] {
+        &self.units
+    }
+
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn config(&self) -> &SwarmConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> &SwarmStats {
+        &self.stats
+    }
+
+    /// Scene id attached to the episodes of subsequent `present_vector` calls.
+    pub fn set_scene(&mut self, scene_context_id: u64) {
+        self.scene_context_id = scene_context_id;
+    }
+
+    /// Moves on to a new scene; returns its id.
+    pub fn next_scene(&mut self) -> u64 {
+        self.scene_context_id += 1;
+        self.scene_context_id
+    }
+
+    pub fn scene(&self) -> u64 {
+        self.scene_context_id
+    }
+
+    /// Mexican-hat wiring of every unit (`wire_swarm_topology`). The
+    /// configuration is kept to wire the units spawned by `present_input`
+    /// (see `wire_incremental`).
+    pub fn wire(&mut self, config: &TopologyConfig) {
+        wire_swarm_topology(&mut self.units, config);
+        self.topology = Some(config.clone());
+    }
+
+    /// Presents an input to the swarm: the unit whose prototype resonates best
+    /// (cosine similarity) matches it if the resonance reaches the vigilance
+    /// plus the unit's `vigilance_contribution` (ART-style gate: a weak match
+    /// never drags a prototype). The matched unit takes the resonance as
+    /// activation, registers the surprise (its neighbors too, scaled by the link
+    /// magnitude), learns the normalized input (`learn_oja_surprised`,
+    /// κ = `surprise_gain`) and records it as an episode (see
+    /// `record_on_surprise_only`).
+    /// Otherwise, with `neurogenesis`, the swarm grows a unit whose prototype is
+    /// the normalized input, born at `now` with the input as its first episode,
+    /// and wired to the others if the swarm was wired; without, the input is
+    /// rejected. An empty input, an input whose length differs from the
+    /// prototypes of a non-empty swarm, or one with a NaN or infinite term is
+    /// refused before it reaches any unit.
+    pub fn present_input(&mut self, input: &[f32], now: f64) -> Result<PresentOutcome, CortexError> {
+        let episode = SignatureHandle { signature_segment: input.to_vec(), timestamp: now, ..SignatureHandle::default() };
+        self.present_with_episode(input, now, episode)
+    }
+
+    pub(crate) fn present_with_episode(&mut self, input: &[f32], now: f64, episode: SignatureHandle) -> Result<PresentOutcome, CortexError> {
+        if input.is_empty() {
+            return Err(CortexError::EmptyPrototype);
+        }
+        if let Some(expected) = self.units.first().map(|pnu| pnu.weight_vector.len()).filter(|&n| n != input.len()) {
+            return Err(CortexError::DimensionMismatch { expected, got: input.len() });
+        }
+        if let Some(index) = input.iter().position(|x| !x.is_finite()) {
+            return Err(CortexError::NonFiniteInput { index });
+        }
+        let norm = input.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let scale = if norm > 0.0 { 1.0 / norm } else { 1.0 };
+        let normalized: Vec<f32> = input.iter().map(|x| x * scale).collect();
+
+        let best = self.units.iter()
+            .filter(|pnu| pnu.weight_vector.len() == input.len())
+            .map(|pnu| (pnu, resonance(pnu, input)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let surprise = best.map_or(1.0, |(_, r)| (1.0 - r).clamp(0.0, 1.0));
+        self.stats.presented += 1;
+        match best {
+            Some((pnu, resonance)) if resonance >= self.config.vigilance + pnu.vigilance_contribution => {
+                let id = pnu.id;
+                self.learn_presented(id, resonance, surprise, &normalized, now);
+                if !self.config.record_on_surprise_only || surprise >= self.config.episode_surprise_threshold {
+                    self.units[self.index[&id]].record_episode(episode);
+                }
+                self.stats.matched += 1;
+                return Ok(PresentOutcome::Matched { id, resonance, surprise });
+            }
+            Some(_) => self.stats.vigilance_failures += 1,
+            None => {}
+        }
+        if !self.config.neurogenesis {
+            self.stats.rejected += 1;
+            return Ok(PresentOutcome::Rejected { surprise });
+        }
+
+        let id = self.next_id;
+        let mut unit = PnuBuilder::new(id)
+            .weights(normalized)
+            .birth(now)
+            .build()
+            .expect("input is not empty");
+        unit.record_episode(episode);
+        self.push(unit).expect("next_id is unused");
+        if let Some(topology) = self.topology.clone() {
+            self.wire_incremental(&[id], &topology);
+        }
+        self.stats.spawned += 1;
+        Ok(PresentOutcome::Spawned { id, surprise })
+    }
+
+    /// `present_input` for a perceived vector: its signature is flattened
+    /// (canonical order) and projected onto the unit sphere, and the episode
+    /// recorded by the matched or spawned unit is the raw signature, with the vector's
+    /// timestamp and the current scene. The flattened length must be the
+    /// prototype length of the swarm.
+    pub fn present_vector(&mut self, v: &UniversalVector, now: f64) -> Result<PresentOutcome, CortexError> {
+        let flat = v.signature.flatten();
+        let norm = flat.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let scale = if norm > 0.0 { 1.0 / norm } else { 1.0 };
+        let input: Vec<f32> = flat.iter().map(|&x| (x * scale) as f32).collect();
+
+        let episode = SignatureHandle::new(&v.signature, v.metadata.timestamp, self.scene_context_id);
+        self.present_with_episode(&input, now, episode)
+    }
+
+    fn learn_presented(&mut self, id: usize, resonance: f32, surprise: f32, input: &[f32], now: f64) {
+        let kappa = self.config.surprise_gain;
+        let unit = &mut self.units[self.index[&id]];
+        unit.state.activation = resonance;
+        unit.register_surprise(surprise, now);
+        unit.learn_oja_surprised(input, surprise, kappa).expect("matched units have the input length");
+
+        let neighbors: Vec<(usize, f32)> = unit.lateral_links.iter()
+            .filter_map(|link| self.index.get(&link.target_id).map(|&j| (j, link.weight.abs())))
+            .collect();
+        for (j, weight) in neighbors {
+            self.units[j].register_surprise(surprise * weight, now);
+        }
+    }
+
+    /// Wires the units `new_ids` (already pushed) without rewiring the rest:
+    /// Mexican-hat weights are only computed between a new unit and every other
+    /// unit. New units keep their strongest `max_neighbors` links like in `wire`;
+    /// an existing unit gains a link toward a new one if it has room or if the
+    /// new link beats its weakest one, which is then evicted. Gershgorin bounds
+    /// are only re-enforced on touched units. Unknown ids are ignored.
+    pub fn wire_incremental(&mut self, new_ids: &[usize], config: &TopologyConfig) {
+        let fresh: Vec<usize> = new_ids.iter().filter_map(|id| self.index.get(id).copied()).collect();
+        let mut touched = Vec::new();
+
+        for &i in &fresh {
+            let id = self.units[i].id;
+            let candidates: Vec<(usize, LateralLink)> = self.units.iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(j, other)| {
+                    let weight = mexican_hat(self.units[i].distance_with(other, config.metric), config);
+                    let link = LateralLink { target_id: other.id, weight, plasticity_rate: BASE_PLASTICITY, prior_weight: weight };
+                    (j, link)
+                })
+                .filter(|(_, link)| link.weight.abs() > config.connection_cutoff)
+                .collect();
+
+            // Reverse links on the existing units (new ones get theirs below).
+            // Existing links may have been rescaled (Gershgorin, plasticity): links
+            // are ranked on their wiring weight, and the new one gets the current
+            // gain of the unit.
+            for (j, link) in &candidates {
+                if fresh.contains(j) {
+                    continue;
+                }
+                let unit = &self.units[*j];
+                let priors: Vec<f32> = unit.lateral_links.iter()
+                    .map(|l| self.wiring_weight(unit, l.target_id, config).abs())
+                    .collect();
+                let prior_sum: f32 = priors.iter().sum();
+                let current_sum: f32 = unit.lateral_links.iter().map(|l| l.weight.abs()).sum();
+                let gain = if prior_sum > 0.0 { current_sum / prior_sum } else { 1.0 };
+                let slot = if priors.len() < config.max_neighbors {
+                    priors.len()
+                } else {
+                    match priors.iter().enumerate().min_by(|a, b| a.1.total_cmp(b.1)) {
+                        Some((k, &weakest)) if weakest < link.weight.abs() => k,
+                        _ => continue,
+                    }
+                };
+
+                let weight = link.weight * gain;
+                let reverse = LateralLink { target_id: id, weight, prior_weight: weight, ..link.clone() };
+                let links = &mut self.units[*j].lateral_links;
+                if slot == links.len() {
+                    links.push(reverse);
+                } else {
+                    links[slot] = reverse;
+                }
+                touched.push(*j);
+            }
+
+            let mut links: Vec<LateralLink> = candidates.into_iter().map(|(_, link)| link).collect();
+            links.sort_by(|a, b| b.weight.abs().total_cmp(&a.weight.abs()));
+            links.truncate(config.max_neighbors);
+            self.units[i].lateral_links = links;
+            touched.push(i);
+        }
+
+        touched.sort_unstable();
+        touched.dedup();
+        for i in touched {
+            let report = self.units[i].enforce_gershgorin_stability();
+            if report.violated {
+                self.stats.stability_violations += 1;
+            }
+            // The rescaling is part of the topology: priors follow it
+            for link in &mut self.units[i].lateral_links {
+                link.prior_weight *= report.scaling_applied;
+            }
+        }
+        self.topology = Some(config.clone());
+    }
+
+    // Mexican-hat weight `wire` gives to a link of `unit` (0 for an unknown target)
+    fn wiring_weight(&self, unit: &PrototypicalNeuralUnit, target_id: usize, config: &TopologyConfig) -> f32 {
+        self.index.get(&target_id)
+            .map_or(0.0, |&t| mexican_hat(unit.distance_with(&self.units[t], config.metric), config))
+    }
+
+    /// First lateral link whose target is not in the swarm. Such links are
+    /// ignored by the dynamics and plasticity.
+    pub fn check_links(&self) -> Result<(), CortexError> {
+        for pnu in &self.units {
+            if let Some(link) = pnu.lateral_links.iter().find(|l| !self.index.contains_key(&l.target_id)) {
+                return Err(CortexError::DanglingLink { source: pnu.id, target: link.target_id });
+            }
+        }
+        Ok(())
+    }
+
+    /// (excitatory, inhibitory) lateral sums received by `pnu`.
+    pub fn lateral_input(&self, pnu: &PrototypicalNeuralUnit) -> (f32, f32) {
+        calculate_lateral_input(pnu, &self.units, &self.index, self.config.transfer)
+    }
+
+    /// Advances every unit by `dt` (`SwarmConfig::integrator`). Lateral inputs
+    /// are all computed from the activations before the step (two-buffer
+    /// scheme), so the update order doesn't matter. Returns the ids of the
+    /// units that spiked, which are also appended to the `SpikeLog`.
+    pub fn step(&mut self, dt: f32, inputs: &SwarmInput) -> Vec<usize> {
+        let lateral: Vec<(f32, f32)> = self.units.iter().map(|pnu| self.lateral_input(pnu)).collect();
+        let integrator = self.config.integrator;
+        let spikes = &mut self.spikes;
+
+        let spiked = self.units.iter_mut()
+            .zip(lateral)
+            .filter_map(|(pnu, lateral)| {
+                let excitation = inputs.excitation(pnu);
+                if !pnu.step_with(excitation, lateral, dt, integrator) {
+                    return None;
+                }
+                let activation = pnu.state.activation;
+                spikes.push(SpikeEvent { unit_id: pnu.id, time: pnu.state.time, activation });
+                Some(pnu.id)
+            })
+            .collect();
+        self.time += dt as f64;
+        spiked
+    }
+
+    /// Steps under `inputs` until every unit has settled (largest |dx/dt| below
+    /// `epsilon`) or `max_steps` steps were taken.
+    pub fn step_until_settled(&mut self, dt: f32, inputs: &SwarmInput, max_steps: usize, epsilon: f32) -> SettleResult {
+        let mut steps_taken = 0;
+        let mut max_derivative = self.max_derivative();
+        while steps_taken < max_steps {
+            self.step(dt, inputs);
+            steps_taken += 1;
+            max_derivative = self.max_derivative();
+            if max_derivative < epsilon {
+                return SettleResult { steps_taken, converged: true, max_derivative };
+            }
+        }
+        SettleResult { steps_taken, converged: false, max_derivative }
+    }
+
+    /// Largest |dx/dt| of the last step over all units (0 for an empty swarm).
+    pub fn max_derivative(&self) -> f32 {
+        self.units.iter().map(|pnu| pnu.state.derivative.abs()).fold(0.0, f32::max)
+    }
+
+    /// Co-activation pass, to be called regularly (e.g. after each `step`).
+    /// Every pair of units whose last spikes are within `window` of each other,
+    /// one of them new since the previous pass, strengthens its (symmetric)
+    /// `TemporalCorrelation`; all entries decay exponentially with time. See
+    /// `SwarmConfig` for smoothing, decay, floor and size limit.
+    pub fn update_temporal_correlations(&mut self, window: f64, now: f64) {
+        let previous = self.correlations_updated.replace(now);
+        let elapsed = previous.map_or(0.0, |t| (now - t).max(0.0));
+        let decay = (-elapsed / self.config.correlation_time_constant).exp() as f32;
+        let alpha = self.config.correlation_smoothing;
+
+        let spikes: Vec<(usize, f64)> = self.units.iter()
+            .filter_map(|pnu| pnu.last_spike_time.map(|t| (pnu.id, t)))
+            .filter(|&(_, t)| now - t <= window + elapsed)
+            .collect();
+        let is_new = |t: f64| previous.is_none_or(|p| t > p);
+        let mut pairs = Vec::new();
+        for (k, &(a, ta)) in spikes.iter().enumerate() {
+            for &(b, tb) in &spikes[k + 1..] {
+                if (ta - tb).abs() <= window && (is_new(ta) || is_new(tb)) {
+                    pairs.push((a, b));
+                }
+            }
+        }
+
+        for pnu in &mut self.units {
+            for entry in &mut pnu.temporal_correlations {
+                entry.correlation_strength *= decay;
+            }
+            let partners = pairs.iter().filter_map(|&(a, b)| match pnu.id {
+                id if id == a => Some(b),
+                id if id == b => Some(a),
+                _ => None,
+            });
+            for other in partners {
+                match pnu.temporal_correlations.iter_mut().find(|c| c.pnu_id == other) {
+                    Some(entry) => {
+                        entry.correlation_strength += alpha * (1.0 - entry.correlation_strength);
+                        entry.last_coactivation_time = now;
+                    }
+                    None => pnu.temporal_correlations.push(TemporalCorrelation {
+                        pnu_id: other,
+                        correlation_strength: alpha,
+                        last_coactivation_time: now,
+                    }),
+                }
+            }
+
+            pnu.temporal_correlations.retain(|c| c.correlation_strength >= self.config.correlation_floor);
+            pnu.temporal_correlations.sort_by(|a, b| b.correlation_strength.total_cmp(&a.correlation_strength));
+            pnu.temporal_correlations.truncate(self.config.max_correlations);
+        }
+    }
+
+    /// Temporal correlation of `a` toward `b` (0 if none).
+    pub fn correlation(&self, a: usize, b: usize) -> f32 {
+        self.get(a)
+            .and_then(|pnu| pnu.temporal_correlations.iter().find(|c| c.pnu_id == b))
+            .map_or(0.0, |c| c.correlation_strength)
+    }
+
+    /// Hebbian rule on every lateral link, to be called after `step`:
+    /// |w| += plasticity_rate * (pre * post - λ|w|) * dt, with pre and post the
+    /// outputs (`SwarmConfig::transfer`) of the target and of the owner. The rule acts on the
+    /// magnitude, so a link never changes sign (it stops at 0). Each unit is then
+    /// brought back within its Gershgorin bound. No-op when plasticity is frozen.
+    pub fn update_lateral_plasticity(&mut self, dt: f32) {
+        if !self.config.plasticity_enabled {
+            return;
+        }
+        let signals: Vec<f32> = self.units.iter().map(|pnu| self.config.transfer.apply(pnu.state.activation)).collect();
+        let lambda = self.config.plasticity_decay;
+
+        for (pnu, &post) in self.units.iter_mut().zip(&signals) {
+            for link in &mut pnu.lateral_links {
+                let Some(&target) = self.index.get(&link.target_id) else { continue };
+                let pre = signals[target];
+                let magnitude = link.weight.abs();
+                let updated = (magnitude + link.plasticity_rate * (pre * post - lambda * magnitude) * dt).max(0.0);
+                link.weight = updated.copysign(link.weight);
+            }
+            if pnu.enforce_gershgorin_stability().violated {
+                self.stats.stability_violations += 1;
+            }
+        }
+    }
+
+    /// Relaxes idle links toward their wiring-time weight:
+    /// w += (prior - w) * dt/τ (the step is capped at the prior). A link is idle
+    /// unless both its endpoints spiked within `SwarmConfig::coactivity_window`
+    /// of each other and of the swarm clock. A link that changed sign crosses
+    /// zero back toward its prior. Co-active links keep their weight, so a
+    /// relaxing neighbour can push the row past the Gershgorin bound: units
+    /// with a decayed link are rescaled afterwards, as after plasticity.
+    pub fn decay_links(&mut self, dt: f32, tau: f32) {
+        let rate = (dt / tau).clamp(0.0, 1.0);
+        let window = self.config.coactivity_window;
+        let now = self.time;
+        let recent: Vec<Option<f64>> = self.units.iter()
+            .map(|pnu| pnu.last_spike_time.filter(|&t| now - t <= window))
+            .collect();
+
+        for (i, pnu) in self.units.iter_mut().enumerate() {
+            let mut touched = false;
+            for link in &mut pnu.lateral_links {
+                let target = self.index.get(&link.target_id).and_then(|&j| recent[j]);
+                let coactive = matches!((recent[i], target), (Some(a), Some(b)) if (a - b).abs() <= window);
+                if !coactive {
+                    link.weight += (link.prior_weight - link.weight) * rate;
+                    touched = true;
+                }
+            }
+            if touched && pnu.enforce_gershgorin_stability().violated {
+                self.stats.stability_violations += 1;
+            }
+        }
+    }
+
+    /// Spectral radius of the whole lateral weight matrix, by power iteration.
+    /// Unlike the per-unit Gershgorin bound this accounts for loops through
+    /// the network; a complex dominant pair may keep it from converging.
+    pub fn global_stability_check(&self) -> GlobalStability {
+        let rows: Vec<Vec<(usize, f32)>> = self.units.iter()
+            .map(|pnu| {
+                pnu.lateral_links.iter()
+                    .filter_map(|link| self.index.get(&link.target_id).map(|&j| (j, link.weight)))
+                    .collect()
+            })
+            .collect();
+        let min_auto_inhibition = self.units.iter()
+            .map(|pnu| pnu.auto_inhibition_a)
+            .reduce(f32::min)
+            .unwrap_or(0.0);
+
+        let n = rows.len();
+        // Uneven start, unlikely to be orthogonal to the dominant eigenvector
+        let mut v: Vec<f32> = (0..n).map(|i| 1.0 + (i as f32 * 0.61).sin() * 0.5).collect();
+        let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        v.iter_mut().for_each(|x| *x /= norm);
+
+        let mut estimate = 0.0;
+        let mut result = GlobalStability { spectral_radius: 0.0, min_auto_inhibition, iterations: 0, converged: n == 0 };
+        for iteration in 1..=POWER_ITERATIONS {
+            let next: Vec<f32> = rows.iter()
+                .map(|row| row.iter().map(|&(j, w)| w * v[j]).sum())
+                .collect();
+            let norm = next.iter().map(|x| x * x).sum::<f32>().sqrt();
+            result.iterations = iteration;
+            if norm == 0.0 {
+                // Nilpotent (e.g. no links): every eigenvalue is 0
+                estimate = 0.0;
+                result.converged = true;
+                break;
+            }
+            let settled = (norm - estimate).abs() <= 1e-6 * norm;
+            estimate = norm;
+            v = next.into_iter().map(|x| x / norm).collect();
+            if settled {
+                result.converged = true;
+                break;
+            }
+        }
+        result.spectral_radius = estimate;
+        result
+    }
+
+    /// Removes stale and long-exhausted units, then merges redundant ones: the
+    /// first unit of a close pair (same prototype length) keeps the
+    /// activation-weighted average of both prototypes (plain average if neither
+    /// is active), projected back onto the unit sphere, and absorbs the other.
+    /// Links toward an absorbed unit are redirected to its survivor (a link
+    /// that would become a self-link or duplicate one is dropped); other links
+    /// and correlations toward removed units are dropped everywhere.
+    /// Returns the removed ids. Times are measured against the swarm clock.
+    pub fn prune(&mut self, policy: PrunePolicy) -> Vec<usize> {
+        let now = self.time;
+        let mut removed: Vec<usize> = self.units.iter()
+            .filter(|pnu| {
+                let stale = policy.stale_after
+                    .is_some_and(|limit| now - pnu.last_spike_time.map_or(pnu.birth_timestamp, |t| t.max(pnu.birth_timestamp)) > limit);
+                let exhausted = policy.exhausted_for
+                    .is_some_and(|limit| pnu.state.exhausted_since.is_some_and(|t| now - t > limit));
+                stale || exhausted
+            })
+            .map(|pnu| pnu.id)
+            .collect();
+
+        // absorbed id -> survivor id
+        let mut absorbed: HashMap<usize, usize> = HashMap::new();
+        if let Some(merge_distance) = policy.merge_distance {
+            for i in 0..self.units.len() {
+                if removed.contains(&self.units[i].id) {
+                    continue;
+                }
+                for j in i + 1..self.units.len() {
+                    let (kept, other) = (&self.units[i], &self.units[j]);
+                    if removed.contains(&other.id)
+                        || kept.weight_vector.len() != other.weight_vector.len()
+                        || kept.semantic_distance(other) >= merge_distance
+                    {
+                        continue;
+                    }
+                    let (a, b) = (kept.state.activation.max(0.0), other.state.activation.max(0.0));
+                    let (a, b) = if a + b > 0.0 { (a / (a + b), b / (a + b)) } else { (0.5, 0.5) };
+                    let mut blended: Vec<f32> = kept.weight_vector.iter().zip(other.weight_vector.iter())
+                        .map(|(x, y)| a * x + b * y)
+                        .collect();
+                    let norm = blended.iter().map(|x| x * x).sum::<f32>().sqrt();
+                    if norm > 0.0 {
+                        blended.iter_mut().for_each(|x| *x /= norm);
+                    }
+                    absorbed.insert(other.id, self.units[i].id);
+                    removed.push(other.id);
+                    self.units[i].weight_vector = blended.into();
+                }
+            }
+        }
+
+        if !removed.is_empty() {
+            self.units.retain(|pnu| !removed.contains(&pnu.id));
+            for pnu in &mut self.units {
+                let mut targets = HashSet::new();
+                pnu.lateral_links.retain_mut(|link| {
+                    if let Some(&survivor) = absorbed.get(&link.target_id) {
+                        link.target_id = survivor;
+                    }
+                    link.target_id != pnu.id && !removed.contains(&link.target_id) && targets.insert(link.target_id)
+                });
+                pnu.temporal_correlations.retain(|c| !removed.contains(&c.pnu_id));
+            }
+            self.index = self.units.iter().enumerate().map(|(i, pnu)| (pnu.id, i)).collect();
+        }
+        self.stats.pruned += removed.len();
+        removed
+    }
+
+    /// (id, activation) of every unit, in insertion order.
+    pub fn activations(&self) -> Vec<(usize, f32)> {
+        self.units.iter().map(|pnu| (pnu.id, pnu.state.activation)).collect()
+    }
+
+    /// Aggregate state of the swarm; all means are 0 for an empty swarm.
+    pub fn metrics(&self) -> SwarmMetrics {
+        let n = self.units.len();
+        let mean = |total: f32, count: usize| if count > 0 { total / count as f32 } else { 0.0 };
+        let rectified: Vec<f32> = self.units.iter().map(|pnu| pnu.state.activation.max(0.0)).collect();
+        let total_rectified: f32 = rectified.iter().sum();
+        let activation_entropy = if total_rectified > 0.0 {
+            rectified.iter()
+                .filter(|&&a| a > 0.0)
+                .map(|&a| {
+                    let p = a / total_rectified;
+                    -p * p.ln()
+                })
+                .sum()
+        } else {
+            0.0
+        };
+        let total_links = self.units.iter().map(|pnu| pnu.lateral_links.len()).sum();
+        let total_link_weight: f32 = self.units.iter()
+            .flat_map(|pnu| &pnu.lateral_links)
+            .map(|link| link.weight.abs())
+            .sum();
+
+        SwarmMetrics {
+            n_units: n,
+            mean_activation: mean(self.units.iter().map(|pnu| pnu.state.activation).sum(), n),
+            active_fraction: mean(rectified.iter().filter(|&&a| a > 0.0).count() as f32, n),
+            activation_entropy,
+            mean_budget: mean(self.units.iter().map(|pnu| pnu.activation_budget).sum(), n),
+            exhausted_count: self.units.iter().filter(|pnu| pnu.is_exhausted()).count(),
+            total_links,
+            mean_abs_link_weight: mean(total_link_weight, total_links),
+            spawned_since_last: self.stats.spawned - self.metrics_mark.spawned,
+            pruned_since_last: self.stats.pruned - self.metrics_mark.pruned,
+            stability_violations_since_last: self.stats.stability_violations - self.metrics_mark.stability_violations,
+        }
+    }
+
+    /// Restarts the `*_since_last` counters of `metrics`.
+    pub fn reset_metrics(&mut self) {
+        self.metrics_mark = self.stats.clone();
+    }
+
+    /// The (at most) `k` most active units with an output (`SwarmConfig::transfer`)
+    /// of at least `min_activation`, strongest first. Equal outputs are ranked
+    /// by activation, then by smallest id.
+    pub fn winners(&self, k: usize, min_activation: f32) -> Vec<Winner> {
+        let mut active: Vec<Winner> = self.units.iter()
+            .map(|pnu| Winner {
+                id: pnu.id,
+                label: pnu.symbolic_label.clone(),
+                activation: pnu.state.activation,
+                output: self.config.transfer.apply(pnu.state.activation),
+                truth_value: pnu.truth_value,
+            })
+            .filter(|w| w.output >= min_activation)
+            .collect();
+        active.sort_by(|a, b| {
+            b.output.total_cmp(&a.output)
+                .then(b.activation.total_cmp(&a.activation))
+                .then(a.id.cmp(&b.id))
+        });
+        active.truncate(k);
+        active
+    }
+
+    /// Id of the most active unit (smallest id on ties), `None` for an empty swarm.
+    pub fn argmax(&self) -> Option<usize> {
+        self.winners(1, f32::NEG_INFINITY).first().map(|w| w.id)
+    }
+
+    /// Spikes of the session (since `new` or `load`), oldest first.
+    pub fn spike_log(&self) -> &SpikeLog {
+        &self.spikes
+    }
+
+    /// Writes the spike log as CSV: a `unit_id,time,activation` header, then
+    /// one spike per line, oldest first.
+    pub fn export_spikes_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "unit_id,time,activation")?;
+        for spike in self.spikes.iter() {
+            writeln!(writer, "{},{},{}", spike.unit_id, spike.time, spike.activation)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the whole swarm (units, links, correlations, clock, config) in
+    /// compact binary form, behind a magic/format-version header.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(SWARM_MAGIC)?;
+        writer.write_all(&SWARM_FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, self).map_err(invalid_data)?;
+        writer.flush()
+    }
+
+    /// Reads a swarm written by `save`. Files of another format version are
+    /// rejected rather than misparsed. The id map is rebuilt (duplicate ids are
+    /// an error) and every unit is brought back within its Gershgorin bound.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != SWARM_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a swarm file"));
+        }
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != SWARM_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported swarm format version {} (expected {})", version, SWARM_FORMAT_VERSION),
+            ));
+        }
+
+        let mut swarm: Swarm = bincode::deserialize_from(reader).map_err(invalid_data)?;
+        swarm.spikes.set_capacity(swarm.config.spike_log_capacity);
+        for (i, pnu) in swarm.units.iter_mut().enumerate() {
+            if swarm.index.insert(pnu.id, i).is_some() {
+                return Err(invalid_data(CortexError::DuplicateId(pnu.id)));
+            }
+            pnu.enforce_gershgorin_stability();
+        }
+        Ok(swarm)
+    }
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cortex::prototypical_neural_unit::tests::pnu;
+    use crate::perception::universal_vector::SignatureMetric;
+
+    fn link(target_id: usize, weight: f32) -> LateralLink {
+        LateralLink { target_id, weight, plasticity_rate: 0.0, prior_weight: weight }
+    }
+
+    #[test]
+    fn test_step_is_order_independent() {
+        // Two mutually inhibiting units under the same drive stay identical
+        let mut swarm = Swarm::default();
+        for id in 0..2 {
+            let mut unit = pnu(id, vec![1.0]);
+            unit.lateral_links.push(link(1 - id, -0.5));
+            swarm.push(unit).unwrap();
+        }
+        for _ in 0..100 {
+            swarm.step(0.05, &SwarmInput::Uniform(1.0));
+        }
+        let activations = swarm.activations();
+        assert_eq!(activations[0].1, activations[1].1);
+        assert!((swarm.time() - 5.0).abs() < 1e-6);
+        assert_eq!(swarm.push(pnu(1, vec![1.0])), Err(CortexError::DuplicateId(1)));
+    }
+
+    #[test]
+    fn test_non_contiguous_ids() {
+        // Unit 42 excites unit 7, only 42 is driven: 7 must read 42's activation
+        let mut swarm = Swarm::default();
+        let mut seven = pnu(7, vec![1.0, 0.0]);
+        seven.lateral_links.push(link(42, 0.5));
+        swarm.push(seven).unwrap();
+        swarm.push(pnu(42, vec![0.0, 1.0])).unwrap();
+        swarm.push(pnu(1000, vec![1.0, 0.0])).unwrap();
+
+        let mut drive = HashMap::new();
+        drive.insert(42, 2.0);
+        for _ in 0..200 {
+            swarm.step(0.01, &SwarmInput::ById(drive.clone()));
+        }
+        let activation = |id| swarm.get(id).unwrap().state.activation;
+        assert!(activation(42) > 0.5);
+        assert!(activation(7) > 0.0);
+        assert_eq!(activation(1000), 0.0);
+        assert_eq!(swarm.lateral_input(swarm.get(7).unwrap()), (0.5 * activation(42), 0.0));
+
+        // A pattern drives the units whose prototypes point its way
+        swarm.step(0.01, &SwarmInput::Pattern(vec![3.0, 0.0].into_boxed_slice()));
+        assert!(swarm.get(1000).unwrap().state.activation > 0.0);
+        assert!(swarm.get(99).is_none());
+    }
+
+    #[test]
+    fn test_temporal_correlations() {
+        let config = SwarmConfig {
+            correlation_smoothing: 0.5,
+            correlation_time_constant: 5.0,
+            max_correlations: 1,
+            ..SwarmConfig::default()
+        };
+        let mut swarm = Swarm::new(config);
+        for id in [3, 5, 8] {
+            swarm.push(pnu(id, vec![1.0])).unwrap();
+        }
+
+        // Pulses of 0.5 s every 2 s: units 3 and 5 together, unit 8 half a period later
+        let dt = 0.01;
+        let run = |swarm: &mut Swarm, steps: usize, driven: bool| {
+            for _ in 0..steps {
+                let phase = swarm.time() % 2.0;
+                let mut drive = HashMap::new();
+                if driven && phase < 0.5 {
+                    drive.insert(3, 3.0);
+                    drive.insert(5, 3.0);
+                }
+                if driven && (1.0..1.5).contains(&phase) {
+                    drive.insert(8, 3.0);
+                }
+                swarm.step(dt, &SwarmInput::ById(drive));
+                let now = swarm.time();
+                swarm.update_temporal_correlations(0.1, now);
+            }
+        };
+
+        // Ends just after a shared pulse
+        run(&mut swarm, 2040, true);
+        assert!(swarm.correlation(3, 5) > 0.5);
+        assert_eq!(swarm.correlation(3, 5), swarm.correlation(5, 3));
+        assert!(swarm.correlation(3, 8) < 0.05);
+        assert!(swarm.correlation(8, 5) < 0.05);
+        assert!(swarm.units().iter().all(|u| u.temporal_correlations.len() <= 1));
+        let coactive_at = swarm.get(3).unwrap().temporal_correlations[0].last_coactivation_time;
+        assert!(coactive_at > 0.0);
+
+        // Input stops: entries decay below the floor and are removed
+        run(&mut swarm, 2500, false);
+        assert_eq!(swarm.correlation(3, 5), 0.0);
+        assert!(swarm.units().iter().all(|u| u.temporal_correlations.is_empty()));
+    }
+
+    #[test]
+    fn test_hebbian_lateral_plasticity() {
+        let mut swarm = Swarm::new(SwarmConfig { plasticity_decay: 0.5, ..SwarmConfig::default() });
+        // 1 and 2 co-active; 3 and 4 alternate; 1 also inhibits 4
+        for id in 1..=4 {
+            let mut unit = pnu(id, vec![1.0]);
+            // Fast leak: activations follow the alternating drive closely
+            unit.auto_inhibition_a = 5.0;
+            let partner = [0, 2, 1, 4, 3][id];
+            unit.lateral_links.push(LateralLink {
+                target_id: partner,
+                weight: 0.1,
+                plasticity_rate: 0.5,
+                prior_weight: 0.1,
+            });
+            swarm.push(unit).unwrap();
+        }
+        swarm.get_mut(1).unwrap().lateral_links.push(LateralLink {
+            target_id: 4,
+            weight: -0.1,
+            plasticity_rate: 0.5,
+            prior_weight: -0.1,
+        });
+
+        let weight = |swarm: &Swarm, from: usize, to: usize| {
+            swarm.get(from).unwrap().lateral_links.iter().find(|l| l.target_id == to).unwrap().weight
+        };
+        for i in 0..2000 {
+            let mut drive = HashMap::new();
+            drive.insert(1, 2.0);
+            drive.insert(2, 2.0);
+            drive.insert(if (i / 500) % 2 == 0 { 3 } else { 4 }, 2.0);
+            swarm.step(0.01, &SwarmInput::ById(drive));
+            swarm.update_lateral_plasticity(0.01);
+            for unit in swarm.units() {
+                let sum: f32 = unit.lateral_links.iter().map(|l| l.weight.abs()).sum();
+                assert!(sum < unit.auto_inhibition_a);
+            }
+        }
+        assert!(weight(&swarm, 1, 2) > 0.1);
+        assert!(weight(&swarm, 2, 1) > 0.1);
+        assert!(weight(&swarm, 3, 4) < 0.1 && weight(&swarm, 3, 4) >= 0.0);
+        assert!(weight(&swarm, 4, 3) < 0.1 && weight(&swarm, 4, 3) >= 0.0);
+        // Inhibitory links stay inhibitory
+        assert!(weight(&swarm, 1, 4) < 0.0);
+
+        // Frozen plasticity leaves the weights alone
+        swarm.config.plasticity_enabled = false;
+        let before = weight(&swarm, 1, 2);
+        swarm.step(0.01, &SwarmInput::Uniform(2.0));
+        swarm.update_lateral_plasticity(0.01);
+        assert_eq!(weight(&swarm, 1, 2), before);
+    }
+
+    #[test]
+    fn test_runtime_labels() {
+        use crate::cortex::label::Label;
+
+        let mut swarm = Swarm::default();
+        for id in 0..4 {
+            // Labels built at runtime, e.g. concept names from the dictionary
+            let mut unit = pnu(id, vec![1.0]);
+            unit.symbolic_label = format!("concept-{}", id % 2).into();
+            swarm.push(unit).unwrap();
+        }
+        assert_eq!(swarm.label_of(3), Some("concept-1"));
+        assert_eq!(swarm.find_by_label("concept-0"), vec![0, 2]);
+        assert!(swarm.find_by_label("Test").is_empty());
+
+        let label = swarm.get(1).unwrap().symbolic_label.clone();
+        let json = serde_json::to_string(&label).unwrap();
+        assert_eq!(json, "\"concept-1\"");
+        assert_eq!(serde_json::from_str::<Label>(&json).unwrap(), label);
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let mut swarm = Swarm::default();
+        for id in 0..100 {
+            let weights = (0..4).map(|k| ((id * 4 + k) as f32 * 0.37).sin()).collect();
+            swarm.push(pnu(id, weights)).unwrap();
+        }
+        swarm.wire(&TopologyConfig {
+            sigma_excitation: 0.5,
+            sigma_inhibition: 1.5,
+            amp_excitation: 2.0,
+            amp_inhibition: 1.0,
+            connection_cutoff: 0.01,
+            max_neighbors: 10,
+            metric: SignatureMetric::L2,
+        });
+        for _ in 0..20 {
+            swarm.step(0.01, &SwarmInput::Uniform(0.5));
+        }
+
+        let path = std::env::temp_dir().join(format!("archt3_swarm_{}.bin", std::process::id()));
+        swarm.save(&path).unwrap();
+        let mut loaded = Swarm::load(&path).unwrap();
+
+        assert_eq!(loaded.len(), 100);
+        assert_eq!(loaded.time(), swarm.time());
+        for (a, b) in swarm.units().iter().zip(loaded.units()) {
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.weight_vector, b.weight_vector);
+            assert_eq!(a.lateral_links.len(), b.lateral_links.len());
+            for (la, lb) in a.lateral_links.iter().zip(&b.lateral_links) {
+                assert_eq!(
+                    (la.target_id, la.weight, la.plasticity_rate, la.prior_weight),
+                    (lb.target_id, lb.weight, lb.plasticity_rate, lb.prior_weight)
+                );
+            }
+        }
+        assert_eq!(loaded.get(42).map(|u| u.id), Some(42));
+        // The spike log is session history: empty, but sized from the config
+        assert!(loaded.spike_log().is_empty());
+        assert_eq!(loaded.spike_log().capacity(), loaded.config().spike_log_capacity);
+
+        let input = SwarmInput::Pattern(vec![0.3, -0.2, 0.9, 0.1].into());
+        for _ in 0..200 {
+            assert_eq!(swarm.step(0.01, &input), loaded.step(0.01, &input));
+            assert_eq!(swarm.activations(), loaded.activations());
+        }
+
+        // Foreign or future files are rejected
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[4..8].copy_from_slice(&(SWARM_FORMAT_VERSION + 1).to_le_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(Swarm::load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        bytes[0] = b'X';
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(Swarm::load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_neurogenesis_on_poor_resonance() {
+        let mut swarm = Swarm::default();
+        swarm.wire(&TopologyConfig {
+            sigma_excitation: 0.5,
+            sigma_inhibition: 1.5,
+            amp_excitation: 2.0,
+            amp_inhibition: 1.0,
+            connection_cutoff: 0.01,
+            max_neighbors: 10,
+            metric: SignatureMetric::L2,
+        });
+
+        // Three well-separated clusters, members interleaved
+        let member = |cluster: usize, k: usize| -> Vec<f32> {
+            let mut x: Vec<f32> = (0..3).map(|d| 0.05 * ((k * 3 + d) as f32).sin()).collect();
+            x[cluster] += 2.0;
+            x
+        };
+        let mut spawned = Vec::new();
+        for k in 0..10 {
+            for cluster in 0..3 {
+                if let PresentOutcome::Spawned { id, .. } = swarm.present_input(&member(cluster, k), k as f64).unwrap() {
+                    spawned.push(id);
+                }
+            }
+        }
+        assert_eq!(spawned, vec![0, 1, 2]);
+        assert_eq!(swarm.len(), 3);
+
+        let first = swarm.get(0).unwrap();
+        // Spawn and the 9 matches recorded an episode each; the oldest 2 are gone
+        assert_eq!(first.episodes.len(), DEFAULT_EPISODE_CAPACITY);
+        assert_eq!(first.episodes().next().unwrap().signature_segment, member(0, 2));
+        let norm = first.weight_vector.iter().map(|w| w * w).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert_eq!(swarm.get(2).unwrap().birth_timestamp, 0.0);
+
+        // Spawned units are wired to each other (inhibitory crown)
+        for pnu in swarm.units() {
+            assert_eq!(pnu.lateral_links.len(), 2);
+            assert!(pnu.lateral_links.iter().all(|l| l.weight < 0.0));
+        }
+
+        for cluster in 0..3 {
+            match swarm.present_input(&member(cluster, 20), 30.0).unwrap() {
+                PresentOutcome::Matched { id, resonance, .. } => {
+                    assert_eq!(id, cluster);
+                    assert!(resonance > 0.99);
+                }
+                outcome => panic!("cluster {} spawned: {:?}", cluster, outcome),
+            }
+        }
+        assert_eq!(swarm.len(), 3);
+    }
+
+    #[test]
+    fn test_prune_stale_and_redundant_units() {
+        let mut swarm = Swarm::default();
+        for id in 0..3 {
+            let mut unit = pnu(id, vec![1.0, id as f32]);
+            for other in (0..3).filter(|&o| o != id) {
+                unit.lateral_links.push(link(other, 0.1));
+                unit.temporal_correlations.push(TemporalCorrelation {
+                    pnu_id: other,
+                    correlation_strength: 0.5,
+                    last_coactivation_time: 0.0,
+                });
+            }
+            swarm.push(unit).unwrap();
+        }
+        swarm.get_mut(1).unwrap().birth_timestamp = 9.0;
+        swarm.get_mut(2).unwrap().last_spike_time = Some(9.5);
+        for _ in 0..100 {
+            swarm.step(0.1, &SwarmInput::None);
+        }
+
+        // Unit 0 never spiked and was born long ago
+        let removed = swarm.prune(PrunePolicy { stale_after: Some(5.0), ..PrunePolicy::default() });
+        assert_eq!(removed, vec![0]);
+        assert!(swarm.get(0).is_none());
+        assert_eq!(swarm.get(2).map(|u| u.id), Some(2));
+        for pnu in swarm.units() {
+            assert!(pnu.lateral_links.iter().all(|l| l.target_id != 0));
+            assert!(pnu.temporal_correlations.iter().all(|c| c.pnu_id != 0));
+            assert_eq!(pnu.lateral_links.len(), 1);
+        }
+
+        // Near-duplicates: 3 (active) absorbs most of 4, back on the unit sphere
+        let mut swarm = Swarm::default();
+        let mut kept = pnu(3, vec![1.0, 0.0]);
+        kept.lateral_links.push(link(4, 0.1));
+        let mut far = pnu(5, vec![0.0, 1.0]);
+        far.lateral_links.push(link(4, 0.2));
+        let mut both = pnu(6, vec![0.0, -1.0]);
+        both.lateral_links.extend([link(3, 0.3), link(4, 0.2)]);
+        swarm.push(kept).unwrap();
+        swarm.push(pnu(4, vec![0.98, 0.04])).unwrap();
+        swarm.push(far).unwrap();
+        swarm.push(both).unwrap();
+        // Same leading term, different length: never merged
+        swarm.push(pnu(7, vec![1.0])).unwrap();
+        swarm.get_mut(3).unwrap().state.activation = 0.75;
+        swarm.get_mut(4).unwrap().state.activation = 0.25;
+        let removed = swarm.prune(PrunePolicy { merge_distance: Some(0.1), ..PrunePolicy::default() });
+        assert_eq!(removed, vec![4]);
+        assert_eq!(swarm.len(), 4);
+        let merged = &swarm.get(3).unwrap().weight_vector;
+        let norm = 0.995f32.hypot(0.01);
+        assert!((merged[0] - 0.995 / norm).abs() < 1e-6 && (merged[1] - 0.01 / norm).abs() < 1e-6, "{:?}", merged);
+        assert_eq!(swarm.get(5).unwrap().weight_vector[1], 1.0);
+        assert_eq!(&*swarm.get(7).unwrap().weight_vector, &[1.0]);
+
+        // Incoming links follow the survivor; no self-link, no duplicate
+        let targets = |id: usize| swarm.get(id).unwrap().lateral_links.iter().map(|l| (l.target_id, l.weight)).collect::<Vec<_>>();
+        assert!(targets(3).is_empty());
+        assert_eq!(targets(5), vec![(3, 0.2)]);
+        assert_eq!(targets(6), vec![(3, 0.3)]);
+    }
+
+    #[test]
+    fn test_prune_long_exhausted_units() {
+        let mut swarm = Swarm::default();
+        for id in 0..2 {
+            swarm.push(pnu(id, vec![1.0])).unwrap();
+        }
+        let tired = swarm.get_mut(1).unwrap();
+        tired.activation_budget = 0.0;
+        tired.activation_consumption = 1.0;
+        for _ in 0..30 {
+            swarm.step(0.1, &SwarmInput::None);
+        }
+        let policy = PrunePolicy { exhausted_for: Some(2.0), ..PrunePolicy::default() };
+        assert_eq!(swarm.prune(policy.clone()), vec![1]);
+        assert_eq!(swarm.prune(policy), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_winners_readout() {
+        let mut swarm = Swarm::default();
+        for id in 0..4 {
+            swarm.push(PnuBuilder::new(id).weights(vec![1.0]).label(format!("concept-{}", id)).build().unwrap()).unwrap();
+        }
+        swarm.get_mut(2).unwrap().truth_value = -0.5;
+        assert_eq!(swarm.argmax(), Some(0));
+
+        let mut drive = HashMap::new();
+        drive.insert(2, 2.0);
+        drive.insert(1, 0.5);
+        for _ in 0..100 {
+            swarm.step(0.05, &SwarmInput::ById(drive.clone()));
+        }
+
+        let winners = swarm.winners(3, 0.1);
+        assert_eq!(winners.iter().map(|w| w.id).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(winners[0].activation > winners[1].activation);
+        assert_eq!(winners[0].label, "concept-2");
+        // Relu would clamp it to 0: the truth value is copied raw
+        assert_eq!(winners[0].truth_value, -0.5);
+        assert_eq!(swarm.winners(1, 0.1).len(), 1);
+        assert!(swarm.winners(3, 0.9).is_empty());
+        assert_eq!(swarm.argmax(), Some(2));
+
+        // Ties (the two undriven units) are broken by id
+        let all = swarm.winners(4, f32::NEG_INFINITY);
+        assert_eq!(all.iter().map(|w| w.id).collect::<Vec<_>>(), vec![2, 1, 0, 3]);
+    }
+
+    #[test]
+    fn test_incremental_wiring_approximates_full_wiring() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let units: Vec<PrototypicalNeuralUnit> = (0..120)
+            .map(|id| pnu(id, vec![rng.gen_range(-2.0..2.0), rng.gen_range(-2.0..2.0)]))
+            .collect();
+        let config = TopologyConfig {
+            sigma_excitation: 0.3,
+            sigma_inhibition: 0.9,
+            amp_excitation: 2.0,
+            amp_inhibition: 1.0,
+            connection_cutoff: 0.05,
+            max_neighbors: 8,
+            metric: SignatureMetric::L2,
+        };
+
+        let mut full = Swarm::default();
+        for unit in &units {
+            full.push(unit.clone()).unwrap();
+        }
+        full.wire(&config);
+
+        let mut incremental = Swarm::default();
+        for unit in &units[..60] {
+            incremental.push(unit.clone()).unwrap();
+        }
+        incremental.wire(&config);
+        for unit in &units[60..] {
+            incremental.push(unit.clone()).unwrap();
+            incremental.wire_incremental(&[unit.id], &config);
+        }
+
+        let targets = |pnu: &PrototypicalNeuralUnit| -> Vec<usize> {
+            let mut t: Vec<usize> = pnu.lateral_links.iter().map(|l| l.target_id).collect();
+            t.sort_unstable();
+            t
+        };
+        let (mut shared, mut total) = (0, 0);
+        for (a, b) in full.units().iter().zip(incremental.units()) {
+            let (ta, tb) = (targets(a), targets(b));
+            assert!(tb.len() <= config.max_neighbors);
+            assert!(b.lateral_links.iter().map(|l| l.weight.abs()).sum::<f32>() < b.auto_inhibition_a);
+            // Nothing learned yet: every link sits at its prior
+            assert!(a.lateral_links.iter().chain(&b.lateral_links).all(|l| l.weight == l.prior_weight));
+            shared += ta.iter().filter(|t| tb.contains(t)).count();
+            total += ta.len().max(tb.len());
+        }
+        assert!(shared as f32 >= 0.9 * total as f32, "{} / {} links shared", shared, total);
+
+        // The last unit saw the whole swarm: exactly its full-wiring links
+        assert_eq!(targets(&full.units()[119]), targets(&incremental.units()[119]));
+    }
+
+    #[test]
+    fn test_lateral_input_resolves_ids() {
+        // Ids {10, 42, 7} stored out of order, none equal to its position
+        let mut swarm = Swarm::default();
+        let mut ten = pnu(10, vec![1.0]);
+        ten.lateral_links.push(link(42, 0.5));
+        ten.lateral_links.push(link(7, -0.25));
+        let mut seven = pnu(7, vec![1.0]);
+        seven.lateral_links.push(link(10, 1.0));
+        seven.lateral_links.push(link(3, 1.0));
+        swarm.push(pnu(42, vec![1.0])).unwrap();
+        swarm.push(ten).unwrap();
+        swarm.push(seven).unwrap();
+        for (id, activation) in [(42, 0.8), (10, 0.4), (7, 0.6)] {
+            swarm.get_mut(id).unwrap().state.activation = activation;
+        }
+
+        let (exc, inh) = swarm.lateral_input(swarm.get(10).unwrap());
+        assert!((exc - 0.5 * 0.8).abs() < 1e-6 && (inh - 0.25 * 0.6).abs() < 1e-6);
+        // The link to the unknown id 3 is skipped
+        let (exc, inh) = swarm.lateral_input(swarm.get(7).unwrap());
+        assert!((exc - 0.4).abs() < 1e-6 && inh == 0.0);
+        assert_eq!(swarm.check_links(), Err(CortexError::DanglingLink { source: 7, target: 3 }));
+
+        swarm.get_mut(7).unwrap().lateral_links.retain(|l| l.target_id != 3);
+        assert_eq!(swarm.check_links(), Ok(()));
+    }
+
+    #[test]
+    fn test_faster_than_linear_signal_selects_one_winner() {
+        // Self-excitation and mutual inhibition, unequal drive for 2 time units,
+        // then free recurrent competition
+        let compete = |transfer: TransferFn| -> Vec<Winner> {
+            let mut swarm = Swarm::new(SwarmConfig { transfer, ..SwarmConfig::default() });
+            for id in 0..2 {
+                let mut unit = pnu(id, vec![1.0]);
+                unit.lateral_links.push(link(id, 4.0));
+                unit.lateral_links.push(link(1 - id, -2.0));
+                swarm.push(unit).unwrap();
+            }
+            let mut drive = HashMap::new();
+            drive.insert(0, 1.0);
+            drive.insert(1, 0.8);
+            for k in 0..4000 {
+                let input = if k < 200 { SwarmInput::ById(drive.clone()) } else { SwarmInput::None };
+                swarm.step(0.01, &input);
+            }
+            swarm.winners(2, 0.05)
+        };
+
+        let linear = compete(TransferFn::Relu);
+        assert_eq!(linear.len(), 2, "{:?}", linear);
+
+        let squared = compete(TransferFn::Power { exponent: 2.0 });
+        assert_eq!(squared.len(), 1, "{:?}", squared);
+        assert_eq!(squared[0].id, 0);
+        assert!(squared[0].activation > 0.3);
+    }
+
+    #[test]
+    fn test_surprise_drives_learning() {
+        let mut swarm = Swarm::new(SwarmConfig { vigilance: 0.0, surprise_gain: 2.0, ..SwarmConfig::default() });
+        let mut unit = pnu(0, vec![1.0, 0.0, 0.0]);
+        unit.lateral_links.push(link(1, -0.5));
+        swarm.push(unit).unwrap();
+        swarm.push(pnu(1, vec![0.0, 0.0, 1.0])).unwrap();
+
+        let familiar = swarm.present_input(&[2.0, 0.0, 0.0], 1.0).unwrap();
+        assert!(matches!(familiar, PresentOutcome::Matched { id: 0, .. }));
+        assert!(familiar.surprise() < 1e-6);
+        assert!(swarm.get(0).unwrap().weight_vector[1].abs() < 1e-6);
+
+        // Weakly matching input: high surprise, large pull of the prototype
+        let novel = swarm.present_input(&[0.3, 1.0, 0.0], 2.0).unwrap();
+        assert!(matches!(novel, PresentOutcome::Matched { id: 0, .. }));
+        assert!(novel.surprise() > 0.7, "{:?}", novel);
+        let matched = swarm.get(0).unwrap();
+        assert!(matched.weight_vector[1] > 1e-3);
+        assert_eq!(matched.last_surprise_time, 2.0);
+        let neighbor = swarm.get(1).unwrap();
+        assert!((neighbor.fatigue() - neighbor.surprise_sensitivity * novel.surprise() * 0.5).abs() < 1e-6);
+
+        // Surprise raises the learning rate
+        let mut calm = pnu(0, vec![1.0, 0.0, 0.0]);
+        calm.state.activation = 0.3;
+        let mut surprised = calm.clone();
+        let base = calm.learn_oja(&[0.0, 1.0, 0.0]).unwrap();
+        let boosted = surprised.learn_oja_surprised(&[0.0, 1.0, 0.0], 0.7, 2.0).unwrap();
+        assert!(boosted > 2.0 * base);
+
+        // An empty swarm is maximally surprised
+        assert_eq!(Swarm::default().present_input(&[1.0], 0.0).unwrap(), PresentOutcome::Spawned { id: 0, surprise: 1.0 });
+    }
+
+    #[test]
+    fn test_present_rejects_invalid_input() {
+        let mut swarm = Swarm::default();
+        swarm.present_input(&[1.0, 0.0], 0.0).unwrap();
+        let before = swarm.get(0).unwrap().weight_vector.clone();
+
+        for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert_eq!(swarm.present_input(&[1.0, bad], 1.0), Err(CortexError::NonFiniteInput { index: 1 }));
+        }
+        assert_eq!(swarm.present_input(&[], 1.0), Err(CortexError::EmptyPrototype));
+        assert_eq!(swarm.present_input(&[1.0, 0.0, 0.0], 1.0), Err(CortexError::DimensionMismatch { expected: 2, got: 3 }));
+        assert_eq!(swarm.present_input(&[1.0], 1.0), Err(CortexError::DimensionMismatch { expected: 2, got: 1 }));
+        assert_eq!(swarm.len(), 1);
+        assert_eq!(swarm.get(0).unwrap().weight_vector, before);
+        assert_eq!(swarm.stats().presented, 1);
+    }
+
+    #[test]
+    fn test_vigilance_gates_learning() {
+        let borderline = [0.8, 0.6];
+        let present = |config: SwarmConfig, contribution: f32| -> (PresentOutcome, Swarm) {
+            let mut swarm = Swarm::new(config);
+            swarm.push(PnuBuilder::new(0).weights(vec![1.0, 0.0]).vigilance_contribution(contribution).build().unwrap()).unwrap();
+            (swarm.present_input(&borderline, 1.0).unwrap(), swarm)
+        };
+
+        // High vigilance: a new unit, the prototype is untouched
+        let (outcome, swarm) = present(SwarmConfig { vigilance: 0.9, ..SwarmConfig::default() }, 0.0);
+        assert!(matches!(outcome, PresentOutcome::Spawned { id: 1, .. }));
+        assert_eq!(&*swarm.get(0).unwrap().weight_vector, &[1.0, 0.0]);
+        assert_eq!(swarm.stats().vigilance_failures, 1);
+
+        // Zero vigilance: the input blends into the prototype
+        let (outcome, swarm) = present(SwarmConfig { vigilance: 0.0, ..SwarmConfig::default() }, 0.0);
+        assert!(matches!(outcome, PresentOutcome::Matched { id: 0, .. }));
+        assert_eq!(swarm.len(), 1);
+        assert!(swarm.get(0).unwrap().weight_vector[1] > 0.0);
+        assert_eq!(swarm.stats().vigilance_failures, 0);
+
+        // A protected unit raises its own bar; without neurogenesis the input is dropped
+        let config = SwarmConfig { vigilance: 0.5, neurogenesis: false, ..SwarmConfig::default() };
+        let (outcome, swarm) = present(config, 0.4);
+        assert!(matches!(outcome, PresentOutcome::Rejected { .. }));
+        assert_eq!(swarm.len(), 1);
+        assert_eq!(&*swarm.get(0).unwrap().weight_vector, &[1.0, 0.0]);
+        assert_eq!(
+            swarm.stats(),
+            &SwarmStats {
+                presented: 1,
+                matched: 0,
+                spawned: 0,
+                rejected: 1,
+                vigilance_failures: 1,
+                pruned: 0,
+                stability_violations: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_present_vector_end_to_end() {
+        use crate::perception::source::Sample;
+        use crate::perception::universal_scanner::UniversalScanner;
+
+        let samples = |channels: usize| -> Vec<Sample> {
+            (0..600)
+                .map(|i| {
+                    let t = i as f64 * 0.01;
+                    let regime = if (i / 150) % 2 == 0 { 1.0 } else { -2.0 };
+                    Sample::new(t, (0..channels).map(|c| (t * (3.0 + c as f64) * regime).sin()).collect())
+                })
+                .collect()
+        };
+        let vectors = UniversalScanner::new(50).ingest_all(samples(2));
+        assert!(vectors.len() > 4);
+        let len = vectors[0].signature.flatten().len();
+
+        let mut swarm = Swarm::default();
+        swarm.set_scene(3);
+        for v in &vectors {
+            swarm.present_vector(v, v.metadata.timestamp).unwrap();
+        }
+        assert!(!swarm.is_empty());
+        assert_eq!(swarm.stats().presented, vectors.len());
+        for pnu in swarm.units() {
+            assert_eq!(pnu.weight_vector.len(), len);
+            let norm = pnu.weight_vector.iter().map(|w| w * w).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-4);
+            let episode = pnu.most_recent_episode().unwrap();
+            assert_eq!(episode.signature_segment.len(), len);
+            assert_eq!(episode.scene_context_id, 3);
+        }
+        let last = vectors.last().unwrap();
+        let outcome = swarm.present_vector(last, 100.0).unwrap();
+        let PresentOutcome::Matched { id, .. } = outcome else { panic!("{:?}", outcome) };
+        assert_eq!(swarm.get(id).unwrap().most_recent_episode().unwrap().timestamp, last.metadata.timestamp);
+
+        // Three channels flatten to a longer signature
+        let other = UniversalScanner::new(50).ingest_all(samples(3));
+        assert_eq!(
+            swarm.present_vector(&other[0], 0.0),
+            Err(CortexError::DimensionMismatch { expected: len, got: other[0].signature.flatten().len() })
+        );
+    }
+
+    #[test]
+    fn test_episodes_are_bounded_and_gated_by_surprise() {
+        let config = SwarmConfig { vigilance: 0.0, episode_capacity: 3, ..SwarmConfig::default() };
+        let mut swarm = Swarm::new(config.clone());
+        for k in 0..5 {
+            swarm.present_input(&[1.0, 0.01 * k as f32], k as f64).unwrap();
+        }
+        let unit = swarm.get(0).unwrap();
+        assert_eq!(swarm.len(), 1);
+        assert_eq!(unit.episodes.capacity(), 3);
+        // The two oldest were evicted
+        assert_eq!(unit.episodes().map(|e| e.timestamp).collect::<Vec<_>>(), vec![2.0, 3.0, 4.0]);
+        assert_eq!(unit.most_recent_episode().unwrap().signature_segment, vec![1.0, 0.04]);
+
+        let mut swarm = Swarm::new(SwarmConfig { record_on_surprise_only: true, ..config });
+        swarm.present_input(&[1.0, 0.0], 0.0).unwrap();
+        let familiar = swarm.present_input(&[1.0, 0.01], 1.0).unwrap();
+        assert!(familiar.surprise() < swarm.config().episode_surprise_threshold);
+        let novel = swarm.present_input(&[0.2, 1.0], 2.0).unwrap();
+        assert!(matches!(novel, PresentOutcome::Matched { id: 0, .. }));
+        let times: Vec<f64> = swarm.get(0).unwrap().episodes().map(|e| e.timestamp).collect();
+        assert_eq!(times, vec![0.0, 2.0]);
+
+        // Episodes survive a save/load round trip
+        let path = std::env::temp_dir().join(format!("archt3_episodes_{}.bin", std::process::id()));
+        swarm.save(&path).unwrap();
+        let loaded = Swarm::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let segments = |s: &Swarm| -> Vec<(f64, Vec<f32>)> {
+            s.get(0).unwrap().episodes().map(|e| (e.timestamp, e.signature_segment.clone())).collect()
+        };
+        assert_eq!(segments(&loaded), segments(&swarm));
+        assert_eq!(loaded.get(0).unwrap().episodes.capacity(), 3);
+    }
+
+    #[test]
+    fn test_metrics_snapshot() {
+        let mut swarm = Swarm::default();
+        for (id, activation, budget) in [(0, 0.6, 100.0), (1, 0.2, 50.0), (2, -0.2, 0.0)] {
+            let mut unit = PnuBuilder::new(id).weights(vec![1.0, id as f32]).budget(budget, 1.0, 0.0).build().unwrap();
+            unit.state.activation = activation;
+            swarm.push(unit).unwrap();
+        }
+        swarm.get_mut(0).unwrap().lateral_links.extend([link(1, 0.5), link(2, -0.25)]);
+        swarm.get_mut(1).unwrap().lateral_links.push(link(0, 0.75));
+
+        let metrics = swarm.metrics();
+        assert_eq!(metrics.n_units, 3);
+        assert!((metrics.mean_activation - 0.2).abs() < 1e-6);
+        assert!((metrics.active_fraction - 2.0 / 3.0).abs() < 1e-6);
+        // Rectified distribution (0.75, 0.25, 0)
+        let entropy = -(0.75f32 * 0.75f32.ln() + 0.25 * 0.25f32.ln());
+        assert!((metrics.activation_entropy - entropy).abs() < 1e-6);
+        assert!((metrics.mean_budget - 50.0).abs() < 1e-6);
+        assert_eq!(metrics.exhausted_count, 1);
+        assert_eq!(metrics.total_links, 3);
+        assert!((metrics.mean_abs_link_weight - 0.5).abs() < 1e-6);
+        assert_eq!((metrics.spawned_since_last, metrics.pruned_since_last), (0, 0));
+
+        swarm.present_input(&[-1.0, 0.0], 1.0).unwrap();
+        swarm.step(0.1, &SwarmInput::None);
+        assert_eq!(swarm.prune(PrunePolicy { exhausted_for: Some(0.0), ..PrunePolicy::default() }), Vec::<usize>::new());
+        swarm.step(0.1, &SwarmInput::None);
+        assert_eq!(swarm.prune(PrunePolicy { exhausted_for: Some(0.05), ..PrunePolicy::default() }), vec![2]);
+        let metrics = swarm.metrics();
+        assert_eq!((metrics.spawned_since_last, metrics.pruned_since_last), (1, 1));
+        assert!(serde_json::to_string(&metrics).unwrap().contains("\"n_units\":3"));
+
+        swarm.reset_metrics();
+        assert_eq!((swarm.metrics().spawned_since_last, swarm.metrics().pruned_since_last), (0, 0));
+    }
+
+    #[test]
+    fn test_step_until_settled() {
+        let mut drive = HashMap::new();
+        drive.insert(0, 1.0);
+        drive.insert(1, 0.5);
+        let drive = SwarmInput::ById(drive);
+
+        let mut stable = Swarm::default();
+        for id in 0..2 {
+            let mut unit = pnu(id, vec![1.0]);
+            unit.lateral_links.push(link(1 - id, -0.5));
+            stable.push(unit).unwrap();
+        }
+        let result = stable.step_until_settled(0.05, &drive, 10_000, 1e-4);
+        assert!(result.converged);
+        assert!(result.steps_taken < 1000, "{:?}", result);
+        assert!(result.max_derivative < 1e-4);
+        assert_eq!(stable.max_derivative(), result.max_derivative);
+
+        // Excitatory-inhibitory loop far beyond the leak (sum |w| >> A, never
+        // rescaled): 0 excites 1 which shuts 0 down, and so on
+        let mut unstable = Swarm::default();
+        for (id, weight) in [(0, -50.0), (1, 50.0)] {
+            let mut unit = pnu(id, vec![1.0]);
+            unit.lateral_links.push(link(1 - id, weight));
+            unstable.push(unit).unwrap();
+        }
+        let result = unstable.step_until_settled(0.1, &SwarmInput::Uniform(1.0), 2000, 1e-4);
+        assert!(!result.converged);
+        assert_eq!(result.steps_taken, 2000);
+        assert!(result.max_derivative > 0.1);
+    }
+
+    #[test]
+    fn test_integrators_accuracy() {
+        let network = |integrator: Integrator| -> Swarm {
+            let mut swarm = Swarm::new(SwarmConfig { integrator, ..SwarmConfig::default() });
+            for (id, links) in [(0, [(1, 0.1), (2, -0.2)]), (1, [(0, -0.2), (2, 0.1)]), (2, [(0, 0.1), (1, -0.1)])] {
+                let mut unit = pnu(id, vec![1.0]);
+                unit.lateral_links.extend(links.iter().map(|&(target, weight)| link(target, weight)));
+                swarm.push(unit).unwrap();
+            }
+            swarm
+        };
+        let mut drive = HashMap::new();
+        drive.insert(0, 6.0);
+        drive.insert(1, 3.0);
+        drive.insert(2, 1.0);
+        let drive = SwarmInput::ById(drive);
+        let run = |integrator: Integrator, dt: f32, steps: usize| -> Vec<f32> {
+            let mut swarm = network(integrator);
+            (0..steps).flat_map(|_| {
+                swarm.step(dt, &drive);
+                swarm.activations().into_iter().map(|(_, a)| a).collect::<Vec<_>>()
+            })
+            .collect()
+        };
+
+        // Reference sampled every 0.1 over 1 time unit
+        let fine = run(Integrator::Euler, 0.0001, 10_000);
+        let reference: Vec<f32> = fine.chunks(3).skip(999).step_by(1000).flatten().copied().collect();
+        let error = |trajectory: Vec<f32>| -> f32 {
+            trajectory.iter().zip(&reference).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max)
+        };
+
+        let euler = error(run(Integrator::Euler, 0.1, 10));
+        let rk4 = error(run(Integrator::Rk4, 0.1, 10));
+        let adaptive = error(run(Integrator::AdaptiveEuler { max_dx: 0.005 }, 0.1, 10));
+        assert!(rk4 < 0.01, "rk4 error {}", rk4);
+        assert!(euler > 0.05, "euler error {}", euler);
+        assert!(adaptive < euler / 2.0, "adaptive error {} vs {}", adaptive, euler);
+    }
+
+    #[test]
+    fn test_stability_diagnostics() {
+        // W = [[0, 0.3], [0.2, 0.1]]: eigenvalues 0.3 and -0.2
+        let mut swarm = Swarm::new(SwarmConfig::default());
+        let mut a = pnu(0, vec![1.0]);
+        a.lateral_links.push(link(1, 0.3));
+        let mut b = pnu(1, vec![1.0]);
+        b.lateral_links.extend([link(0, 0.2), link(1, 0.1)]);
+        swarm.push(a).unwrap();
+        swarm.push(b).unwrap();
+
+        let check = swarm.global_stability_check();
+        assert!(check.converged);
+        assert!((check.spectral_radius - 0.3).abs() < 1e-4, "rho = {}", check.spectral_radius);
+        assert_eq!(check.min_auto_inhibition, swarm.get(0).unwrap().auto_inhibition_a);
+        assert!(check.is_stable());
+        assert_eq!(Swarm::new(SwarmConfig::default()).global_stability_check().spectral_radius, 0.0);
+
+        // Plasticity pushing a unit past its bound is counted
+        let bound = swarm.get(0).unwrap().auto_inhibition_a;
+        swarm.get_mut(0).unwrap().lateral_links[0].weight = 2.0 * bound;
+        swarm.update_lateral_plasticity(0.01);
+        assert_eq!(swarm.stats().stability_violations, 1);
+        assert_eq!(swarm.metrics().stability_violations_since_last, 1);
+        swarm.reset_metrics();
+        swarm.update_lateral_plasticity(0.01);
+        assert_eq!(swarm.metrics().stability_violations_since_last, 0);
+    }
+
+    #[test]
+    fn test_idle_links_decay_toward_prior() {
+        let mut swarm = Swarm::new(SwarmConfig { coactivity_window: 1.0, ..SwarmConfig::default() });
+        let drifted = |target_id, weight, prior_weight| {
+            LateralLink { target_id, weight, plasticity_rate: 0.0, prior_weight }
+        };
+        let mut a = pnu(0, vec![1.0]);
+        // Reinforced toward a co-active partner; flipped toward a silent one
+        a.lateral_links.extend([drifted(1, 0.5, 0.2), drifted(2, -0.2, 0.1)]);
+        let mut b = pnu(2, vec![1.0]);
+        b.lateral_links.push(drifted(0, 0.5, 0.2));
+        swarm.push(a).unwrap();
+        swarm.push(pnu(1, vec![1.0])).unwrap();
+        swarm.push(b).unwrap();
+        for _ in 0..10 {
+            swarm.step(0.5, &SwarmInput::Uniform(0.0));
+        }
+        let now = swarm.time();
+        swarm.get_mut(0).unwrap().last_spike_time = Some(now - 0.2);
+        swarm.get_mut(1).unwrap().last_spike_time = Some(now - 0.5);
+
+        let weight = |swarm: &Swarm, from: usize, to: usize| {
+            swarm.get(from).unwrap().lateral_links.iter().find(|l| l.target_id == to).unwrap().weight
+        };
+        swarm.decay_links(0.1, 1.0);
+        assert_eq!(weight(&swarm, 0, 1), 0.5);
+        assert!((weight(&swarm, 2, 0) - 0.47).abs() < 1e-6);
+        assert!((weight(&swarm, 0, 2) + 0.17).abs() < 1e-6);
+
+        let mut previous = weight(&swarm, 0, 2);
+        for _ in 0..100 {
+            swarm.decay_links(0.1, 1.0);
+            let w = weight(&swarm, 0, 2);
+            assert!(w > previous && w <= 0.1);
+            previous = w;
+        }
+        assert!((previous - 0.1).abs() < 1e-3);
+        assert!((weight(&swarm, 2, 0) - 0.2).abs() < 1e-3);
+        assert_eq!(weight(&swarm, 0, 1), 0.5);
+
+        // Once the co-activity is old, the reinforced link relaxes too
+        swarm.step(1.0, &SwarmInput::Uniform(0.0));
+        swarm.decay_links(1.0, 1.0);
+        assert!((weight(&swarm, 0, 1) - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_decay_keeps_gershgorin_bound() {
+        let mut swarm = Swarm::new(SwarmConfig { coactivity_window: 1.0, ..SwarmConfig::default() });
+        let mut a = pnu(0, vec![1.0]);
+        a.lateral_links.extend([
+            LateralLink { target_id: 1, weight: 0.66, plasticity_rate: 0.0, prior_weight: 0.2 },
+            LateralLink { target_id: 2, weight: 0.33, plasticity_rate: 0.0, prior_weight: 0.45 },
+        ]);
+        a.last_spike_time = Some(0.0);
+        let mut b = pnu(1, vec![1.0]);
+        b.last_spike_time = Some(0.0);
+        swarm.push(a).unwrap();
+        swarm.push(b).unwrap();
+        swarm.push(pnu(2, vec![1.0])).unwrap();
+
+        // The co-active link holds at 0.66 while the idle one relaxes to 0.45
+        swarm.decay_links(1.0, 1.0);
+        let unit = swarm.get(0).unwrap();
+        let sum: f32 = unit.lateral_links.iter().map(|l| l.weight.abs()).sum();
+        assert!(sum < unit.auto_inhibition_a, "sum = {}", sum);
+        assert!(unit.lateral_links[0].weight > unit.lateral_links[1].weight);
+        assert_eq!(swarm.stats().stability_violations, 1);
+    }
+
+    #[test]
+    fn test_spikes_at_time_zero_count_as_coactive() {
+        let mut swarm = Swarm::new(SwarmConfig { coactivity_window: 1.0, ..SwarmConfig::default() });
+        let mut a = pnu(0, vec![1.0]);
+        a.lateral_links.push(LateralLink { target_id: 1, weight: 0.5, plasticity_rate: 0.0, prior_weight: 0.2 });
+        a.last_spike_time = Some(0.0);
+        let mut b = pnu(1, vec![1.0]);
+        b.last_spike_time = Some(0.0);
+        swarm.push(a).unwrap();
+        swarm.push(b).unwrap();
+
+        swarm.decay_links(0.1, 1.0);
+        assert_eq!(swarm.get(0).unwrap().lateral_links[0].weight, 0.5);
+
+        // A partner that never spiked leaves the link idle
+        swarm.get_mut(1).unwrap().last_spike_time = None;
+        swarm.decay_links(0.1, 1.0);
+        assert!((swarm.get(0).unwrap().lateral_links[0].weight - 0.47).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_spike_log() {
+        let run = |capacity: usize| -> (Swarm, Vec<(usize, f64)>) {
+            let mut swarm = Swarm::new(SwarmConfig { spike_log_capacity: capacity, ..SwarmConfig::default() });
+            for id in [3, 7, 9] {
+                let mut unit = pnu(id, vec![1.0]);
+                unit.auto_inhibition_a = 5.0;
+                swarm.push(unit).unwrap();
+            }
+            // Pulses alternating between 3 and 9; 7 stays silent
+            let mut expected = Vec::new();
+            for cycle in 0..6 {
+                for k in 0..40 {
+                    let mut drive = HashMap::new();
+                    if k < 20 {
+                        drive.insert(if cycle % 2 == 0 { 3 } else { 9 }, 20.0);
+                    }
+                    let spiked = swarm.step(0.01, &SwarmInput::ById(drive));
+                    expected.extend(spiked.into_iter().map(|id| (id, swarm.time())));
+                }
+            }
+            (swarm, expected)
+        };
+
+        let (swarm, expected) = run(64);
+        assert_eq!(expected.len(), 6);
+        let log: Vec<(usize, f64)> = swarm.spike_log().iter().map(|s| (s.unit_id, s.time)).collect();
+        assert_eq!(log, expected);
+        assert_eq!(log.iter().map(|s| s.0).collect::<Vec<_>>(), vec![3, 9, 3, 9, 3, 9]);
+        assert!(swarm.spike_log().iter().all(|s| s.activation >= swarm.get(s.unit_id).unwrap().theta_base));
+
+        // Ring: only the latest spikes are kept
+        let (small, _) = run(4);
+        assert!(small.spike_log().iter().eq(swarm.spike_log().iter().skip(2)));
+
+        let path = std::env::temp_dir().join(format!("archt3_spikes_{}.csv", std::process::id()));
+        swarm.export_spikes_csv(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("unit_id,time,activation"));
+        let parsed: Vec<SpikeEvent> = lines
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                assert_eq!(fields.len(), 3);
+                SpikeEvent {
+                    unit_id: fields[0].parse().unwrap(),
+                    time: fields[1].parse().unwrap(),
+                    activation: fields[2].parse().unwrap(),
+                }
+            })
+            .collect();
+        assert!(parsed.iter().eq(swarm.spike_log().iter()));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod perception;
+pub mod cortex;
 pub mod meta_cognition;
+pub mod pipeline;
 
 pub use meta_cognition::reflex::{
     ReflexMetrics,
@@ -8,7 +10,19 @@ pub use meta_cognition::reflex::{
 
 pub use cortex::prototypical_neural_unit::{
     PrototypicalNeuralUnit,
+    PnuBuilder,
     TopologyConfig,
+    TransferFn,
+    Integrator,
+    StabilityReport,
     SignatureHandle,
     PNUState,
+    MetabolicState,
+};
+
+pub use cortex::error::CortexError;
+pub use cortex::label::Label;
+pub use cortex::swarm::{
+    GlobalStability, PresentOutcome, PrunePolicy, SettleResult, SpikeEvent, SpikeLog, Swarm, SwarmConfig, SwarmInput,
+    SwarmMetrics, SwarmStats, Winner,
 };
\ No newline at end of file
@@ -1,5 +1,5 @@
 use ArchT3::{
-    PrototypicalNeuralUnit, ReflexMetrics, ReflexConfig, PNUState, SignatureHandle, TopologyConfig,
+    PrototypicalNeuralUnit, ReflexMetrics, ReflexConfig, TopologyConfig,
 };
 
 use std::sync::{Arc, Mutex};
@@ -145,37 +145,13 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ArchT3::cortex::prototypical_neural_unit::LateralLink;
+    use ArchT3::{PnuBuilder, Swarm};
+    use ArchT3::perception::universal_vector::SignatureMetric;
 
     // Helper to create a dummy PNU
     fn create_dummy_pnu(id: usize, coords: Vec<f32>) -> PrototypicalNeuralUnit {
-        PrototypicalNeuralUnit {
-            id,
-            symbolic_label: "Test",
-            state: PNUState { activation: 0.0, derivative: 0.0 },
-            weight_vector: coords.into_boxed_slice(),
-            learning_rate_eta: 0.01,
-            theta_base: 0.5,
-            theta_homeostatic: 0.0,
-            theta_semantic_fatigue: 0.0,
-            activation_budget: 100.0,
-            activation_consumption: 0.0,
-            auto_inhibition_a: 1.0, // Decay rate A
-            a_base: 1.0,
-            gain_modulation_phi: 0.1,
-            shunting_b: 1.0,
-            shunting_c: 0.2,
-            decay_rate: 0.1,
-            lateral_links: Vec::new(),
-            temporal_correlations: Vec::new(),
-            signature_handle: SignatureHandle { signature_segment: vec![], timestamp: 0.0, scene_context_id: 0 },
-            truth_value: 0.0,
-            injection_threshold: 0.8,
-            surprise_sensitivity: 0.1,
-            vigilance_contribution: 0.0,
-            last_spike_time: 0.0,
-            last_surprise_time: 0.0,
-            birth_timestamp: 0.0,
-        }
+        PnuBuilder::new(id).weights(coords).label("Test").build().unwrap()
     }
 
     #[test]
@@ -183,11 +159,10 @@ mod tests {
         // Create 3 PNUs: 
         // 0 and 1 are very close (should excite)
         // 0 and 2 are medium distance (should inhibit - The Crown)
-        let mut swarm = vec![
-            create_dummy_pnu(0, vec![1.0, 1.0]),
-            create_dummy_pnu(1, vec![1.0, 1.1]), // Dist = 0.1
-            create_dummy_pnu(2, vec![1.0, 3.0]), // Dist = 2.0
-        ];
+        let mut swarm = Swarm::default();
+        swarm.push(create_dummy_pnu(0, vec![1.0, 1.0])).unwrap();
+        swarm.push(create_dummy_pnu(1, vec![1.0, 1.1])).unwrap(); // Dist = 0.1
+        swarm.push(create_dummy_pnu(2, vec![1.0, 3.0])).unwrap(); // Dist = 2.0
 
         let config = TopologyConfig {
             sigma_excitation: 0.5,
@@ -196,11 +171,12 @@ mod tests {
             amp_inhibition: 1.0,
             connection_cutoff: 0.01,
             max_neighbors: 10,
+            metric: SignatureMetric::L2,
         };
 
-        wire_swarm_topology(&mut swarm, &config);
+        swarm.wire(&config);
 
-        let pnu0 = &swarm[0];
+        let pnu0 = swarm.get(0).unwrap();
 
         // Find link to PNU 1 (Close neighbor)
         let link_to_1 = pnu0.lateral_links.iter().find(|l| l.target_id == 1).unwrap();
@@ -221,8 +197,8 @@ mod tests {
         pnu.auto_inhibition_a = 1.0;
 
         // Manually add unstable links (Sum > 1.0)
-        pnu.lateral_links.push(LateralLink { target_id: 1, weight: 0.8, plasticity_rate: 0.0 });
-        pnu.lateral_links.push(LateralLink { target_id: 2, weight: -0.8, plasticity_rate: 0.0 }); // Abs = 0.8
+        pnu.lateral_links.push(LateralLink { target_id: 1, weight: 0.8, plasticity_rate: 0.0, prior_weight: 0.8 });
+        pnu.lateral_links.push(LateralLink { target_id: 2, weight: -0.8, plasticity_rate: 0.0, prior_weight: -0.8 }); // Abs = 0.8
 
         // Total Influx = 1.6 > 1.0 (Unstable!)
 
@@ -1,23 +1,322 @@
-#[derive(Clone, Debug)]
+use serde::{Serialize, Deserialize};
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::perception::quantile::{P2Estimator, QuantileTracker};
+
+/// Facteur rendant le MAD cohérent avec l'écart-type d'une gaussienne.
+const MAD_TO_STD: f64 = 1.4826;
+
+/// Fenêtre courte (en échantillons) comparée aux statistiques de long terme par
+/// `drift_score`.
+pub const DEFAULT_DRIFT_WINDOW: usize = 32;
+const DRIFT_CLAMP: f64 = 4.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NormalizerError {
+    /// Le vecteur n'a pas la dimension des statistiques accumulées.
+    DimensionMismatch { expected: usize, got: usize },
+    /// État incohérent (moyenne et M2 de longueurs différentes).
+    InconsistentState { mean: usize, m2: usize },
+    /// Statistiques non fusionnables (seul le mode Cumulative l'est).
+    UnsupportedMode(NormalizerMode),
+    /// Composante non finie (NaN ou infinie) refusée, à l'indice donné.
+    NonFinite { index: usize },
+}
+
+impl fmt::Display for NormalizerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NormalizerError::DimensionMismatch { expected, got } => {
+                write!(f, "Normalizer expects {} dimensions, got {}", expected, got)
+            }
+            NormalizerError::InconsistentState { mean, m2 } => {
+                write!(f, "Inconsistent normalizer state: {} means for {} M2 terms", mean, m2)
+            }
+            NormalizerError::UnsupportedMode(mode) => {
+                write!(f, "Cannot merge normalizer statistics in {:?} mode", mode)
+            }
+            NormalizerError::NonFinite { index } => {
+                write!(f, "Non-finite value at index {}", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NormalizerError {}
+
+/// Ce que le `UniversalScanner` attend de son normaliseur : apprendre en ligne et
+/// transformer chaque échantillon brut avant la transduction.
+pub trait Normalizer {
+    /// Dimension des statistiques (None avant le premier échantillon).
+    fn dim(&self) -> Option<usize>;
+
+    /// Intègre un échantillon observé à l'instant `t` ; refusé sans rien modifier si
+    /// sa dimension ne correspond pas.
+    fn try_update_at(&mut self, values: &[f64], t: f64) -> Result<(), NormalizerError>;
+
+    fn normalize(&self, values: &[f64]) -> Vec<f64>;
+
+    /// Moyenne courante par dimension (vide avant le premier échantillon).
+    fn mean(&self) -> &[f64];
+
+    /// Oublie les statistiques en gardant la configuration.
+    fn reset(&mut self);
+
+    /// Compare l'échantillon aux statistiques courantes, avant `try_update_at`, pour
+    /// la détection de dérive ; appelé même quand l'apprentissage est gelé.
+    fn track_drift(&mut self, _values: &[f64]) {}
+
+    /// Écart entre la fenêtre récente et le long terme, en z-score (0 si non suivi).
+    fn drift_score(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Pondération de l'historique.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum NormalizerMode {
+    /// Welford : tout l'historique a le même poids.
+    #[default]
+    Cumulative,
+    /// Moyenne et variance à oubli exponentiel : le poids d'un échantillon est divisé
+    /// par deux toutes les `half_life` unités de temps (secondes avec `update_at`,
+    /// échantillons avec `update`). Suit la dérive lente d'une ligne de base.
+    Ewma { half_life: f64 },
+    /// (x − médiane) / (1.4826 · MAD), médiane et MAD estimées par P² : quelques
+    /// valeurs aberrantes ne déforment pas l'échelle des échantillons suivants.
+    Robust,
+}
+
+/// Statistiques sérialisables, pour reprendre une calibration. En mode Ewma, `m2`
+/// contient directement la variance.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct NormalizerState {
+    pub count: u64,
+    pub mean: Vec<f64>,
+    pub m2: Vec<f64>,
+    pub initialized: bool,
+    #[serde(default)]
+    pub mode: NormalizerMode,
+    #[serde(default)]
+    pub last_t: Option<f64>,
+    /// Estimateurs (médiane, MAD) par dimension, en mode Robust.
+    #[serde(default)]
+    pub robust: Vec<[P2Estimator; 2]>,
+    /// Dimensions apprises (vide : toutes).
+    #[serde(default)]
+    pub learning_mask: Vec<bool>,
+    /// (moyenne, écart-type) imposés par dimension.
+    #[serde(default)]
+    pub fixed: Vec<Option<(f64, f64)>>,
+    #[serde(default)]
+    pub quantiles: Option<QuantileTracker>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "NormalizerState", into = "NormalizerState")]
 pub struct AdaptiveNormalizer {
     count: u64,
     mean: Vec<f64>,
     m2: Vec<f64>, // Somme des carrés des différences (pour la variance)
     initialized: bool,
+    mode: NormalizerMode,
+    // Instant du dernier échantillon (mode Ewma avec `update_at`)
+    last_t: Option<f64>,
+    // (médiane, MAD) par dimension, en mode Robust
+    robust: Vec<[P2Estimator; 2]>,
+    // Dimensions apprises (vide : toutes) et paramètres imposés par dimension
+    learning_mask: Vec<bool>,
+    fixed: Vec<Option<(f64, f64)>>,
+    // Percentiles courants, si activés
+    quantiles: Option<QuantileTracker>,
+    // Copie saine (count, mean, m2) maintenue dès qu'un échantillon non fini a empoisonné
+    // les statistiques : elle continue d'intégrer les échantillons finis, pour `repair()`.
+    clean: Option<(u64, Vec<f64>, Vec<f64>)>,
+    // Détection de dérive : moyenne exponentielle sur `drift_window` échantillons
+    // et dernier score
+    drift_window: usize,
+    short_mean: Vec<f64>,
+    drift_score: f64,
+}
+
+/// Une étape de Welford sur chaque dimension (`alpha` : None), ou une étape
+/// exponentielle de poids `alpha` (m2 est alors la variance). Les dimensions
+/// exclues par `learn` restent inchangées.
+fn stat_step(count: &mut u64, mean: &mut [f64], m2: &mut [f64], values: &[f64], alpha: Option<f64>, learn: &[bool]) {
+    *count += 1;
+    for (i, x) in values.iter().enumerate() {
+        if !learn.get(i).copied().unwrap_or(true) {
+            continue;
+        }
+        let delta = x - mean[i];
+        match alpha {
+            None => {
+                mean[i] += delta / *count as f64;
+                let delta2 = x - mean[i];
+                m2[i] += delta * delta2;
+            }
+            Some(_) if *count == 1 => {
+                mean[i] = *x;
+                m2[i] = 0.0;
+            }
+            Some(alpha) => {
+                let increment = alpha * delta;
+                mean[i] += increment;
+                m2[i] = (1.0 - alpha) * (m2[i] + delta * increment);
+            }
+        }
+    }
+}
+
+/// Combinaison de Chan et al. : statistiques de l'union de deux échantillons.
+fn chan_merge(count: &mut u64, mean: &mut [f64], m2: &mut [f64], other: (u64, &[f64], &[f64])) {
+    let (n_b, mean_b, m2_b) = other;
+    if n_b == 0 {
+        return;
+    }
+    let (n_a, n_b) = (*count as f64, n_b as f64);
+    let n = n_a + n_b;
+    for i in 0..mean.len() {
+        let delta = mean_b[i] - mean[i];
+        mean[i] += delta * n_b / n;
+        m2[i] += m2_b[i] + delta * delta * n_a * n_b / n;
+    }
+    *count += n_b as u64;
 }
 
 impl AdaptiveNormalizer {
     pub fn new() -> Self {
+        Self::with_mode(NormalizerMode::Cumulative)
+    }
+
+    pub fn with_mode(mode: NormalizerMode) -> Self {
         AdaptiveNormalizer {
             count: 0,
             mean: Vec::new(),
             m2: Vec::new(),
             initialized: false,
+            mode,
+            last_t: None,
+            robust: Vec::new(),
+            learning_mask: Vec::new(),
+            fixed: Vec::new(),
+            quantiles: None,
+            clean: None,
+            drift_window: DEFAULT_DRIFT_WINDOW,
+            short_mean: Vec::new(),
+            drift_score: 0.0,
         }
     }
 
+    pub fn mode(&self) -> NormalizerMode {
+        self.mode
+    }
+
     /// Met à jour les statistiques avec un nouveau vecteur brut
     pub fn update(&mut self, values: &[f64]) {
+        if let Err(e) = self.try_update(values) {
+            panic!("Dimension mismatch in stream: {}", e);
+        }
+    }
+
+    /// Comme `update`, mais un vecteur de mauvaise dimension est refusé sans rien modifier.
+    pub fn try_update(&mut self, values: &[f64]) -> Result<(), NormalizerError> {
+        self.try_update_inner(values, None)
+    }
+
+    /// Comme `try_update`, en mode Ewma l'oubli est calé sur l'instant `t` de
+    /// l'échantillon plutôt que sur le nombre d'échantillons. Identique à
+    /// `try_update` en mode Cumulative.
+    pub fn try_update_at(&mut self, values: &[f64], t: f64) -> Result<(), NormalizerError> {
+        self.try_update_inner(values, Some(t))
+    }
+
+    /// Poids du nouvel échantillon en mode Ewma.
+    fn alpha(&self, t: Option<f64>) -> Option<f64> {
+        match self.mode {
+            NormalizerMode::Cumulative | NormalizerMode::Robust => None,
+            NormalizerMode::Ewma { half_life } => {
+                let elapsed = match (t, self.last_t) {
+                    (Some(t), Some(last)) => (t - last).max(0.0),
+                    _ => 1.0,
+                };
+                if half_life > 0.0 { Some(1.0 - 0.5f64.powf(elapsed / half_life)) } else { Some(1.0) }
+            }
+        }
+    }
+
+    /// Restreint l'apprentissage aux dimensions `mask[i] == true`. Une dimension gelée
+    /// garde ses statistiques ; `normalize` y utilise la moyenne et l'écart-type du
+    /// moment du gel (ou les valeurs de `set_fixed`). Une dimension réactivée reprend
+    /// avec une variance sous-estimée : les échantillons manqués comptent dans `count`.
+    pub fn set_learning_mask(&mut self, mask: Vec<bool>) -> Result<(), NormalizerError> {
+        if let Some(expected) = self.dim().filter(|d| *d != mask.len()) {
+            return Err(NormalizerError::DimensionMismatch { expected, got: mask.len() });
+        }
+        for (i, learns) in mask.iter().enumerate() {
+            if !learns && self.learns(i) && self.count >= 2 && self.fixed.get(i).is_none_or(|f| f.is_none()) {
+                let frozen = (self.center(i), self.scale(i));
+                self.set_fixed(i, frozen.0, frozen.1)?;
+            }
+        }
+        self.learning_mask = mask;
+        Ok(())
+    }
+
+    /// Impose la moyenne et l'écart-type de la dimension `dim` pour `normalize`
+    /// (grandeur déjà calibrée) ; ses statistiques continuent d'être apprises sauf
+    /// si elle est gelée par `set_learning_mask`.
+    pub fn set_fixed(&mut self, dim: usize, mean: f64, std: f64) -> Result<(), NormalizerError> {
+        if let Some(expected) = self.dim().filter(|d| dim >= *d) {
+            return Err(NormalizerError::DimensionMismatch { expected, got: dim + 1 });
+        }
+        if self.fixed.len() <= dim {
+            self.fixed.resize(dim + 1, None);
+        }
+        self.fixed[dim] = Some((mean, std));
+        Ok(())
+    }
+
+    /// Suit en plus les percentiles de chaque dimension (P²) aux probabilités données
+    /// (`DEFAULT_QUANTILES` par exemple), à partir des échantillons suivants.
+    pub fn track_quantiles(&mut self, probabilities: &[f64]) {
+        self.quantiles = Some(QuantileTracker::new(probabilities));
+    }
+
+    pub fn quantiles(&self) -> Option<&QuantileTracker> {
+        self.quantiles.as_ref()
+    }
+
+    /// Quantile `q` estimé de la dimension `dim` (NaN sans suivi ni données).
+    pub fn quantile(&self, dim: usize, q: f64) -> f64 {
+        self.quantiles.as_ref().map_or(f64::NAN, |tracker| tracker.quantile(dim, q))
+    }
+
+    /// Rang estimé de chaque valeur dans [0, 1] (0.95 : au-dessus de 95 % des
+    /// échantillons vus), insensible aux queues lourdes. 0.5 sans suivi ni données.
+    pub fn rank_normalize(&self, values: &[f64]) -> Vec<f64> {
+        values.iter().enumerate()
+            .map(|(i, x)| self.quantiles.as_ref().map_or(0.5, |tracker| tracker.rank(i, *x)))
+            .collect()
+    }
+
+    fn learns(&self, i: usize) -> bool {
+        self.learning_mask.get(i).copied().unwrap_or(true)
+    }
+
+    fn try_update_inner(&mut self, values: &[f64], t: Option<f64>) -> Result<(), NormalizerError> {
+        // Un masque ou des paramètres imposés avant le premier vecteur contraignent sa dimension
+        if !self.initialized {
+            let mask_mismatch = !self.learning_mask.is_empty() && self.learning_mask.len() != values.len();
+            if mask_mismatch || self.fixed.len() > values.len() {
+                let expected = self.learning_mask.len().max(self.fixed.len());
+                return Err(NormalizerError::DimensionMismatch { expected, got: values.len() });
+            }
+        }
         // Initialisation paresseuse (Lazy) basée sur la dimension du premier vecteur
         if !self.initialized {
             self.mean = vec![0.0; values.len()];
@@ -25,41 +324,536 @@ impl AdaptiveNormalizer {
             self.initialized = true;
         }
 
-        assert_eq!(values.len(), self.mean.len(), "Dimension mismatch in stream");
+        if values.len() != self.mean.len() {
+            return Err(NormalizerError::DimensionMismatch { expected: self.mean.len(), got: values.len() });
+        }
+
+        let alpha = self.alpha(t);
+        if values.iter().all(|x| x.is_finite()) {
+            if let Some((count, mean, m2)) = &mut self.clean {
+                stat_step(count, mean, m2, values, alpha, &self.learning_mask);
+            }
+        } else if self.clean.is_none() {
+            self.clean = Some((self.count, self.mean.clone(), self.m2.clone()));
+        }
+
+        // Algorithme de Welford (ou pondération exponentielle) pour chaque dimension
+        stat_step(&mut self.count, &mut self.mean, &mut self.m2, values, alpha, &self.learning_mask);
+        if self.mode == NormalizerMode::Robust {
+            if self.robust.len() != values.len() {
+                self.robust = vec![[P2Estimator::new(0.5), P2Estimator::new(0.5)]; values.len()];
+            }
+            // Le MAD suit l'écart à la médiane courante ; P² ignore les valeurs non finies
+            for (i, ([median, mad], x)) in self.robust.iter_mut().zip(values).enumerate() {
+                if !self.learning_mask.get(i).copied().unwrap_or(true) {
+                    continue;
+                }
+                median.update(*x);
+                mad.update((x - median.estimate()).abs());
+            }
+        }
+        if let Some(quantiles) = &mut self.quantiles {
+            // Les dimensions gelées sont masquées par NaN, que le suivi ignore
+            let learned: Vec<f64> = values.iter().enumerate()
+                .map(|(i, x)| if self.learning_mask.get(i).copied().unwrap_or(true) { *x } else { f64::NAN })
+                .collect();
+            quantiles.update(&learned);
+        }
+        if t.is_some() {
+            self.last_t = t;
+        }
+        Ok(())
+    }
+
+    /// Statistiques d'un jeu de données complet.
+    pub fn fit(dataset: &[Vec<f64>]) -> Result<Self, NormalizerError> {
+        let mut normalizer = Self::new();
+        for values in dataset {
+            normalizer.try_update(values)?;
+        }
+        Ok(normalizer)
+    }
+
+    /// Intègre les statistiques de `other` (calculées sur d'autres données), comme si
+    /// ses échantillons avaient été vus ici. Mode Cumulative uniquement.
+    pub fn merge(&mut self, other: &AdaptiveNormalizer) -> Result<(), NormalizerError> {
+        for mode in [self.mode, other.mode] {
+            if mode != NormalizerMode::Cumulative {
+                return Err(NormalizerError::UnsupportedMode(mode));
+            }
+        }
+        if !other.initialized || other.count == 0 {
+            return Ok(());
+        }
+        if !self.initialized {
+            self.mean = vec![0.0; other.mean.len()];
+            self.m2 = vec![0.0; other.mean.len()];
+            self.initialized = true;
+        }
+        if self.mean.len() != other.mean.len() {
+            return Err(NormalizerError::DimensionMismatch { expected: self.mean.len(), got: other.mean.len() });
+        }
+
+        // La copie saine intègre la version saine de l'autre
+        if let Some((count, mean, m2)) = &mut self.clean {
+            let (n, other_mean, other_m2) = match &other.clean {
+                Some((n, mean, m2)) => (*n, mean, m2),
+                None => (other.count, &other.mean, &other.m2),
+            };
+            chan_merge(count, mean, m2, (n, other_mean, other_m2));
+        } else if let Some(other_clean) = &other.clean {
+            let mut clean = (self.count, self.mean.clone(), self.m2.clone());
+            chan_merge(&mut clean.0, &mut clean.1, &mut clean.2, (other_clean.0, &other_clean.1, &other_clean.2));
+            self.clean = Some(clean);
+        }
+        chan_merge(&mut self.count, &mut self.mean, &mut self.m2, (other.count, &other.mean, &other.m2));
+        Ok(())
+    }
+
+    /// Dimension des statistiques (None avant le premier échantillon).
+    pub fn dim(&self) -> Option<usize> {
+        self.initialized.then_some(self.mean.len())
+    }
+
+    pub fn to_state(&self) -> NormalizerState {
+        NormalizerState {
+            count: self.count,
+            mean: self.mean.clone(),
+            m2: self.m2.clone(),
+            initialized: self.initialized,
+            mode: self.mode,
+            last_t: self.last_t,
+            robust: self.robust.clone(),
+            learning_mask: self.learning_mask.clone(),
+            fixed: self.fixed.clone(),
+            quantiles: self.quantiles.clone(),
+        }
+    }
+
+    pub fn from_state(state: NormalizerState) -> Result<Self, NormalizerError> {
+        if state.mean.len() != state.m2.len() {
+            return Err(NormalizerError::InconsistentState { mean: state.mean.len(), m2: state.m2.len() });
+        }
+        if !state.robust.is_empty() && state.robust.len() != state.mean.len() {
+            return Err(NormalizerError::InconsistentState { mean: state.mean.len(), m2: state.robust.len() });
+        }
+        Ok(AdaptiveNormalizer {
+            count: state.count,
+            mean: state.mean,
+            m2: state.m2,
+            initialized: state.initialized,
+            mode: state.mode,
+            last_t: state.last_t,
+            robust: state.robust,
+            learning_mask: state.learning_mask,
+            fixed: state.fixed,
+            quantiles: state.quantiles,
+            clean: None,
+            drift_window: DEFAULT_DRIFT_WINDOW,
+            short_mean: Vec::new(),
+            drift_score: 0.0,
+        })
+    }
+
+    /// Sauvegarde l'état en JSON.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, &self.to_state()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        writer.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let state: NormalizerState = serde_json::from_reader(BufReader::new(File::open(path)?))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Self::from_state(state).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
 
-        self.count += 1;
+    /// Échantillons intégrés aux statistiques.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Moyenne courante par dimension (vide avant le premier échantillon).
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
 
-        // Algorithme de Welford pour chaque dimension
-        for (i, x) in values.iter().enumerate() {
-            let delta = x - self.mean[i];
-            self.mean[i] += delta / self.count as f64;
-            let delta2 = x - self.mean[i];
-            self.m2[i] += delta * delta2;
+    /// Échelle utilisée par `normalize` pour chaque dimension : écart-type, ou
+    /// 1.4826 · MAD en mode Robust, ou valeur imposée (`set_fixed`, gel). Nulle
+    /// tant que count < 2.
+    pub fn std_dev(&self) -> Vec<f64> {
+        (0..self.mean.len()).map(|i| match self.fixed.get(i).copied().flatten() {
+            Some((_, std_dev)) => std_dev,
+            None if self.count < 2 => 0.0,
+            None => self.scale(i),
+        }).collect()
+    }
+
+    /// Longueur de la fenêtre récente de `drift_score` ; remet le suivi à zéro.
+    pub fn set_drift_window(&mut self, window: usize) {
+        assert!(window > 0, "Drift window must be positive");
+        self.drift_window = window;
+        self.short_mean.clear();
+        self.drift_score = 0.0;
+    }
+
+    pub fn drift_window(&self) -> usize {
+        self.drift_window
+    }
+
+    /// Met à jour la moyenne récente (exponentielle, `drift_window` échantillons) et le
+    /// score : plus grand |moyenne récente − centre| / (échelle / √fenêtre) sur les
+    /// dimensions, un z-test de la moyenne récente contre les statistiques de long
+    /// terme. Rien n'est suivi avant `drift_window` échantillons appris.
+    pub fn track_drift(&mut self, values: &[f64]) {
+        if self.count < self.drift_window as u64 || values.len() != self.mean.len() {
+            return;
+        }
+        if self.short_mean.len() != values.len() {
+            self.short_mean = (0..values.len()).map(|i| self.center(i)).collect();
+        }
+        let alpha = 2.0 / (self.drift_window as f64 + 1.0);
+        let sqrt_window = (self.drift_window as f64).sqrt();
+        let mut score: f64 = 0.0;
+        for (i, x) in values.iter().enumerate().filter(|(_, x)| x.is_finite()) {
+            let (center, scale) = (self.center(i), self.scale(i));
+            if scale <= 1e-9 {
+                continue;
+            }
+            // Écart borné à ±DRIFT_CLAMP échelles : un pic isolé n'est pas une dérive
+            let x = x.clamp(center - DRIFT_CLAMP * scale, center + DRIFT_CLAMP * scale);
+            self.short_mean[i] += alpha * (x - self.short_mean[i]);
+            score = score.max((self.short_mean[i] - center).abs() * sqrt_window / scale);
+        }
+        self.drift_score = score;
+    }
+
+    pub fn drift_score(&self) -> f64 {
+        self.drift_score
+    }
+
+    fn center(&self, i: usize) -> f64 {
+        match (self.mode, self.robust.get(i)) {
+            (NormalizerMode::Robust, Some([median, _])) => median.estimate(),
+            _ => self.mean[i],
+        }
+    }
+
+    fn scale(&self, i: usize) -> f64 {
+        match self.mode {
+            NormalizerMode::Robust => self.robust.get(i).map_or(0.0, |[_, mad]| MAD_TO_STD * mad.estimate()),
+            // Directement M2 en mode Ewma
+            NormalizerMode::Ewma { .. } => self.m2[i].sqrt(),
+            // Variance = M2 / (count - 1)
+            NormalizerMode::Cumulative => (self.m2[i] / (self.count - 1) as f64).sqrt(),
+        }
+    }
+
+    /// Vrai si une moyenne ou une variance est devenue NaN/infinie (échantillon non fini).
+    pub fn is_poisoned(&self) -> bool {
+        self.mean.iter().chain(self.m2.iter()).any(|x| !x.is_finite())
+    }
+
+    /// Restaure des statistiques calculées sans les échantillons non finis.
+    /// Retourne false s'il n'y avait rien à réparer.
+    pub fn repair(&mut self) -> bool {
+        match self.clean.take() {
+            Some((count, mean, m2)) => {
+                self.count = count;
+                self.mean = mean;
+                self.m2 = m2;
+                true
+            }
+            None => false,
         }
     }
 
     /// Transforme le vecteur brut en Z-Score : (x - mean) / std_dev
     pub fn normalize(&self, values: &[f64]) -> Vec<f64> {
-        if self.count < 2 {
-            // Pas assez de données pour la variance, on retourne centré ou brut
-            // Ici on retourne brut pour ne pas casser le début du signal
-            return values.to_vec(); 
+        values.iter().enumerate().map(|(i, x)| {
+            let (center, std_dev) = match self.fixed.get(i).copied().flatten() {
+                Some(fixed) => fixed,
+                // Pas assez de données pour la variance (ou dimension gelée avant) :
+                // on retourne brut pour ne pas casser le début du signal
+                None if self.count < 2 || !self.learns(i) => return *x,
+                None => (self.center(i), self.scale(i)),
+            };
+            // Si la variance est nulle (signal constant), on renvoie 0.0
+            if std_dev > 1e-9 { (x - center) / std_dev } else { 0.0 }
+        }).collect()
+    }
+}
+
+impl Normalizer for AdaptiveNormalizer {
+    fn dim(&self) -> Option<usize> {
+        AdaptiveNormalizer::dim(self)
+    }
+
+    fn try_update_at(&mut self, values: &[f64], t: f64) -> Result<(), NormalizerError> {
+        AdaptiveNormalizer::try_update_at(self, values, t)
+    }
+
+    fn normalize(&self, values: &[f64]) -> Vec<f64> {
+        AdaptiveNormalizer::normalize(self, values)
+    }
+
+    fn mean(&self) -> &[f64] {
+        AdaptiveNormalizer::mean(self)
+    }
+
+    /// Statistiques oubliées ; le mode, le masque, les paramètres imposés, les
+    /// probabilités suivies et la fenêtre de dérive sont conservés.
+    fn reset(&mut self) {
+        let mut fresh = Self::with_mode(self.mode);
+        fresh.drift_window = self.drift_window;
+        fresh.learning_mask = std::mem::take(&mut self.learning_mask);
+        fresh.fixed = std::mem::take(&mut self.fixed);
+        fresh.quantiles = self.quantiles.as_ref().map(|tracker| QuantileTracker::new(tracker.probabilities()));
+        *self = fresh;
+    }
+
+    fn track_drift(&mut self, values: &[f64]) {
+        AdaptiveNormalizer::track_drift(self, values)
+    }
+
+    fn drift_score(&self) -> f64 {
+        AdaptiveNormalizer::drift_score(self)
+    }
+}
+
+
+impl TryFrom<NormalizerState> for AdaptiveNormalizer {
+    type Error = NormalizerError;
+
+    fn try_from(state: NormalizerState) -> Result<Self, Self::Error> {
+        Self::from_state(state)
+    }
+}
+
+impl From<AdaptiveNormalizer> for NormalizerState {
+    fn from(normalizer: AdaptiveNormalizer) -> Self {
+        normalizer.to_state()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poison_and_repair() {
+        let mut poisoned = AdaptiveNormalizer::new();
+        let mut reference = AdaptiveNormalizer::new();
+        for i in 0..20 {
+            let x = vec![i as f64, (i as f64).sin()];
+            poisoned.update(&x);
+            reference.update(&x);
+            if i == 10 {
+                poisoned.update(&[f64::NAN, 1.0]);
+            }
         }
+        assert!(poisoned.is_poisoned());
+        assert!(!reference.is_poisoned());
 
-        let mut normalized = Vec::with_capacity(values.len());
+        assert!(poisoned.repair());
+        assert!(!poisoned.is_poisoned());
+        assert_eq!(poisoned.normalize(&[3.0, 0.5]), reference.normalize(&[3.0, 0.5]));
+        assert!(!poisoned.repair());
+    }
 
-        for (i, x) in values.iter().enumerate() {
-            // Variance = M2 / (count - 1)
-            let variance = self.m2[i] / (self.count - 1) as f64;
-            let std_dev = variance.sqrt();
+    #[test]
+    fn test_state_round_trip() {
+        let mut calibrated = AdaptiveNormalizer::new();
+        for i in 0..500 {
+            let t = i as f64 * 0.01;
+            calibrated.update(&[20.0 + (t * 3.0).sin(), t * 1e-3]);
+        }
+        let path = std::env::temp_dir().join(format!("archt3_normalizer_{}.json", std::process::id()));
+        calibrated.save(&path).unwrap();
+        let restored = AdaptiveNormalizer::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let probe = [20.7, 0.003];
+        assert_eq!(restored.normalize(&probe), calibrated.normalize(&probe));
+        assert_eq!(restored.to_state(), calibrated.to_state());
+        let json = serde_json::to_string(&calibrated).unwrap();
+        assert_eq!(serde_json::from_str::<AdaptiveNormalizer>(&json).unwrap().normalize(&probe), calibrated.normalize(&probe));
+
+        // Mauvaise dimension : erreur, pas de panique, état intact
+        let mut wrong = AdaptiveNormalizer::from_state(calibrated.to_state()).unwrap();
+        assert_eq!(wrong.try_update(&[1.0, 2.0, 3.0]), Err(NormalizerError::DimensionMismatch { expected: 2, got: 3 }));
+        assert_eq!(wrong.to_state(), calibrated.to_state());
+        let broken = NormalizerState { count: 3, mean: vec![0.0; 2], m2: vec![0.0; 3], initialized: true, ..Default::default() };
+        assert!(AdaptiveNormalizer::from_state(broken).is_err());
+    }
 
-            if std_dev > 1e-9 {
-                normalized.push((x - self.mean[i]) / std_dev);
-            } else {
-                // Si la variance est nulle (signal constant), on renvoie 0.0
-                normalized.push(0.0);
+    #[test]
+    fn test_ewma_tracks_baseline_shift() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(8);
+        let mut cumulative = AdaptiveNormalizer::new();
+        let mut ewma = AdaptiveNormalizer::with_mode(NormalizerMode::Ewma { half_life: 5.0 });
+
+        // 100 Hz pendant 200 s ; la ligne de base passe de 0 à 10 à mi-parcours
+        let mut z = (Vec::new(), Vec::new());
+        for i in 0..20_000 {
+            let t = i as f64 * 0.01;
+            let x = [if t < 100.0 { 0.0 } else { 10.0 } + rng.gen_range(-1.7..1.7)];
+            cumulative.try_update_at(&x, t).unwrap();
+            ewma.try_update_at(&x, t).unwrap();
+            // Quatre demi-vies après la rupture
+            if t > 120.0 {
+                z.0.push(cumulative.normalize(&x)[0]);
+                z.1.push(ewma.normalize(&x)[0]);
             }
         }
-        normalized
+        let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+        assert!(mean(&z.1).abs() < 0.2, "ewma {}", mean(&z.1));
+        assert!(mean(&z.0) > 0.5, "cumulative {}", mean(&z.0));
+
+        // Le mode Cumulative ignore les instants
+        let mut plain = AdaptiveNormalizer::new();
+        let mut timed = AdaptiveNormalizer::new();
+        for i in 0..50 {
+            plain.update(&[i as f64]);
+            timed.try_update_at(&[i as f64], i as f64 * 3.0).unwrap();
+        }
+        assert_eq!(plain.normalize(&[7.0]), timed.normalize(&[7.0]));
+    }
+
+    #[test]
+    fn test_robust_ignores_spikes() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(21);
+        // Gaussienne unitaire par Box-Muller
+        let mut gaussian = || {
+            let (u, v): (f64, f64) = (rng.gen_range(f64::EPSILON..1.0), rng.gen_range(0.0..1.0));
+            (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+        };
+        let mut cumulative = AdaptiveNormalizer::new();
+        let mut robust = AdaptiveNormalizer::with_mode(NormalizerMode::Robust);
+
+        for i in 0..5000 {
+            // Trois pointes à 100 sigmas
+            let x = [if matches!(i, 1000 | 1001 | 2500) { 100.0 } else { gaussian() }];
+            cumulative.update(&x);
+            robust.update(&x);
+        }
+
+        // Un échantillon à 2 sigmas reste à ~2 en Robust, écrasé en Cumulative
+        let z_robust = robust.normalize(&[2.0])[0];
+        let z_cumulative = cumulative.normalize(&[2.0])[0];
+        assert!((z_robust - 2.0).abs() < 0.2, "robust {}", z_robust);
+        assert!(z_cumulative < 1.0, "cumulative {}", z_cumulative);
+
+        let restored = AdaptiveNormalizer::from_state(robust.to_state()).unwrap();
+        assert_eq!(restored.normalize(&[2.0]), robust.normalize(&[2.0]));
+    }
+
+    #[test]
+    fn test_merge_matches_fit() {
+        let dataset: Vec<Vec<f64>> = (0..1000)
+            .map(|i| vec![(i as f64 * 0.37).sin() * 3.0 + 1.0, (i as f64 * 0.011).cos(), i as f64])
+            .collect();
+        let whole = AdaptiveNormalizer::fit(&dataset).unwrap();
+        let mut merged = AdaptiveNormalizer::fit(&dataset[..321]).unwrap();
+        merged.merge(&AdaptiveNormalizer::fit(&dataset[321..]).unwrap()).unwrap();
+
+        let probe = [0.5, -0.2, 400.0];
+        assert_eq!(merged.count, whole.count);
+        for (a, b) in merged.normalize(&probe).iter().zip(whole.normalize(&probe)) {
+            assert!((a - b).abs() < 1e-9);
+        }
+
+        // Fusion avec un normaliseur vide, dans les deux sens
+        let before = merged.to_state();
+        merged.merge(&AdaptiveNormalizer::new()).unwrap();
+        assert_eq!(merged.to_state(), before);
+        let mut empty = AdaptiveNormalizer::new();
+        empty.merge(&merged).unwrap();
+        assert_eq!(empty.to_state(), before);
+
+        let other_dim = AdaptiveNormalizer::fit(&[vec![1.0, 2.0], vec![2.0, 3.0]]).unwrap();
+        assert_eq!(merged.merge(&other_dim), Err(NormalizerError::DimensionMismatch { expected: 3, got: 2 }));
+        assert_eq!(merged.to_state(), before);
+    }
+
+    #[test]
+    fn test_learning_mask_freezes_dimensions() {
+        let sample = |i: usize| vec![12.0 + (i as f64 * 0.1).sin(), (i as f64 * 0.3).cos() * 4.0, i as f64];
+        let mut normalizer = AdaptiveNormalizer::new();
+        assert!(normalizer.set_fixed(0, 12.0, 0.5).is_ok());
+        for i in 0..50 {
+            normalizer.update(&sample(i));
+        }
+        assert_eq!(normalizer.set_learning_mask(vec![true, false]), Err(NormalizerError::DimensionMismatch { expected: 3, got: 2 }));
+        normalizer.set_learning_mask(vec![true, false, true]).unwrap();
+
+        let frozen = (normalizer.mean()[1], normalizer.std_dev()[1], normalizer.normalize(&[0.0, 1.0, 0.0])[1]);
+        let learning = normalizer.mean()[2];
+        for i in 50..500 {
+            normalizer.update(&sample(i));
+        }
+        // La dimension gelée garde ses statistiques et sa normalisation, les autres apprennent
+        assert_eq!((normalizer.mean()[1], normalizer.std_dev()[1], normalizer.normalize(&[0.0, 1.0, 0.0])[1]), frozen);
+        assert!(normalizer.mean()[2] > learning + 100.0);
+        // Paramètres imposés : la dimension 0 est normalisée par (12, 0.5)
+        assert_eq!(normalizer.normalize(&[13.0, 0.0, 0.0])[0], 2.0);
+
+        let restored = AdaptiveNormalizer::from_state(normalizer.to_state()).unwrap();
+        assert_eq!(restored.normalize(&sample(3)), normalizer.normalize(&sample(3)));
+        assert!(normalizer.set_fixed(3, 0.0, 1.0).is_err());
+
+        // Un masque posé avant le premier vecteur en fixe la dimension
+        let mut masked = AdaptiveNormalizer::new();
+        masked.set_learning_mask(vec![false, true]).unwrap();
+        assert!(masked.try_update(&[1.0, 2.0, 3.0]).is_err());
+        masked.update(&[1.0, 2.0]);
+        assert_eq!(masked.mean(), &[0.0, 2.0]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_rank_normalize_with_quantiles() {
+        use crate::perception::quantile::DEFAULT_QUANTILES;
+        let mut normalizer = AdaptiveNormalizer::new();
+        assert!(normalizer.quantile(0, 0.5).is_nan());
+        normalizer.track_quantiles(&DEFAULT_QUANTILES);
+        // Valeurs 0..1000 dans un ordre mélangé
+        for i in 0..1000u64 {
+            normalizer.update(&[((i * 7919) % 1000) as f64]);
+        }
+
+        assert!((normalizer.quantile(0, 0.95) - 950.0).abs() < 20.0);
+        let ranks: Vec<f64> = [-5.0, 500.0, 960.0, 2000.0].iter().map(|x| normalizer.rank_normalize(&[*x])[0]).collect();
+        assert_eq!((ranks[0], ranks[3]), (0.0, 1.0));
+        assert!((ranks[1] - 0.5).abs() < 0.02 && ranks[2] > 0.95);
+
+        let restored = AdaptiveNormalizer::from_state(normalizer.to_state()).unwrap();
+        assert_eq!(restored.rank_normalize(&[500.0]), normalizer.rank_normalize(&[500.0]));
+    }
+
+    #[test]
+    fn test_reset_keeps_configuration() {
+        use crate::perception::quantile::DEFAULT_QUANTILES;
+        let mut normalizer = AdaptiveNormalizer::with_mode(NormalizerMode::Robust);
+        normalizer.set_fixed(0, 10.0, 2.0).unwrap();
+        normalizer.set_learning_mask(vec![true, false]).unwrap();
+        normalizer.track_quantiles(&DEFAULT_QUANTILES);
+        for i in 0..100 {
+            normalizer.update(&[i as f64, 5.0]);
+        }
+
+        Normalizer::reset(&mut normalizer);
+        assert_eq!((normalizer.dim(), normalizer.count()), (None, 0));
+        assert_eq!(normalizer.mode(), NormalizerMode::Robust);
+        assert_eq!(normalizer.quantiles().map(|q| q.probabilities()), Some(&DEFAULT_QUANTILES[..]));
+        assert!(normalizer.quantile(0, 0.5).is_nan());
+        // Masque et paramètres imposés : même dimension exigée, dimension 0 toujours (10, 2)
+        assert!(normalizer.try_update(&[1.0, 2.0, 3.0]).is_err());
+        normalizer.update(&[1.0, 2.0]);
+        normalizer.update(&[3.0, 4.0]);
+        assert_eq!(normalizer.mean()[1], 0.0);
+        assert_eq!(normalizer.normalize(&[14.0, 0.0])[0], 2.0);
+    }
+}
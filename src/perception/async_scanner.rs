@@ -0,0 +1,138 @@
+use crate::perception::adaptive_normalizer::{AdaptiveNormalizer, Normalizer};
+use crate::perception::error::PerceptionError;
+use crate::perception::source::UniversalSource;
+use crate::perception::universal_scanner::{ScannerConfig, UniversalScanner};
+use crate::perception::universal_vector::UniversalVector;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time::{Instant, Interval};
+
+/// `UniversalScanner` pour une application asynchrone (feature `async`) : les lots
+/// complets sont mis en file par `ingest` et récupérés par `next_batch`, sans thread
+/// dédié. Avec `max_batch_age`, `next_batch` vide le lot trop ancien au rythme d'un
+/// `tokio::time::interval`, l'âge étant mesuré sur l'horloge du flux prolongée par
+/// le temps écoulé depuis le dernier échantillon.
+pub struct AsyncScanner<N: Normalizer = AdaptiveNormalizer> {
+    scanner: UniversalScanner<N>,
+    sender: UnboundedSender<Vec<UniversalVector>>,
+    receiver: UnboundedReceiver<Vec<UniversalVector>>,
+    // Créé au premier besoin (il faut un runtime tokio)
+    interval: Option<Interval>,
+    // Instant réel et instant du flux du dernier échantillon ingéré
+    clock: Option<(Instant, f64)>,
+}
+
+impl AsyncScanner {
+    pub fn new(batch_size: usize) -> Self {
+        Self::from_scanner(UniversalScanner::new(batch_size))
+    }
+
+    pub fn with_config(config: ScannerConfig) -> Self {
+        Self::from_scanner(UniversalScanner::with_config(config))
+    }
+}
+
+impl<N: Normalizer> AsyncScanner<N> {
+    pub fn from_scanner(scanner: UniversalScanner<N>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        AsyncScanner { scanner, sender, receiver, interval: None, clock: None }
+    }
+
+    pub fn scanner(&self) -> &UniversalScanner<N> {
+        &self.scanner
+    }
+
+    pub fn scanner_mut(&mut self) -> &mut UniversalScanner<N> {
+        &mut self.scanner
+    }
+
+    pub fn into_inner(self) -> UniversalScanner<N> {
+        self.scanner
+    }
+
+    /// Comme `UniversalScanner::ingest` ; un lot complet est transduit et mis en file.
+    pub async fn ingest<T: UniversalSource>(&mut self, data: &T) -> Result<(), PerceptionError> {
+        self.scanner.ingest(data)?;
+        self.clock = Some((Instant::now(), data.timestamp()));
+        if self.scanner.is_ready() {
+            let batch = self.scanner.process_and_flush();
+            self.enqueue(batch);
+        }
+        Ok(())
+    }
+
+    /// Fin du flux : le reste est transduit et mis en file (`UniversalScanner::drain`).
+    pub async fn drain(&mut self) {
+        let batch = self.scanner.drain();
+        self.enqueue(batch);
+    }
+
+    /// Prochain lot en file. Sinon, avec `max_batch_age`, attend que le lot en cours
+    /// soit assez vieux pour être vidé. `None` quand rien ne peut plus produire de
+    /// lot sans nouvel échantillon.
+    pub async fn next_batch(&mut self) -> Option<Vec<UniversalVector>> {
+        loop {
+            if let Ok(batch) = self.receiver.try_recv() {
+                return Some(batch);
+            }
+            let max_age = self.scanner.config().max_batch_age?;
+            let (last_ingest, last_t) = self.clock?;
+            if self.scanner.pending_samples() == 0 {
+                return None;
+            }
+
+            self.interval.get_or_insert_with(|| tokio::time::interval(max_age)).tick().await;
+            let elapsed = last_ingest.elapsed();
+            if let Some(batch) = self.scanner.poll(last_t + elapsed.as_secs_f64()) {
+                self.enqueue(batch);
+            } else if elapsed >= max_age {
+                // Lot déjà assez vieux mais trop court pour former un segment
+                return None;
+            }
+        }
+    }
+
+    fn enqueue(&mut self, batch: Vec<UniversalVector>) {
+        if !batch.is_empty() {
+            // Le récepteur vit aussi longtemps que l'émetteur
+            let _ = self.sender.send(batch);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::source::Sample;
+
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_async_ingest_from_stream() {
+        let (tx, mut stream) = mpsc::channel(16);
+        tokio::spawn(async move {
+            for i in 0..250 {
+                let t = i as f64 * 0.01;
+                tx.send(Sample::new(t, vec![(t * 3.0).sin(), (t * 1.7).cos()])).await.unwrap();
+            }
+        });
+
+        let config = ScannerConfig { max_batch_age: Some(Duration::from_millis(20)), ..ScannerConfig::new(100) };
+        let mut scanner = AsyncScanner::with_config(config);
+        while let Some(sample) = stream.recv().await {
+            scanner.ingest(&sample).await.unwrap();
+        }
+
+        let first = scanner.next_batch().await.unwrap();
+        let second = scanner.next_batch().await.unwrap();
+        assert!(!first.is_empty() && !second.is_empty());
+        assert!(first.last().unwrap().metadata.timestamp <= second[0].metadata.timestamp);
+
+        // Les 50 derniers échantillons sont vidés par l'horloge, puis plus rien
+        let stale = scanner.next_batch().await.unwrap();
+        assert!(!stale.is_empty());
+        assert!(scanner.next_batch().await.is_none());
+        assert_eq!(scanner.scanner().stats().ingested, 250);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::perception::adaptive_normalizer::NormalizerError;
+use crate::perception::universal_transducer::TransducerError;
+
+use std::fmt;
+use std::io;
+
+/// Erreur de la chaîne de perception (ingestion, normalisation, transduction).
+#[derive(Debug, Clone, PartialEq)]
+pub enum PerceptionError {
+    /// Échantillon contenant une valeur NaN ou infinie (politique `NanPolicy::RecordError`).
+    NonFinite { t: f64 },
+    /// Tampon plein (`OverflowPolicy::ErrorOnOverflow`) : l'échantillon est refusé.
+    BufferOverflow { capacity: usize },
+    /// Trame réseau de taille inattendue (octets reçus contre octets attendus).
+    MalformedFrame { expected: usize, got: usize },
+    /// Erreur d'entrée/sortie d'une source (socket fermée, connexion perdue...),
+    /// avec le message du système.
+    Io { kind: io::ErrorKind, message: String },
+    Normalizer(NormalizerError),
+    Transducer(TransducerError),
+}
+
+impl fmt::Display for PerceptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PerceptionError::NonFinite { t } => write!(f, "Non-finite sample at t={}", t),
+            PerceptionError::BufferOverflow { capacity } => write!(f, "Scanner buffer is full ({} samples)", capacity),
+            PerceptionError::MalformedFrame { expected, got } => write!(f, "Malformed frame: expected {} bytes, got {}", expected, got),
+            PerceptionError::Io { message, .. } => write!(f, "I/O error: {}", message),
+            PerceptionError::Normalizer(e) => write!(f, "Normalizer: {}", e),
+            PerceptionError::Transducer(e) => write!(f, "Transducer: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PerceptionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PerceptionError::NonFinite { .. }
+            | PerceptionError::BufferOverflow { .. }
+            | PerceptionError::MalformedFrame { .. }
+            | PerceptionError::Io { .. } => None,
+            PerceptionError::Normalizer(e) => Some(e),
+            PerceptionError::Transducer(e) => Some(e),
+        }
+    }
+}
+
+impl From<NormalizerError> for PerceptionError {
+    fn from(e: NormalizerError) -> Self {
+        PerceptionError::Normalizer(e)
+    }
+}
+
+impl From<TransducerError> for PerceptionError {
+    fn from(e: TransducerError) -> Self {
+        PerceptionError::Transducer(e)
+    }
+}
+
+impl From<io::Error> for PerceptionError {
+    fn from(e: io::Error) -> Self {
+        PerceptionError::Io { kind: e.kind(), message: e.to_string() }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_io_keeps_the_message() {
+        let err = PerceptionError::from(io::Error::new(io::ErrorKind::ConnectionReset, "peer went away"));
+        assert_eq!(err, PerceptionError::Io { kind: io::ErrorKind::ConnectionReset, message: "peer went away".to_string() });
+        assert_eq!(err.to_string(), "I/O error: peer went away");
+    }
+}
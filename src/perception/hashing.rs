@@ -0,0 +1,160 @@
+use crate::perception::universal_vector::Signature;
+
+use std::collections::HashMap;
+
+/// Hachage LSH par hyperplans aléatoires (SimHash) sur la signature aplatie.
+/// Deux signatures proches en cosinus partagent la plupart des bits de leur clé.
+///
+/// Les hyperplans sont dérivés de la graine de manière déterministe, composante par
+/// composante : aucune matrice n'est stockée et la dimension n'a pas à être connue à l'avance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureHasher {
+    num_planes: u32,
+    seed: u64,
+}
+
+/// SplitMix64 : générateur sans état, suffisant pour tirer les coefficients des hyperplans.
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+impl SignatureHasher {
+    pub fn new(num_planes: u32, seed: u64) -> Self {
+        assert!((1..=64).contains(&num_planes), "num_planes must be in 1..=64");
+        SignatureHasher { num_planes, seed }
+    }
+
+    pub fn num_planes(&self) -> u32 {
+        self.num_planes
+    }
+
+    /// Coefficient `index` de l'hyperplan `plane`, uniforme dans [-1, 1].
+    fn coefficient(&self, plane: u32, index: usize) -> f64 {
+        let h = splitmix64(self.seed ^ splitmix64(((plane as u64) << 32) ^ index as u64));
+        (h >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    }
+
+    pub fn key(&self, sig: &Signature) -> u64 {
+        let flat = sig.flatten();
+        let mut key = 0u64;
+        for plane in 0..self.num_planes {
+            let projection: f64 = flat.iter()
+                .enumerate()
+                .map(|(i, x)| x * self.coefficient(plane, i))
+                .sum();
+            if projection >= 0.0 {
+                key |= 1 << plane;
+            }
+        }
+        key
+    }
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Index approximatif : clé LSH -> identifiants (concepts, PNUs...).
+#[derive(Debug, Clone)]
+pub struct LshIndex {
+    hasher: SignatureHasher,
+    buckets: HashMap<u64, Vec<usize>>,
+}
+
+impl LshIndex {
+    pub fn new(hasher: SignatureHasher) -> Self {
+        LshIndex { hasher, buckets: HashMap::new() }
+    }
+
+    pub fn insert(&mut self, id: usize, sig: &Signature) {
+        self.buckets.entry(self.hasher.key(sig)).or_default().push(id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Identifiants des seaux à distance de Hamming ≤ `radius` de la clé de `sig`.
+    /// Le coût croît comme C(num_planes, radius) : garder un rayon de 1 ou 2.
+    pub fn candidates(&self, sig: &Signature, radius: u32) -> Vec<usize> {
+        let key = self.hasher.key(sig);
+        let mut found = Vec::new();
+        self.probe(key, 0, radius, &mut found);
+        found
+    }
+
+    fn probe(&self, key: u64, from_bit: u32, radius: u32, found: &mut Vec<usize>) {
+        if let Some(ids) = self.buckets.get(&key) {
+            found.extend_from_slice(ids);
+        }
+        if radius == 0 {
+            return;
+        }
+        for bit in from_bit..self.hasher.num_planes {
+            self.probe(key ^ (1 << bit), bit + 1, radius - 1, found);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    fn noisy_path(center: &[(f64, Vec<f64>)], noise: f64, rng: &mut impl Rng) -> Signature {
+        center.iter().fold(Signature::zero(3), |acc, (dt, dx)| {
+            let dx: Vec<f64> = dx.iter().map(|x| x + rng.gen_range(-noise..=noise)).collect();
+            acc.combine(&Signature::from_segment(*dt, &dx))
+        })
+    }
+
+    #[test]
+    fn test_hasher_is_deterministic() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let center: Vec<(f64, Vec<f64>)> = (0..5).map(|_| (0.1, vec![rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)])).collect();
+        let sig = noisy_path(&center, 0.0, &mut rng);
+
+        let a = SignatureHasher::new(16, 42);
+        let b = SignatureHasher::new(16, 42);
+        assert_eq!(a.key(&sig), b.key(&sig));
+        assert!(a.key(&sig) < 1 << 16);
+        assert_ne!(SignatureHasher::new(32, 7).key(&sig), SignatureHasher::new(32, 8).key(&sig));
+        assert_eq!(hamming_distance(0b1011, 0b0010), 2);
+    }
+
+    #[test]
+    fn test_cluster_recall() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(2);
+        let centers: Vec<Vec<(f64, Vec<f64>)>> = (0..6)
+            .map(|_| (0..6).map(|_| (0.1, vec![rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)])).collect())
+            .collect();
+
+        let mut index = LshIndex::new(SignatureHasher::new(12, 99));
+        let mut cluster_of = Vec::new();
+        for (c, center) in centers.iter().enumerate() {
+            for _ in 0..20 {
+                index.insert(cluster_of.len(), &noisy_path(center, 0.02, &mut rng));
+                cluster_of.push(c);
+            }
+        }
+        assert_eq!(index.len(), 120);
+
+        let (mut hits, mut total) = (0, 0);
+        for (c, center) in centers.iter().enumerate() {
+            let query = noisy_path(center, 0.02, &mut rng);
+            let found = index.candidates(&query, 1);
+            hits += found.iter().filter(|&&id| cluster_of[id] == c).count();
+            total += 20;
+        }
+        let recall = hits as f64 / total as f64;
+        assert!(recall > 0.8, "recall too low: {}", recall);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::perception::universal_vector::UniversalVector;
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Format de persistance d'un lot de UniversalVectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorFormat {
+    /// Un objet JSON par ligne (lisible, diffable).
+    JsonLines,
+    /// Binaire compact (bincode), pour les longues sessions d'enregistrement.
+    Bincode,
+}
+
+impl VectorFormat {
+    /// Déduit le format de l'extension : `.jsonl`/`.json` ou `.bin`/`.bincode`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "jsonl" | "json" => Some(VectorFormat::JsonLines),
+            "bin" | "bincode" => Some(VectorFormat::Bincode),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+fn format_for<P: AsRef<Path>>(path: P) -> io::Result<VectorFormat> {
+    VectorFormat::from_path(&path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unknown vector file extension: {}", path.as_ref().display()),
+        )
+    })
+}
+
+/// Sauvegarde un lot de vecteurs, le format étant choisi par l'extension du fichier.
+pub fn save_vectors<P: AsRef<Path>>(path: P, vectors: &[UniversalVector]) -> io::Result<()> {
+    let format = format_for(&path)?;
+    save_vectors_as(path, vectors, format)
+}
+
+/// Recharge un lot de vecteurs, le format étant choisi par l'extension du fichier.
+pub fn load_vectors<P: AsRef<Path>>(path: P) -> io::Result<Vec<UniversalVector>> {
+    let format = format_for(&path)?;
+    load_vectors_as(path, format)
+}
+
+pub fn save_vectors_as<P: AsRef<Path>>(path: P, vectors: &[UniversalVector], format: VectorFormat) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        VectorFormat::JsonLines => {
+            for v in vectors {
+                serde_json::to_writer(&mut writer, v).map_err(invalid_data)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        VectorFormat::Bincode => {
+            bincode::serialize_into(&mut writer, vectors).map_err(invalid_data)?;
+        }
+    }
+    writer.flush()
+}
+
+pub fn load_vectors_as<P: AsRef<Path>>(path: P, format: VectorFormat) -> io::Result<Vec<UniversalVector>> {
+    let reader = BufReader::new(File::open(path)?);
+    match format {
+        VectorFormat::JsonLines => {
+            let mut vectors = Vec::new();
+            for line in reader.lines() {
+                let line = line?;
+                if line.trim().is_empty() { continue; }
+                vectors.push(serde_json::from_str(&line).map_err(invalid_data)?);
+            }
+            Ok(vectors)
+        }
+        VectorFormat::Bincode => bincode::deserialize_from(reader).map_err(invalid_data),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::universal_transducer::UniversalTransducer;
+    use crate::perception::universal_vector::{Gradient, Metadata, Signature};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("archt3_io_{}_{}", std::process::id(), name))
+    }
+
+    fn sample_vectors() -> Vec<UniversalVector> {
+        let raw: Vec<Vec<f64>> = (0..40).map(|i| vec![(i as f64 * 0.37).sin(), (i as f64 * 0.11).cos()]).collect();
+        let times: Vec<f64> = (0..40).map(|i| i as f64 * 0.1).collect();
+        let mut vectors = UniversalTransducer::segment_and_process(&raw, &times).unwrap();
+        vectors.push(UniversalVector {
+            signature: Signature::from_segment(0.1, &[1.0 / 3.0, -2.0e-300]),
+            gradient: Gradient::zero(),
+            metadata: Metadata::zero(),
+        });
+        vectors
+    }
+
+    fn assert_bit_exact(a: &[UniversalVector], b: &[UniversalVector]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b.iter()) {
+            for (lx, ly) in x.signature.levels.iter().zip(y.signature.levels.iter()) {
+                let bits_x: Vec<u64> = lx.iter().map(|v| v.to_bits()).collect();
+                let bits_y: Vec<u64> = ly.iter().map(|v| v.to_bits()).collect();
+                assert_eq!(bits_x, bits_y);
+            }
+            assert_eq!(x, y);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_both_formats() {
+        let vectors = sample_vectors();
+        assert!(vectors.len() > 2);
+
+        for name in ["batch.jsonl", "batch.bin"] {
+            let path = temp_path(name);
+            save_vectors(&path, &vectors).unwrap();
+            let loaded = load_vectors(&path).unwrap();
+            assert_bit_exact(&vectors, &loaded);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_empty_gradient_and_unknown_extension() {
+        let vectors = vec![UniversalVector::zero()];
+        let path = temp_path("empty.bincode");
+        save_vectors_as(&path, &vectors, VectorFormat::Bincode).unwrap();
+        assert_eq!(load_vectors_as(&path, VectorFormat::Bincode).unwrap(), vectors);
+        std::fs::remove_file(&path).unwrap();
+
+        let err = save_vectors(temp_path("batch.txt"), &vectors).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
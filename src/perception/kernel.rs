@@ -0,0 +1,66 @@
+use crate::perception::universal_vector::Signature;
+
+/// Matrice de Gram G[i][j] = K(s_i, s_j) pour le noyau de signature tronqué.
+/// Seule la moitié supérieure est calculée, puis recopiée (G est symétrique).
+pub fn gram_matrix(signatures: &[Signature]) -> Vec<Vec<f64>> {
+    let n = signatures.len();
+    let row = |i: usize| -> Vec<f64> {
+        (i..n).map(|j| signatures[i].kernel(&signatures[j])).collect()
+    };
+
+    #[cfg(feature = "parallel")]
+    let upper: Vec<Vec<f64>> = {
+        use rayon::prelude::*;
+        (0..n).into_par_iter().map(row).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let upper: Vec<Vec<f64>> = (0..n).map(row).collect();
+
+    let mut gram = vec![vec![0.0; n]; n];
+    for (i, values) in upper.into_iter().enumerate() {
+        for (offset, v) in values.into_iter().enumerate() {
+            gram[i][i + offset] = v;
+            gram[i + offset][i] = v;
+        }
+    }
+    gram
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    fn random_path(seed: u64) -> Signature {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        (0..8).fold(Signature::zero(3), |acc, _| {
+            let dx = [rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)];
+            acc.combine(&Signature::from_segment(rng.gen_range(0.05..0.2), &dx))
+        })
+    }
+
+    #[test]
+    fn test_gram_matrix_symmetric_positive_diagonal() {
+        let signatures: Vec<Signature> = (0..12).map(random_path).collect();
+        let gram = gram_matrix(&signatures);
+        assert_eq!(gram.len(), 12);
+        for i in 0..12 {
+            assert!(gram[i][i] > 0.0);
+            for j in 0..12 {
+                assert_eq!(gram[i][j], gram[j][i]);
+                assert_eq!(gram[i][j], signatures[i].kernel(&signatures[j]));
+                // Cauchy-Schwarz, conséquence du caractère semi-défini positif
+                assert!(gram[i][j].powi(2) <= gram[i][i] * gram[j][j] * (1.0 + 1e-12));
+            }
+        }
+        assert!(gram_matrix(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_self_kernel_dominates() {
+        let up = (0..6).fold(Signature::zero(2), |acc, _| acc.combine(&Signature::from_segment(0.1, &[0.5])));
+        let down = (0..6).fold(Signature::zero(2), |acc, _| acc.combine(&Signature::from_segment(0.1, &[-0.5])));
+        assert!(up.kernel(&up) > up.kernel(&down));
+    }
+}
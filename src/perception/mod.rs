@@ -1,4 +1,23 @@
 pub mod adaptive_normalizer;
+#[cfg(feature = "async")]
+pub mod async_scanner;
+pub mod error;
+pub mod hashing;
+pub mod io;
+pub mod kernel;
+pub mod multi_scanner;
+pub mod novelty;
+pub mod projection;
+pub mod quantile;
+pub mod quant;
+pub mod signature32;
+pub mod source;
+pub mod sources;
+pub mod streaming;
 pub mod universal_scanner;
 pub mod universal_transducer;
-pub mod universal_vector;
\ No newline at end of file
+pub mod universal_vector;
+pub mod whitening;
+
+#[cfg(test)]
+mod signature_properties;
\ No newline at end of file
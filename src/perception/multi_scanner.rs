@@ -0,0 +1,149 @@
+use crate::perception::source::UniversalSource;
+use crate::perception::universal_scanner::UniversalScanner;
+use crate::perception::universal_vector::{Modality, UniversalVector};
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Plusieurs sources asynchrones (cadences et unités différentes), chacune avec son
+/// propre `UniversalScanner` : normaliseur, taille de lot, apprentissage et modalité
+/// sont indépendants. Un normaliseur unique sur des caractéristiques concaténées
+/// mélangerait des échelles sans rapport.
+pub struct MultiScanner<K> {
+    scanners: HashMap<K, UniversalScanner>,
+    // Ordre d'enregistrement, pour un `process_ready` déterministe
+    order: Vec<K>,
+}
+
+impl<K: Eq + Hash + Clone> MultiScanner<K> {
+    pub fn new() -> Self {
+        MultiScanner { scanners: HashMap::new(), order: Vec::new() }
+    }
+
+    /// Enregistre (ou remplace) le scanner d'une source.
+    pub fn add_source(&mut self, id: K, scanner: UniversalScanner) {
+        if self.scanners.insert(id.clone(), scanner).is_none() {
+            self.order.push(id);
+        }
+    }
+
+    /// Enregistre une source dont tous les vecteurs porteront `modality`.
+    pub fn add_tagged_source(&mut self, id: K, modality: Modality, mut scanner: UniversalScanner) {
+        scanner.set_modality(Some(modality));
+        self.add_source(id, scanner);
+    }
+
+    pub fn remove_source(&mut self, id: &K) -> Option<UniversalScanner> {
+        self.order.retain(|k| k != id);
+        self.scanners.remove(id)
+    }
+
+    pub fn source(&self, id: &K) -> Option<&UniversalScanner> {
+        self.scanners.get(id)
+    }
+
+    pub fn source_mut(&mut self, id: &K) -> Option<&mut UniversalScanner> {
+        self.scanners.get_mut(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Achemine l'échantillon vers le scanner de sa source ; false si la source est inconnue.
+    /// Un échantillon rejeté reste consigné dans les erreurs de son scanner.
+    pub fn ingest<T: UniversalSource>(&mut self, id: &K, sample: &T) -> bool {
+        match self.scanners.get_mut(id) {
+            Some(scanner) => {
+                let _ = scanner.ingest(sample);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Vide les sources dont le lot est complet, dans l'ordre d'enregistrement.
+    pub fn process_ready(&mut self) -> Vec<(K, Vec<UniversalVector>)> {
+        let mut ready = Vec::new();
+        for id in &self.order {
+            if let Some(scanner) = self.scanners.get_mut(id).filter(|s| s.is_ready()) {
+                ready.push((id.clone(), scanner.process_and_flush()));
+            }
+        }
+        ready
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for MultiScanner<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::source::Sample;
+
+    #[test]
+    fn test_sources_are_independent() {
+        let mut multi = MultiScanner::new();
+        multi.add_source("imu", UniversalScanner::new(50));
+        multi.add_source("baro", UniversalScanner::new(20));
+
+        let mut flushes: HashMap<&str, usize> = HashMap::new();
+        // imu à 100 Hz autour de 10 ; baro à 10 Hz autour de -500, en audio
+        for i in 0..1000 {
+            let t = i as f64 * 0.01;
+            assert!(multi.ingest(&"imu", &(t, 10.0 + (t * 3.0).sin())));
+            if i % 10 == 0 {
+                let baro = Sample { t, features: vec![-500.0 + 100.0 * (t * 0.7).sin()], modality: Modality::Audio, reliability: 1.0 };
+                assert!(multi.ingest(&"baro", &baro));
+            }
+            for (id, vectors) in multi.process_ready() {
+                *flushes.entry(id).or_default() += 1;
+                let expected = if id == "baro" { Modality::Audio } else { Modality::Sensor };
+                assert!(vectors.iter().all(|v| v.metadata.modality == expected));
+            }
+        }
+        assert!(!multi.ingest(&"gps", &(0.0, 1.0)));
+
+        // 1000 / 50 lots pour l'imu, 100 / 20 pour le baromètre
+        assert_eq!(flushes["imu"], 20);
+        assert_eq!(flushes["baro"], 5);
+        let imu_mean = multi.source(&"imu").unwrap().normalizer().mean()[0];
+        let baro_mean = multi.source(&"baro").unwrap().normalizer().mean()[0];
+        assert!((imu_mean - 10.0).abs() < 0.5);
+        assert!((baro_mean + 500.0).abs() < 50.0);
+    }
+
+    #[test]
+    fn test_tagged_sources() {
+        let mut multi = MultiScanner::new();
+        multi.add_tagged_source("mic", Modality::Audio, UniversalScanner::new(20));
+        multi.add_tagged_source("imu", Modality::Sensor, UniversalScanner::new(20));
+        multi.add_source("lidar", UniversalScanner::new_with_modality(20, Modality::Custom(7)));
+
+        let mut seen: HashMap<&str, Modality> = HashMap::new();
+        for i in 0..200 {
+            let t = i as f64 * 0.01;
+            // Les échantillons déclarent tous `Sensor` ; l'étiquette de la source prime
+            for id in ["mic", "imu", "lidar"] {
+                multi.ingest(&id, &Sample::new(t, vec![(t * 4.0).sin(), t]));
+            }
+            for (id, vectors) in multi.process_ready() {
+                for v in vectors {
+                    assert_eq!(*seen.entry(id).or_insert(v.metadata.modality.clone()), v.metadata.modality);
+                }
+            }
+        }
+        assert_eq!(seen["mic"], Modality::Audio);
+        assert_eq!(seen["imu"], Modality::Sensor);
+        assert_eq!(seen["lidar"], Modality::Custom(7));
+    }
+}
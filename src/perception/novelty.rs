@@ -0,0 +1,85 @@
+use crate::perception::universal_vector::UniversalVector;
+
+/// Sensibilité par défaut de `resonance_full` entre vecteurs normalisés
+/// (leurs distances sont dans [0, 2]).
+pub const DEFAULT_NOVELTY_SENSITIVITY: f64 = 0.5;
+
+/// « À quel point ce segment est-il nouveau par rapport à tout ce qui a été vu ? »
+///
+/// Les vecteurs de référence sont stockés normalisés ; le score est
+/// 1 − max resonance_full sur l'ensemble, dans [0, 1]. Un ensemble vide donne 1.0.
+#[derive(Debug, Clone)]
+pub struct NoveltyDetector {
+    prototypes: Vec<UniversalVector>,
+    sensitivity: f64,
+}
+
+impl NoveltyDetector {
+    pub fn new(reference: &[UniversalVector]) -> Self {
+        Self::with_sensitivity(reference, DEFAULT_NOVELTY_SENSITIVITY)
+    }
+
+    pub fn with_sensitivity(reference: &[UniversalVector], sensitivity: f64) -> Self {
+        NoveltyDetector {
+            prototypes: reference.iter().map(|v| v.to_normalized()).collect(),
+            sensitivity,
+        }
+    }
+
+    pub fn add(&mut self, v: &UniversalVector) {
+        self.prototypes.push(v.to_normalized());
+    }
+
+    pub fn len(&self) -> usize {
+        self.prototypes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prototypes.is_empty()
+    }
+
+    pub fn score(&self, v: &UniversalVector) -> f64 {
+        let query = v.to_normalized();
+        let best = self.prototypes.iter()
+            .map(|p| query.resonance_full(p, self.sensitivity))
+            .filter(|r| r.is_finite())
+            .fold(0.0, f64::max);
+        (1.0 - best).clamp(0.0, 1.0)
+    }
+
+    pub fn is_novel(&self, v: &UniversalVector, threshold: f64) -> bool {
+        self.score(v) > threshold
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::universal_transducer::UniversalTransducer;
+
+    fn vectors(freq: f64) -> Vec<UniversalVector> {
+        let times: Vec<f64> = (0..200).map(|i| i as f64 * 0.02).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * freq).sin(), (t * freq * 0.5).cos()]).collect();
+        UniversalTransducer::segment_and_process(&raw, &times).unwrap()
+    }
+
+    #[test]
+    fn test_novelty_edge_cases() {
+        let known = vectors(3.0);
+        let query = &vectors(11.0)[1];
+
+        assert_eq!(NoveltyDetector::new(&[]).score(query), 1.0);
+
+        let mut detector = NoveltyDetector::new(&known);
+        assert_eq!(detector.len(), known.len());
+        let before = detector.score(query);
+        assert!(before > 0.0);
+        assert!(detector.score(&known[2]) < 1e-9);
+
+        detector.add(query);
+        assert!(detector.score(query) < 1e-9);
+        assert!(detector.is_novel(query, -1.0));
+        assert!(!detector.is_novel(query, 0.01));
+    }
+}
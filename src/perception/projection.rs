@@ -0,0 +1,169 @@
+use crate::perception::universal_vector::Signature;
+
+use std::fmt;
+
+const POWER_ITERATIONS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectionError {
+    /// Au moins deux échantillons sont nécessaires pour estimer une covariance.
+    NotEnoughSamples { got: usize },
+    /// Les vecteurs aplatis n'ont pas tous la même longueur.
+    DimensionMismatch { expected: usize, got: usize },
+}
+
+impl fmt::Display for ProjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectionError::NotEnoughSamples { got } => {
+                write!(f, "PCA needs at least 2 samples to fit, got {}", got)
+            }
+            ProjectionError::DimensionMismatch { expected, got } => {
+                write!(f, "PCA sample has {} components, expected {}", got, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProjectionError {}
+
+/// Projection sur les deux premières composantes principales, pour visualiser
+/// où se situent les prototypes (export CSV, nuage de points).
+///
+/// Les composantes sont obtenues par itération de la puissance sur la covariance
+/// (jamais matérialisée : d^k termes par niveau la rendraient énorme), avec déflation
+/// pour la seconde.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pca2D {
+    mean: Vec<f64>,
+    components: [Vec<f64>; 2],
+    variances: [f64; 2],
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+/// Vecteur propre dominant de la covariance de `centered`, orthogonal à `exclude`.
+fn power_iteration(centered: &[Vec<f64>], exclude: Option<&[f64]>) -> (Vec<f64>, f64) {
+    let d = centered[0].len();
+    let n = centered.len() as f64;
+    // Départ déterministe, non aligné sur un axe
+    let mut v: Vec<f64> = (0..d).map(|i| 1.0 / (i + 1) as f64).collect();
+    let mut eigenvalue = 0.0;
+
+    for _ in 0..POWER_ITERATIONS {
+        if let Some(e) = exclude {
+            let p = dot(&v, e);
+            v.iter_mut().zip(e.iter()).for_each(|(x, y)| *x -= p * y);
+        }
+        let norm = dot(&v, &v).sqrt();
+        if norm < 1e-300 {
+            return (vec![0.0; d], 0.0);
+        }
+        v.iter_mut().for_each(|x| *x /= norm);
+
+        // w = C v = (1/n) Σ x (x·v)
+        let mut w = vec![0.0; d];
+        for x in centered {
+            let p = dot(x, &v);
+            w.iter_mut().zip(x.iter()).for_each(|(acc, xi)| *acc += p * xi / n);
+        }
+        eigenvalue = dot(&w, &v);
+        v = w;
+    }
+
+    if let Some(e) = exclude {
+        let p = dot(&v, e);
+        v.iter_mut().zip(e.iter()).for_each(|(x, y)| *x -= p * y);
+    }
+    let norm = dot(&v, &v).sqrt();
+    if norm < 1e-300 {
+        return (vec![0.0; d], 0.0);
+    }
+    v.iter_mut().for_each(|x| *x /= norm);
+    (v, eigenvalue)
+}
+
+impl Pca2D {
+    /// Ajuste la projection sur des signatures aplaties (`Signature::flatten`).
+    pub fn fit(samples: &[Vec<f64>]) -> Result<Self, ProjectionError> {
+        if samples.len() < 2 {
+            return Err(ProjectionError::NotEnoughSamples { got: samples.len() });
+        }
+        let d = samples[0].len();
+        if let Some(bad) = samples.iter().find(|s| s.len() != d) {
+            return Err(ProjectionError::DimensionMismatch { expected: d, got: bad.len() });
+        }
+
+        let n = samples.len() as f64;
+        let mut mean = vec![0.0; d];
+        for s in samples {
+            mean.iter_mut().zip(s.iter()).for_each(|(m, x)| *m += x / n);
+        }
+        let centered: Vec<Vec<f64>> = samples.iter()
+            .map(|s| s.iter().zip(mean.iter()).map(|(x, m)| x - m).collect())
+            .collect();
+
+        let (first, var1) = power_iteration(&centered, None);
+        let (second, var2) = power_iteration(&centered, Some(&first));
+
+        Ok(Pca2D { mean, components: [first, second], variances: [var1, var2] })
+    }
+
+    pub fn fit_signatures(signatures: &[Signature]) -> Result<Self, ProjectionError> {
+        Self::fit(&signatures.iter().map(|s| s.flatten()).collect::<Vec<_>>())
+    }
+
+    /// Variance expliquée par chacune des deux composantes.
+    pub fn explained_variance(&self) -> [f64; 2] {
+        self.variances
+    }
+
+    pub fn project_flat(&self, flat: &[f64]) -> (f64, f64) {
+        let centered: Vec<f64> = flat.iter().zip(self.mean.iter()).map(|(x, m)| x - m).collect();
+        (dot(&centered, &self.components[0]), dot(&centered, &self.components[1]))
+    }
+
+    pub fn project(&self, signature: &Signature) -> (f64, f64) {
+        self.project_flat(&signature.flatten())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dist(a: (f64, f64), b: (f64, f64)) -> f64 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_projection_preserves_distance_ordering() {
+        let sigs: Vec<Signature> = [0.1, 0.2, 0.5, 1.0, 2.0]
+            .iter()
+            .map(|&s| Signature::from_segment(0.1, &[s, 0.3 - s]))
+            .collect();
+        let pca = Pca2D::fit_signatures(&sigs).unwrap();
+        let points: Vec<(f64, f64)> = sigs.iter().map(|s| pca.project(s)).collect();
+
+        let pairs = [(0, 1), (0, 2), (0, 3), (0, 4)];
+        let exact: Vec<f64> = pairs.iter().map(|&(a, b)| sigs[a].distance(&sigs[b])).collect();
+        let projected: Vec<f64> = pairs.iter().map(|&(a, b)| dist(points[a], points[b])).collect();
+        for w in 0..pairs.len() - 1 {
+            assert!(exact[w] < exact[w + 1]);
+            assert!(projected[w] < projected[w + 1], "{:?}", projected);
+        }
+        let [v1, v2] = pca.explained_variance();
+        assert!(v1 >= v2 && v2 >= 0.0);
+    }
+
+    #[test]
+    fn test_fit_needs_two_samples() {
+        let err = Pca2D::fit_signatures(&[Signature::zero(3)]).unwrap_err();
+        assert_eq!(err, ProjectionError::NotEnoughSamples { got: 1 });
+        assert!(err.to_string().contains("at least 2 samples"));
+        assert!(Pca2D::fit(&[vec![1.0, 2.0], vec![1.0]]).is_err());
+    }
+}
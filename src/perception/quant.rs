@@ -0,0 +1,137 @@
+use crate::perception::universal_vector::Signature;
+
+use serde::{Serialize, Deserialize};
+
+/// Schéma de quantification i8, avec un facteur d'échelle par niveau
+/// (les termes de niveau k croissent comme longueur^k : une échelle globale
+/// écraserait les niveaux bas).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuantScheme {
+    /// Affine : la plage [min, max] du niveau est répartie sur [-127, 127].
+    #[default]
+    AffineI8,
+    /// Symétrique : [-max|x|, max|x|] sur [-127, 127] ; le zéro est exact.
+    SymmetricI8,
+}
+
+/// Un niveau quantifié : terme ≈ offset + scale * q.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantizedLevel {
+    pub scale: f32,
+    pub offset: f32,
+    pub values: Vec<i8>,
+}
+
+impl QuantizedLevel {
+    fn decode(&self, q: i8) -> f32 {
+        self.offset + self.scale * q as f32
+    }
+}
+
+/// Signature stockée en i8 (un octet par terme au lieu de 4 ou 8).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantizedSignature {
+    pub dim: usize,
+    pub depth: usize,
+    pub levels: Vec<QuantizedLevel>,
+}
+
+fn quantize_level(terms: &[f64], scheme: QuantScheme) -> QuantizedLevel {
+    let (offset, half_range) = match scheme {
+        QuantScheme::AffineI8 => {
+            let (min, max) = terms.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| (lo.min(x), hi.max(x)));
+            if terms.is_empty() { (0.0, 0.0) } else { ((min + max) / 2.0, (max - min) / 2.0) }
+        }
+        QuantScheme::SymmetricI8 => (0.0, terms.iter().fold(0.0f64, |m, x| m.max(x.abs()))),
+    };
+    let scale = half_range / 127.0;
+
+    let values = terms.iter()
+        .map(|&x| if scale > 0.0 { ((x - offset) / scale).round().clamp(-127.0, 127.0) as i8 } else { 0 })
+        .collect();
+    QuantizedLevel { scale: scale as f32, offset: offset as f32, values }
+}
+
+/// Quantifie chaque niveau indépendamment. Erreur par terme ≤ scale/2 du niveau.
+pub fn quantize(sig: &Signature, scheme: QuantScheme) -> QuantizedSignature {
+    QuantizedSignature {
+        dim: sig.dim,
+        depth: sig.depth,
+        levels: sig.levels.iter().map(|l| quantize_level(l, scheme)).collect(),
+    }
+}
+
+pub fn dequantize(q: &QuantizedSignature) -> Signature {
+    Signature {
+        dim: q.dim,
+        depth: q.depth,
+        levels: q.levels.iter()
+            .map(|l| l.values.iter().map(|&v| l.decode(v) as f64).collect())
+            .collect(),
+    }
+}
+
+impl QuantizedSignature {
+    /// Distance L2 calculée directement sur les codes i8, sans reconstruire de Signature.
+    pub fn approx_distance(&self, other: &QuantizedSignature) -> f64 {
+        self.levels.iter()
+            .zip(other.levels.iter())
+            .map(|(a, b)| {
+                a.values.iter().zip(b.values.iter())
+                    .map(|(&qa, &qb)| {
+                        let d = (a.decode(qa) - b.decode(qb)) as f64;
+                        d * d
+                    })
+                    .sum::<f64>()
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Termes dequantifiés, niveau par niveau (même disposition que `Signature32::flatten`).
+    pub fn flatten(&self) -> Vec<f32> {
+        self.levels.iter()
+            .flat_map(|l| l.values.iter().map(move |&v| l.decode(v)))
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    fn random_signature(rng: &mut impl Rng) -> Signature {
+        (0..8).fold(Signature::zero(3), |acc, _| {
+            let dx = [rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)];
+            acc.combine(&Signature::from_segment(rng.gen_range(0.01..0.5), &dx))
+        })
+    }
+
+    #[test]
+    fn test_distance_error_is_bounded() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        for scheme in [QuantScheme::AffineI8, QuantScheme::SymmetricI8] {
+            for _ in 0..50 {
+                let (a, b) = (random_signature(&mut rng), random_signature(&mut rng));
+                let (qa, qb) = (quantize(&a, scheme), quantize(&b, scheme));
+
+                let exact = a.distance(&b);
+                let approx = qa.approx_distance(&qb);
+                assert!((approx - exact).abs() <= 0.05 * exact, "{:?}: {} vs {}", scheme, approx, exact);
+                assert!((dequantize(&qa).distance(&dequantize(&qb)) - approx).abs() < 1e-6 * (1.0 + exact));
+            }
+        }
+    }
+
+    #[test]
+    fn test_degenerate_levels() {
+        let zero = Signature::zero(3);
+        for scheme in [QuantScheme::AffineI8, QuantScheme::SymmetricI8] {
+            let q = quantize(&zero, scheme);
+            assert_eq!(dequantize(&q), zero);
+            assert_eq!(q.flatten().len(), 3 + 9 + 27);
+        }
+    }
+}
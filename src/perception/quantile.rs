@@ -0,0 +1,263 @@
+use serde::{Serialize, Deserialize};
+
+/// Estimation en ligne d'un quantile par l'algorithme P² (Jain & Chlamtac, 1985).
+///
+/// Cinq marqueurs suffisent, quelle que soit la longueur du flux ; leurs hauteurs
+/// sont ajustées par interpolation parabolique. Avant cinq observations, le
+/// quantile est calculé exactement sur les valeurs reçues.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct P2Estimator {
+    p: f64,
+    count: u64,
+    // Hauteurs des marqueurs
+    heights: [f64; 5],
+    // Positions réelles et désirées, et incrément des positions désirées
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl P2Estimator {
+    /// `p` dans [0, 1] (0.5 pour la médiane).
+    pub fn new(p: f64) -> Self {
+        let p = p.clamp(0.0, 1.0);
+        P2Estimator {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [0.0, 1.0, 2.0, 3.0, 4.0],
+            desired: [0.0, 2.0 * p, 4.0 * p, 2.0 + 2.0 * p, 4.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Les valeurs non finies sont ignorées.
+    pub fn update(&mut self, x: f64) {
+        if !x.is_finite() {
+            return;
+        }
+        if self.count < 5 {
+            self.heights[self.count as usize] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        // Cellule contenant x (les extrêmes sont repoussés si besoin)
+        let q = &mut self.heights;
+        let k = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| x < q[i + 1]).unwrap_or(3)
+        };
+        for n in &mut self.positions[k + 1..] {
+            *n += 1.0;
+        }
+        for (d, inc) in self.desired.iter_mut().zip(self.increments) {
+            *d += inc;
+        }
+
+        // Ajustement des trois marqueurs centraux
+        for i in 1..4 {
+            let n = &mut self.positions;
+            let d = self.desired[i] - n[i];
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let s = d.signum();
+                let parabolic = q[i] + s / (n[i + 1] - n[i - 1])
+                    * ((n[i] - n[i - 1] + s) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                        + (n[i + 1] - n[i] - s) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    // Repli linéaire vers le voisin dans la direction s
+                    let j = if s > 0.0 { i + 1 } else { i - 1 };
+                    q[i] + s * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                n[i] += s;
+            }
+        }
+    }
+
+    /// Quantile estimé (NaN avant la première observation).
+    pub fn estimate(&self) -> f64 {
+        match self.count {
+            0 => f64::NAN,
+            c if c < 5 => {
+                let mut seen = self.heights[..c as usize].to_vec();
+                seen.sort_by(f64::total_cmp);
+                seen[((c - 1) as f64 * self.p).round() as usize]
+            }
+            _ => self.heights[2],
+        }
+    }
+}
+
+/// Probabilités suivies par défaut par `QuantileTracker`.
+pub const DEFAULT_QUANTILES: [f64; 9] = [0.01, 0.05, 0.1, 0.25, 0.5, 0.75, 0.9, 0.95, 0.99];
+
+/// Quantiles courants de chaque dimension d'un flux : un `P2Estimator` par
+/// probabilité suivie, plus le minimum et le maximum exacts. Les autres quantiles et
+/// le rang d'une valeur sont interpolés linéairement entre ces repères.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantileTracker {
+    probabilities: Vec<f64>,
+    // Par dimension : estimateurs (dans l'ordre de `probabilities`), minimum, maximum
+    estimators: Vec<Vec<P2Estimator>>,
+    min: Vec<f64>,
+    max: Vec<f64>,
+}
+
+impl QuantileTracker {
+    /// Suit les probabilités données (triées et dédoublonnées, bornes exclues).
+    pub fn new(probabilities: &[f64]) -> Self {
+        let mut probabilities: Vec<f64> = probabilities.iter().copied().filter(|p| *p > 0.0 && *p < 1.0).collect();
+        probabilities.sort_by(f64::total_cmp);
+        probabilities.dedup();
+        QuantileTracker { probabilities, estimators: Vec::new(), min: Vec::new(), max: Vec::new() }
+    }
+
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    /// Dimension suivie (0 avant le premier échantillon).
+    pub fn dim(&self) -> usize {
+        self.estimators.len()
+    }
+
+    /// La dimension est fixée par le premier vecteur ; l'appelant garantit la suite.
+    /// Les valeurs non finies sont ignorées.
+    pub fn update(&mut self, values: &[f64]) {
+        if self.estimators.is_empty() {
+            let estimators: Vec<P2Estimator> = self.probabilities.iter().map(|p| P2Estimator::new(*p)).collect();
+            self.estimators = vec![estimators; values.len()];
+            self.min = vec![f64::INFINITY; values.len()];
+            self.max = vec![f64::NEG_INFINITY; values.len()];
+        }
+        for (i, x) in values.iter().enumerate().filter(|(_, x)| x.is_finite()) {
+            for estimator in &mut self.estimators[i] {
+                estimator.update(*x);
+            }
+            self.min[i] = self.min[i].min(*x);
+            self.max[i] = self.max[i].max(*x);
+        }
+    }
+
+    /// Repères (probabilité, valeur) croissants de la dimension `dim`.
+    fn knots(&self, dim: usize) -> Vec<(f64, f64)> {
+        let mut knots = vec![(0.0, self.min[dim])];
+        let mut floor = self.min[dim];
+        for (p, estimator) in self.probabilities.iter().zip(&self.estimators[dim]) {
+            // P² ne garantit pas la monotonie entre estimateurs indépendants
+            floor = estimator.estimate().clamp(floor, self.max[dim]);
+            knots.push((*p, floor));
+        }
+        knots.push((1.0, self.max[dim]));
+        knots
+    }
+
+    /// Quantile `q` de la dimension `dim` (NaN sans données).
+    pub fn quantile(&self, dim: usize, q: f64) -> f64 {
+        if self.min.get(dim).is_none_or(|min| !min.is_finite()) {
+            return f64::NAN;
+        }
+        let q = q.clamp(0.0, 1.0);
+        let knots = self.knots(dim);
+        let k = knots.partition_point(|(p, _)| *p < q).clamp(1, knots.len() - 1);
+        let ((p0, v0), (p1, v1)) = (knots[k - 1], knots[k]);
+        if p1 > p0 { v0 + (v1 - v0) * (q - p0) / (p1 - p0) } else { v1 }
+    }
+
+    /// Rang estimé de `x` dans la dimension `dim`, dans [0, 1] (0.5 sans données).
+    pub fn rank(&self, dim: usize, x: f64) -> f64 {
+        if self.min.get(dim).is_none_or(|min| !min.is_finite()) {
+            return 0.5;
+        }
+        let knots = self.knots(dim);
+        if x <= knots[0].1 {
+            return 0.0;
+        }
+        let k = knots.partition_point(|(_, v)| *v < x);
+        if k == knots.len() {
+            return 1.0;
+        }
+        let ((p0, v0), (p1, v1)) = (knots[k - 1], knots[k]);
+        if v1 > v0 { p0 + (p1 - p0) * (x - v0) / (v1 - v0) } else { p1 }
+    }
+}
+
+impl Default for QuantileTracker {
+    fn default() -> Self {
+        Self::new(&DEFAULT_QUANTILES)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_p2_median_of_uniform() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut median = P2Estimator::new(0.5);
+        assert!(median.estimate().is_nan());
+        for x in [5.0, 1.0, 3.0] {
+            median.update(x);
+        }
+        assert_eq!(median.estimate(), 3.0);
+
+        let mut q90 = P2Estimator::new(0.9);
+        for _ in 0..10_000 {
+            let x = rng.gen_range(0.0..1.0);
+            median.update(x);
+            q90.update(x);
+        }
+        assert!((median.estimate() - 0.5).abs() < 0.02, "{}", median.estimate());
+        assert!((q90.estimate() - 0.9).abs() < 0.02, "{}", q90.estimate());
+    }
+
+    #[test]
+    fn test_tracker_matches_exact_quantiles() {
+        // Loi exponentielle (queue lourde à droite) et uniforme, 10k échantillons
+        let mut rng = rand::rngs::StdRng::seed_from_u64(17);
+        let samples: Vec<[f64; 2]> = (0..10_000)
+            .map(|_| [-(1.0 - rng.gen_range(0.0..1.0f64)).ln(), rng.gen_range(-5.0..5.0)])
+            .collect();
+        let mut tracker = QuantileTracker::default();
+        assert!(tracker.quantile(0, 0.5).is_nan());
+        for s in &samples {
+            tracker.update(s);
+        }
+
+        for dim in 0..2 {
+            let mut sorted: Vec<f64> = samples.iter().map(|s| s[dim]).collect();
+            sorted.sort_by(f64::total_cmp);
+            let exact = |q: f64| sorted[((sorted.len() - 1) as f64 * q).round() as usize];
+            let spread = sorted[sorted.len() - 1] - sorted[0];
+            for q in [0.05, 0.25, 0.5, 0.75, 0.95, 0.99] {
+                let estimate = tracker.quantile(dim, q);
+                assert!((estimate - exact(q)).abs() < 0.03 * exact(q).abs().max(0.1 * spread), "dim {} q {}: {} vs {}", dim, q, estimate, exact(q));
+                assert!((tracker.rank(dim, exact(q)) - q).abs() < 0.02);
+            }
+            assert_eq!(tracker.quantile(dim, 0.0), sorted[0]);
+            assert_eq!(tracker.rank(dim, sorted[sorted.len() - 1] + 1.0), 1.0);
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::perception::universal_vector::Signature;
+
+use serde::{Serialize, Deserialize};
+
+/// Variante f32 de `Signature`, même disposition aplatie (levels[k-1] = d^k termes).
+///
+/// Les poids des PNUs sont en f32 : stocker les prototypes dérivés de signatures
+/// dans ce format divise la mémoire par deux et évite la conversion à chaque comparaison.
+///
+/// Conversions :
+/// - `Signature -> Signature32` arrondit chaque terme au f32 le plus proche
+///   (erreur relative ≤ 2^-24 par terme ; les valeurs hors plage deviennent ±inf).
+/// - `Signature32 -> Signature` est exacte (tout f32 est représentable en f64).
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Signature32 {
+    pub dim: usize,
+    pub depth: usize,
+    pub levels: Vec<Vec<f32>>,
+}
+
+impl Signature32 {
+    pub fn zero(dim: usize, depth: usize) -> Self {
+        Signature32 {
+            dim,
+            depth,
+            levels: (1..=depth).map(|k| vec![0.0; dim.pow(k as u32)]).collect(),
+        }
+    }
+
+    pub fn from_segment(dt: f32, dx: &[f32], depth: usize) -> Self {
+        Signature::from_segment_with_depth(
+            dt as f64,
+            &dx.iter().map(|&x| x as f64).collect::<Vec<f64>>(),
+            depth,
+        ).into()
+    }
+
+    /// Identité de Chen, même sémantique que `Signature::combine`
+    /// (troncature à la plus petite profondeur).
+    pub fn combine(&self, other: &Signature32) -> Signature32 {
+        assert_eq!(self.dim, other.dim, "Dimensions must match to combine signatures");
+        let depth = self.depth.min(other.depth);
+        let mut res = Signature32::zero(self.dim, depth);
+
+        for k in 1..=depth {
+            let out = &mut res.levels[k - 1];
+            for (r, (a, b)) in out.iter_mut().zip(self.levels[k - 1].iter().zip(other.levels[k - 1].iter())) {
+                *r = a + b;
+            }
+            for i in 1..k {
+                let left = &self.levels[i - 1];
+                let right = &other.levels[k - i - 1];
+                let stride = right.len();
+                for (a_idx, a) in left.iter().enumerate() {
+                    let base = a_idx * stride;
+                    for (b_idx, b) in right.iter().enumerate() {
+                        out[base + b_idx] += a * b;
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    fn common_terms<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (&'a f32, &'a f32)> {
+        self.levels.iter()
+            .zip(other.levels.iter())
+            .flat_map(|(a, b)| a.iter().zip(b.iter()))
+    }
+
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.common_terms(other).map(|(a, b)| a * b).sum()
+    }
+
+    pub fn distance(&self, other: &Self) -> f32 {
+        self.common_terms(other)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn scale(&mut self, s: f32) {
+        for x in self.levels.iter_mut().flat_map(|l| l.iter_mut()) {
+            *x *= s;
+        }
+    }
+
+    pub fn normalize(&mut self) {
+        let mag = self.magnitude();
+        if mag > f32::EPSILON {
+            self.scale(1.0 / mag);
+        }
+    }
+
+    /// Tous les termes, niveau par niveau : directement utilisable comme `weight_vector` de PNU.
+    pub fn flatten(&self) -> Box<[f32]> {
+        self.levels.iter().flat_map(|l| l.iter().copied()).collect()
+    }
+}
+
+impl From<&Signature> for Signature32 {
+    fn from(sig: &Signature) -> Self {
+        Signature32 {
+            dim: sig.dim,
+            depth: sig.depth,
+            levels: sig.levels.iter()
+                .map(|l| l.iter().map(|&x| x as f32).collect())
+                .collect(),
+        }
+    }
+}
+
+impl From<Signature> for Signature32 {
+    fn from(sig: Signature) -> Self {
+        Signature32::from(&sig)
+    }
+}
+
+impl From<&Signature32> for Signature {
+    fn from(sig: &Signature32) -> Self {
+        Signature {
+            dim: sig.dim,
+            depth: sig.depth,
+            levels: sig.levels.iter()
+                .map(|l| l.iter().map(|&x| x as f64).collect())
+                .collect(),
+        }
+    }
+}
+
+impl From<Signature32> for Signature {
+    fn from(sig: Signature32) -> Self {
+        Signature::from(&sig)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(incs: &[(f64, Vec<f64>)]) -> Signature {
+        incs.iter().fold(Signature::zero(incs[0].1.len() + 1), |acc, (dt, dx)| {
+            acc.combine(&Signature::from_segment(*dt, dx))
+        })
+    }
+
+    #[test]
+    fn test_conversion_round_trip() {
+        let sig = path(&[(0.1, vec![0.3, -0.7]), (0.2, vec![0.5, 0.25])]);
+        let s32 = Signature32::from(&sig);
+        let back = Signature::from(&s32);
+
+        // f64 -> f32 arrondit, f32 -> f64 est exact
+        assert_eq!(Signature32::from(&back), s32);
+        for (a, b) in sig.levels.iter().flatten().zip(back.levels.iter().flatten()) {
+            assert!((a - b).abs() <= a.abs() * f32::EPSILON as f64);
+        }
+    }
+
+    #[test]
+    fn test_api_matches_f64() {
+        let a = path(&[(0.1, vec![0.3, -0.7]), (0.2, vec![0.5, 0.25])]);
+        let b = path(&[(0.3, vec![-0.1, 0.4]), (0.1, vec![0.9, -0.2])]);
+        let (a32, b32) = (Signature32::from(&a), Signature32::from(&b));
+
+        assert!((a32.distance(&b32) as f64 - a.distance(&b)).abs() < 1e-5);
+        assert!((a32.dot(&b32) as f64 - a.dot(&b)).abs() < 1e-5);
+        let chen = Signature::from(a32.combine(&b32));
+        assert!(chen.distance(&a.combine(&b)) < 1e-5);
+        assert_eq!(a32.flatten().len(), 3 + 9 + 27);
+    }
+}
@@ -0,0 +1,90 @@
+// Propriétés algébriques de la signature tronquée (proptest).
+// Protège `combine` et `from_segment`, utilisés par les transducers 1D et multi-dim.
+
+use crate::perception::universal_vector::Signature;
+
+use proptest::prelude::*;
+
+const TOL: f64 = 1e-9;
+
+/// Chemin 1D linéaire par morceaux : temps croissants (pas nuls autorisés), valeurs bornées.
+fn path_1d() -> impl Strategy<Value = Vec<(f64, f64)>> {
+    prop::collection::vec((0.0..1.0f64, -5.0..5.0f64), 2..24).prop_map(|steps| {
+        let mut t = 0.0;
+        steps.into_iter().map(|(dt, x)| { t += dt; (t, x) }).collect()
+    })
+}
+
+/// Incréments multidimensionnels (dt, dx), y compris des segments de longueur nulle.
+fn increments(channels: usize) -> impl Strategy<Value = Vec<(f64, Vec<f64>)>> {
+    let step = (0.0..1.0f64, prop::collection::vec(-2.0..2.0f64, channels));
+    let zero = Just((0.0, vec![0.0; channels]));
+    prop::collection::vec(prop_oneof![4 => step, 1 => zero], 1..12)
+}
+
+fn signature_of(incs: &[(f64, Vec<f64>)], dim: usize) -> Signature {
+    incs.iter().fold(Signature::zero(dim), |acc, (dt, dx)| acc.combine(&Signature::from_segment(*dt, dx)))
+}
+
+fn scaled_tol(a: &Signature, b: &Signature) -> f64 {
+    TOL * (1.0 + a.magnitude().max(b.magnitude()))
+}
+
+proptest! {
+    #[test]
+    fn prop_split_anywhere_recombines(path in path_1d()) {
+        let whole = Signature::from_points(&path);
+        prop_assert!(Signature::check_consistency(&path) <= TOL * (1.0 + whole.magnitude()));
+    }
+
+    #[test]
+    fn prop_random_split_point(path in path_1d(), cut in 0.0..1.0f64) {
+        let m = 1 + ((path.len() - 2) as f64 * cut) as usize;
+        let m = m.min(path.len() - 1);
+        let whole = Signature::from_points(&path);
+        let split = Signature::from_points(&path[..=m]).combine(&Signature::from_points(&path[m..]));
+        prop_assert!(whole.distance(&split) <= scaled_tol(&whole, &split));
+    }
+
+    #[test]
+    fn prop_associativity(a in increments(2), b in increments(2), c in increments(2)) {
+        let (sa, sb, sc) = (signature_of(&a, 3), signature_of(&b, 3), signature_of(&c, 3));
+        let left = sa.combine(&sb).combine(&sc);
+        let right = sa.combine(&sb.combine(&sc));
+        prop_assert!(left.distance(&right) <= scaled_tol(&left, &right));
+    }
+
+    #[test]
+    fn prop_identity(a in increments(3)) {
+        let sa = signature_of(&a, 4);
+        let id = Signature::zero(4);
+        prop_assert_eq!(sa.combine(&id), sa.clone());
+        prop_assert_eq!(id.combine(&sa), sa);
+    }
+
+    #[test]
+    fn prop_subsampling_invariance(a in increments(2), parts in 2..6usize) {
+        // Subdiviser chaque segment linéaire ne change pas la signature
+        let coarse = signature_of(&a, 3);
+        let fine: Vec<(f64, Vec<f64>)> = a.iter()
+            .flat_map(|(dt, dx)| {
+                let n = parts as f64;
+                std::iter::repeat_n((dt / n, dx.iter().map(|x| x / n).collect::<Vec<f64>>()), parts)
+            })
+            .collect();
+        let fine = signature_of(&fine, 3);
+        prop_assert!(coarse.distance(&fine) <= scaled_tol(&coarse, &fine));
+    }
+}
+
+#[test]
+fn test_even_split_and_zero_length_segments() {
+    let path = vec![(0.0, 0.0), (0.5, 1.0), (0.5, 1.0), (1.0, -0.5), (1.5, -0.5), (2.0, 2.0)];
+    assert!(Signature::check_consistency(&path) < TOL);
+    assert_eq!(Signature::check_consistency(&path[..2]), 0.0);
+
+    let half = path.len() / 2;
+    let whole = Signature::from_points(&path);
+    let split = Signature::from_points(&path[..=half]).combine(&Signature::from_points(&path[half..]));
+    assert!(whole.distance(&split) < TOL);
+}
@@ -0,0 +1,66 @@
+use crate::perception::universal_vector::{Metadata, Modality};
+
+/// Le contrat que tout objet doit remplir pour être ingéré par le système.
+pub trait UniversalSource {
+    /// Retourne le moment de l'événement (en secondes, ou unité monotone croissante).
+    fn timestamp(&self) -> f64;
+
+    /// Extrait les caractéristiques brutes (features).
+    /// C'est ici que vous convertissez votre donnée métier en mathématiques.
+    /// Exemple : Un pixel RGB -> vec![r, g, b]
+    fn to_features(&self) -> Vec<f64>;
+
+    /// Modalité de la source, reportée dans les métadonnées des vecteurs produits.
+    fn modality(&self) -> Modality {
+        Modality::Sensor
+    }
+
+    /// Confiance dans l'échantillon, dans [0, 1] ; 1.0 si elle n'est pas connue.
+    fn reliability(&self) -> f64 {
+        1.0
+    }
+
+    /// Contexte complet de l'échantillon, dérivé des méthodes ci-dessus.
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .timestamp(self.timestamp())
+            .modality(self.modality())
+            .reliability(self.reliability())
+            .build()
+    }
+}
+
+/// Échantillon générique, quand la donnée métier n'a pas de type propre.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub t: f64,
+    pub features: Vec<f64>,
+    pub modality: Modality,
+    pub reliability: f64,
+}
+
+impl Sample {
+    /// Échantillon de capteur (`Modality::Sensor`), fiabilité 1.0.
+    pub fn new(t: f64, features: Vec<f64>) -> Self {
+        Sample { t, features, modality: Modality::Sensor, reliability: 1.0 }
+    }
+}
+
+impl UniversalSource for Sample {
+    fn timestamp(&self) -> f64 { self.t }
+    fn to_features(&self) -> Vec<f64> { self.features.clone() }
+    fn modality(&self) -> Modality { self.modality.clone() }
+    fn reliability(&self) -> f64 { self.reliability }
+}
+
+/// (instant, valeur) : un capteur scalaire.
+impl UniversalSource for (f64, f64) {
+    fn timestamp(&self) -> f64 { self.0 }
+    fn to_features(&self) -> Vec<f64> { vec![self.1] }
+}
+
+/// (instant, caractéristiques).
+impl UniversalSource for (f64, Vec<f64>) {
+    fn timestamp(&self) -> f64 { self.0 }
+    fn to_features(&self) -> Vec<f64> { self.1.clone() }
+}
@@ -0,0 +1,185 @@
+use crate::perception::source::Sample;
+
+use serde::Deserialize;
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Lines};
+use std::path::Path;
+
+/// Colonnes d'un fichier CSV (indices à partir de 0).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvConfig {
+    pub time_column: usize,
+    pub value_columns: Vec<usize>,
+    /// La première ligne est un en-tête, ignoré.
+    pub has_header: bool,
+}
+
+/// Lignes d'un fichier CSV en `Sample`s. Une cellule de valeur vide donne NaN
+/// (traité par la `NanPolicy` du scanner) ; une ligne malformée (colonne absente,
+/// nombre illisible, instant vide) est ignorée et comptée dans `skipped_rows`.
+pub struct CsvSource<R: BufRead = BufReader<File>> {
+    lines: Lines<R>,
+    config: CsvConfig,
+    line: usize,
+    skipped_rows: usize,
+}
+
+impl CsvSource {
+    pub fn open<P: AsRef<Path>>(path: P, config: CsvConfig) -> io::Result<Self> {
+        Ok(Self::from_reader(BufReader::new(File::open(path)?), config))
+    }
+}
+
+impl<R: BufRead> CsvSource<R> {
+    pub fn from_reader(reader: R, config: CsvConfig) -> Self {
+        CsvSource { lines: reader.lines(), config, line: 0, skipped_rows: 0 }
+    }
+
+    /// Lignes ignorées car malformées ou illisibles.
+    pub fn skipped_rows(&self) -> usize {
+        self.skipped_rows
+    }
+
+    fn parse(&self, line: &str) -> Option<Sample> {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        let t = cells.get(self.config.time_column)?.parse().ok()?;
+        let features = self.config.value_columns.iter()
+            .map(|c| match *cells.get(*c)? {
+                "" => Some(f64::NAN),
+                cell => cell.parse().ok(),
+            })
+            .collect::<Option<Vec<f64>>>()?;
+        Some(Sample::new(t, features))
+    }
+}
+
+impl<R: BufRead> Iterator for CsvSource<R> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        loop {
+            let line = self.lines.next()?;
+            self.line += 1;
+            let Ok(line) = line else {
+                self.skipped_rows += 1;
+                continue;
+            };
+            if (self.line == 1 && self.config.has_header) || line.trim().is_empty() {
+                continue;
+            }
+            match self.parse(&line) {
+                Some(sample) => return Some(sample),
+                None => self.skipped_rows += 1,
+            }
+        }
+    }
+}
+
+/// Une ligne JSONL : `{"t": 0.1, "values": [1.0, null]}`, null pour une valeur manquante.
+#[derive(Deserialize)]
+struct JsonlRow {
+    t: f64,
+    values: Vec<Option<f64>>,
+}
+
+/// Lignes `{"t": .., "values": [..]}` en `Sample`s ; une valeur null donne NaN, une
+/// ligne malformée est ignorée et comptée dans `skipped_rows`.
+pub struct JsonlSource<R: BufRead = BufReader<File>> {
+    lines: Lines<R>,
+    skipped_rows: usize,
+}
+
+impl JsonlSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::from_reader(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> JsonlSource<R> {
+    pub fn from_reader(reader: R) -> Self {
+        JsonlSource { lines: reader.lines(), skipped_rows: 0 }
+    }
+
+    pub fn skipped_rows(&self) -> usize {
+        self.skipped_rows
+    }
+}
+
+impl<R: BufRead> Iterator for JsonlSource<R> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        loop {
+            let Ok(line) = self.lines.next()? else {
+                self.skipped_rows += 1;
+                continue;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<JsonlRow>(&line) {
+                Ok(row) => return Some(Sample::new(row.t, row.values.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect())),
+                Err(_) => self.skipped_rows += 1,
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::universal_scanner::{NanPolicy, UniversalScanner};
+
+    fn fixture(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("archt3_sources_{}_{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_csv_and_jsonl_fixtures() {
+        let mut csv = String::from("label,time,x,y\n");
+        for i in 0..60 {
+            let t = i as f64 * 0.1;
+            match i {
+                // Valeur manquante, puis ligne malformée
+                10 => csv.push_str(&format!("a,{},,{}\n", t, t.cos())),
+                20 => csv.push_str("a,oops,1.0\n"),
+                _ => csv.push_str(&format!("a,{},{},{}\n", t, t.sin(), t.cos())),
+            }
+        }
+        let config = CsvConfig { time_column: 1, value_columns: vec![2, 3], has_header: true };
+        let path = fixture("data.csv", &csv);
+        let mut source = CsvSource::open(&path, config.clone()).unwrap();
+        let samples: Vec<Sample> = source.by_ref().collect();
+        assert_eq!((samples.len(), source.skipped_rows()), (59, 1));
+        assert!(samples[10].features[0].is_nan());
+        assert_eq!(samples[1].t, 0.1);
+
+        // Sans en-tête, la première ligne est une donnée (ici malformée : label)
+        let mut headerless = CsvSource::open(&path, CsvConfig { has_header: false, ..config.clone() }).unwrap();
+        assert_eq!(headerless.by_ref().count(), 59);
+        assert_eq!(headerless.skipped_rows(), 2);
+        let plain = CsvSource::from_reader("0.0,1\n0.5,2\n".as_bytes(), CsvConfig { time_column: 0, value_columns: vec![1], has_header: false });
+        assert_eq!(plain.map(|s| s.features[0]).collect::<Vec<_>>(), vec![1.0, 2.0]);
+
+        // Le scanner ingère tout, la valeur manquante étant traitée par sa NanPolicy
+        let mut scanner = UniversalScanner::new(20);
+        scanner.set_nan_policy(NanPolicy::Skip);
+        let vectors = scanner.ingest_all(CsvSource::open(&path, config).unwrap());
+        assert!(!vectors.is_empty());
+        assert_eq!(scanner.skipped_samples(), 1);
+        let _ = std::fs::remove_file(path);
+
+        let jsonl = "{\"t\": 0.0, \"values\": [1.0, 2.0]}\n{\"t\": 0.1, \"values\": [null, 2.5]}\nnot json\n\n{\"t\": 0.2, \"values\": [1.5, 3.0]}\n";
+        let path = fixture("data.jsonl", jsonl);
+        let mut source = JsonlSource::open(&path).unwrap();
+        let samples: Vec<Sample> = source.by_ref().collect();
+        assert_eq!((samples.len(), source.skipped_rows()), (3, 1));
+        assert!(samples[1].features[0].is_nan());
+        assert_eq!(samples[2].features, vec![1.5, 3.0]);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod file;
+pub mod network;
+pub mod replay;
+
+pub use file::{CsvConfig, CsvSource, JsonlSource};
+pub use network::{FrameLayout, FrameSource, TcpSource, UdpSource};
+pub use replay::ReplaySource;
@@ -0,0 +1,296 @@
+use crate::perception::adaptive_normalizer::Normalizer;
+use crate::perception::error::PerceptionError;
+use crate::perception::source::Sample;
+use crate::perception::universal_scanner::UniversalScanner;
+
+use std::io::{self, Read};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+
+/// Trame binaire : l'instant puis `values` valeurs, tous en f64 little-endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameLayout {
+    pub values: usize,
+}
+
+impl FrameLayout {
+    pub fn new(values: usize) -> Self {
+        FrameLayout { values }
+    }
+
+    /// Taille d'une trame en octets.
+    pub fn frame_len(&self) -> usize {
+        8 * (self.values + 1)
+    }
+
+    pub fn encode(&self, sample: &Sample) -> Vec<u8> {
+        assert_eq!(sample.features.len(), self.values, "Sample does not match the frame layout");
+        std::iter::once(sample.t)
+            .chain(sample.features.iter().copied())
+            .flat_map(f64::to_le_bytes)
+            .collect()
+    }
+
+    pub fn decode(&self, frame: &[u8]) -> Result<Sample, PerceptionError> {
+        if frame.len() != self.frame_len() {
+            return Err(PerceptionError::MalformedFrame { expected: self.frame_len(), got: frame.len() });
+        }
+        let mut words = frame.chunks_exact(8).map(|w| f64::from_le_bytes(w.try_into().unwrap()));
+        let t = words.next().unwrap();
+        Ok(Sample::new(t, words.collect()))
+    }
+}
+
+/// Source réseau non bloquante.
+pub trait FrameSource {
+    /// Prochaine trame déjà reçue, sans attendre ; `None` s'il n'y en a pas.
+    fn poll(&mut self) -> Option<Result<Sample, PerceptionError>>;
+
+    /// Ingère au plus `max_frames` trames disponibles sans bloquer, pour entrelacer
+    /// l'ingestion avec d'autres traitements (un émetteur rapide ne peut pas
+    /// monopoliser l'appelant). Les trames illisibles sont consignées par le
+    /// scanner (`errors`) comme les échantillons rejetés et comptent dans la
+    /// limite. Retourne le nombre d'échantillons acceptés.
+    fn pump<N: Normalizer>(&mut self, scanner: &mut UniversalScanner<N>, max_frames: usize) -> usize {
+        let mut accepted = 0;
+        for _ in 0..max_frames {
+            let Some(frame) = self.poll() else { break };
+            match frame {
+                Ok(sample) => {
+                    if scanner.ingest(&sample).is_ok() {
+                        accepted += 1;
+                    }
+                }
+                Err(e) => scanner.report_error(e),
+            }
+        }
+        accepted
+    }
+}
+
+/// Une trame par datagramme UDP.
+pub struct UdpSource {
+    socket: UdpSocket,
+    layout: FrameLayout,
+    buffer: Vec<u8>,
+}
+
+impl UdpSource {
+    pub fn bind<A: ToSocketAddrs>(addr: A, layout: FrameLayout) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        // Un octet de plus que la trame pour détecter les datagrammes trop longs
+        Ok(UdpSource { socket, layout, buffer: vec![0; layout.frame_len() + 1] })
+    }
+
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub fn layout(&self) -> FrameLayout {
+        self.layout
+    }
+
+    /// Attend la prochaine trame.
+    pub fn recv(&mut self) -> Result<Sample, PerceptionError> {
+        self.socket.set_nonblocking(false)?;
+        let received = self.socket.recv(&mut self.buffer);
+        self.socket.set_nonblocking(true)?;
+        self.layout.decode(&self.buffer[..received?])
+    }
+}
+
+impl FrameSource for UdpSource {
+    fn poll(&mut self) -> Option<Result<Sample, PerceptionError>> {
+        match self.socket.recv(&mut self.buffer) {
+            Ok(n) => Some(self.layout.decode(&self.buffer[..n])),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => None,
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+/// Trames consécutives sur un flux TCP. Une trame tronquée par la fermeture de la
+/// connexion est signalée comme malformée.
+pub struct TcpSource {
+    stream: TcpStream,
+    layout: FrameLayout,
+    pending: Vec<u8>,
+    closed: bool,
+}
+
+impl TcpSource {
+    pub fn connect<A: ToSocketAddrs>(addr: A, layout: FrameLayout) -> io::Result<Self> {
+        Self::from_stream(TcpStream::connect(addr)?, layout)
+    }
+
+    /// Flux déjà établi (connexion acceptée par un `TcpListener`).
+    pub fn from_stream(stream: TcpStream, layout: FrameLayout) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        Ok(TcpSource { stream, layout, pending: Vec::new(), closed: false })
+    }
+
+    pub fn layout(&self) -> FrameLayout {
+        self.layout
+    }
+
+    /// Le pair a fermé la connexion.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Attend la prochaine trame ; `None` une fois la connexion fermée.
+    pub fn recv(&mut self) -> Option<Result<Sample, PerceptionError>> {
+        if let Err(e) = self.stream.set_nonblocking(false) {
+            return Some(Err(e.into()));
+        }
+        let frame = loop {
+            match self.poll() {
+                Some(frame) => break Some(frame),
+                None if self.closed => break None,
+                None => {}
+            }
+        };
+        if let Err(e) = self.stream.set_nonblocking(true) {
+            return Some(Err(e.into()));
+        }
+        frame
+    }
+
+    fn take_frame(&mut self) -> Option<Result<Sample, PerceptionError>> {
+        let len = self.layout.frame_len();
+        if self.pending.len() >= len {
+            let frame: Vec<u8> = self.pending.drain(..len).collect();
+            return Some(self.layout.decode(&frame));
+        }
+        None
+    }
+}
+
+impl FrameSource for TcpSource {
+    fn poll(&mut self) -> Option<Result<Sample, PerceptionError>> {
+        if let Some(frame) = self.take_frame() {
+            return Some(frame);
+        }
+        if self.closed {
+            return None;
+        }
+        let mut chunk = [0u8; 4096];
+        match self.stream.read(&mut chunk) {
+            Ok(0) => {
+                self.closed = true;
+                let got = std::mem::take(&mut self.pending).len();
+                (got > 0).then(|| Err(PerceptionError::MalformedFrame { expected: self.layout.frame_len(), got }))
+            }
+            Ok(n) => {
+                self.pending.extend_from_slice(&chunk[..n]);
+                self.take_frame()
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => None,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => None,
+            Err(e) => {
+                self.closed = true;
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    fn frames(layout: FrameLayout) -> Vec<Sample> {
+        (0..5).map(|i| {
+            let t = 0.1 * i as f64;
+            Sample::new(t, (0..layout.values).map(|k| t * 10.0 + k as f64).collect())
+        }).collect()
+    }
+
+    // Interroge la source jusqu'à `n` trames (les paquets loopback arrivent vite)
+    fn collect<S: FrameSource>(source: &mut S, n: usize) -> Vec<Result<Sample, PerceptionError>> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut out = Vec::new();
+        while out.len() < n && Instant::now() < deadline {
+            match source.poll() {
+                Some(frame) => out.push(frame),
+                None => std::thread::sleep(Duration::from_millis(1)),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_loopback_sources() {
+        let layout = FrameLayout::new(2);
+        let sent = frames(layout);
+
+        // UDP : trames valides, puis une trame tronquée consignée par le scanner
+        let mut udp = UdpSource::bind("127.0.0.1:0", layout).unwrap();
+        assert!(udp.poll().is_none());
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        for s in &sent[..3] {
+            sender.send_to(&layout.encode(s), udp.local_addr().unwrap()).unwrap();
+        }
+        let received: Vec<Sample> = collect(&mut udp, 3).into_iter().map(Result::unwrap).collect();
+        assert_eq!(received, sent[..3]);
+
+        sender.send_to(&layout.encode(&sent[3]), udp.local_addr().unwrap()).unwrap();
+        sender.send_to(&[0u8; 5], udp.local_addr().unwrap()).unwrap();
+        let mut scanner = UniversalScanner::new(16);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut accepted = 0;
+        while scanner.error_count() == 0 && Instant::now() < deadline {
+            accepted += udp.pump(&mut scanner, 1);
+        }
+        assert_eq!(accepted, 1);
+        assert_eq!(scanner.stats().ingested, 1);
+        assert_eq!(scanner.errors(), &[PerceptionError::MalformedFrame { expected: 24, got: 5 }]);
+
+        // TCP : trames découpées arbitrairement, dernière trame tronquée
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut tcp = TcpSource::connect(listener.local_addr().unwrap(), layout).unwrap();
+        let (mut peer, _) = listener.accept().unwrap();
+        let bytes: Vec<u8> = sent.iter().flat_map(|s| layout.encode(s)).collect();
+        for chunk in bytes.chunks(7) {
+            peer.write_all(chunk).unwrap();
+        }
+        peer.write_all(&[1, 2, 3]).unwrap();
+        drop(peer);
+
+        let received = collect(&mut tcp, sent.len() + 1);
+        assert_eq!(received.len(), sent.len() + 1);
+        for (frame, expected) in received.iter().zip(&sent) {
+            assert_eq!(frame.as_ref().unwrap(), expected);
+        }
+        assert_eq!(received[sent.len()], Err(PerceptionError::MalformedFrame { expected: 24, got: 3 }));
+        assert!(tcp.is_closed());
+        assert!(tcp.poll().is_none());
+    }
+
+    // Source qui a toujours une trame prête
+    struct Endless {
+        polled: usize,
+    }
+
+    impl FrameSource for Endless {
+        fn poll(&mut self) -> Option<Result<Sample, PerceptionError>> {
+            self.polled += 1;
+            Some(Ok(Sample::new(0.01 * self.polled as f64, vec![0.0, 1.0])))
+        }
+    }
+
+    #[test]
+    fn test_pump_is_bounded() {
+        let mut source = Endless { polled: 0 };
+        let mut scanner = UniversalScanner::new(16);
+        assert_eq!(source.pump(&mut scanner, 3), 3);
+        assert_eq!(source.polled, 3);
+        assert_eq!(source.pump(&mut scanner, 0), 0);
+        assert_eq!(source.polled, 3);
+    }
+}
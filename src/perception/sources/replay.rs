@@ -0,0 +1,99 @@
+use crate::perception::source::Sample;
+
+use std::thread;
+use std::time::Duration;
+
+/// Rejoue un enregistrement à `speed` fois le temps réel. Les instants sont réécrits
+/// sur l'horloge du rejeu : le premier est conservé, les écarts suivants sont divisés
+/// par `speed`. Par défaut l'itérateur attend entre deux échantillons comme le ferait
+/// la source réelle ; `no_sleep` ne fait que réécrire les instants (tests déterministes).
+#[derive(Debug, Clone)]
+pub struct ReplaySource {
+    samples: std::vec::IntoIter<Sample>,
+    speed: f64,
+    sleep: bool,
+    // Premier instant de l'enregistrement, dernier instant réécrit
+    origin: Option<f64>,
+    last: Option<f64>,
+}
+
+impl ReplaySource {
+    pub fn new(samples: Vec<Sample>, speed: f64) -> Self {
+        assert!(speed > 0.0, "Replay speed must be positive");
+        ReplaySource { samples: samples.into_iter(), speed, sleep: true, origin: None, last: None }
+    }
+
+    /// Horloge virtuelle : aucune attente.
+    pub fn no_sleep(mut self) -> Self {
+        self.sleep = false;
+        self
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Échantillons restant à rejouer.
+    pub fn remaining(&self) -> usize {
+        self.samples.len()
+    }
+}
+
+impl Iterator for ReplaySource {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        let mut sample = self.samples.next()?;
+        let origin = *self.origin.get_or_insert(sample.t);
+        sample.t = origin + (sample.t - origin) / self.speed;
+        if let Some(last) = self.last.filter(|_| self.sleep) {
+            thread::sleep(Duration::from_secs_f64((sample.t - last).max(0.0)));
+        }
+        self.last = Some(sample.t);
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.samples.size_hint()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::universal_scanner::UniversalScanner;
+
+    fn recording() -> Vec<Sample> {
+        (0..150).map(|i| {
+            let t = 3.0 + i as f64 * 0.04;
+            Sample::new(t, vec![(t * 2.0).sin(), (t * 0.7).cos()])
+        }).collect()
+    }
+
+    #[test]
+    fn test_replay_rewrites_clock() {
+        let fast: Vec<Sample> = ReplaySource::new(recording(), 2.0).no_sleep().collect();
+        assert_eq!(fast[0].t, 3.0);
+        for (pair, original) in fast.windows(2).zip(recording().windows(2)) {
+            assert!(((pair[1].t - pair[0].t) - (original[1].t - original[0].t) / 2.0).abs() < 1e-12);
+            assert_eq!(pair[1].features, original[1].features);
+        }
+
+        // Au temps réel, le rejeu à travers le scanner redonne les vecteurs d'origine
+        let run = |samples: Vec<Sample>| {
+            let mut scanner = UniversalScanner::new(32);
+            let mut vectors = scanner.ingest_all(samples);
+            vectors.extend(scanner.drain());
+            vectors
+        };
+        let replayed = run(ReplaySource::new(recording(), 1.0).no_sleep().collect());
+        assert!(replayed.len() > 3);
+        assert_eq!(replayed, run(recording()));
+
+        // Avec attente : 5 écarts de 40 ms rejoués à 4× prennent au moins 50 ms
+        let started = std::time::Instant::now();
+        assert_eq!(ReplaySource::new(recording()[..6].to_vec(), 4.0).count(), 6);
+        assert!(started.elapsed() >= Duration::from_millis(49));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::perception::universal_vector::{UniversalVector, Signature, Gradient, Metadata, DEFAULT_DEPTH};
+
+/// Accumulation incrémentale d'une signature, un incrément à la fois
+/// (identité de Chen : S ← S ⊗ exp(dt, dx)). La signature courante est lisible à tout
+/// moment, pour les réflexes à faible latence.
+///
+/// C'est aussi le chemin d'accumulation unique du `UniversalTransducer`. Les mises à
+/// jour se font en place (`Signature::combine_assign`) ; les variantes bornées
+/// travaillent sur une copie de travail réutilisée, sans allocation de signature.
+#[derive(Debug, Clone)]
+pub struct StreamingSignature {
+    signature: Signature,
+    deltas: Vec<(f64, Vec<f64>)>,
+    // Candidat des variantes `try_push`, réutilisé d'un incrément à l'autre
+    scratch: Signature,
+}
+
+impl PartialEq for StreamingSignature {
+    fn eq(&self, other: &Self) -> bool {
+        self.signature == other.signature && self.deltas == other.deltas
+    }
+}
+
+impl StreamingSignature {
+    /// `channels` canaux bruts ; la signature a la dimension channels + 1 (temps).
+    pub fn new(channels: usize) -> Self {
+        Self::with_depth(channels, DEFAULT_DEPTH)
+    }
+
+    pub fn with_depth(channels: usize, depth: usize) -> Self {
+        Self::augmented(channels + 1, depth)
+    }
+
+    /// Signature de dimension `signature_dim` quelconque, alimentée par `push_augmented`
+    /// (chemins transformés : lead-lag, sans canal de temps...).
+    pub fn augmented(signature_dim: usize, depth: usize) -> Self {
+        StreamingSignature {
+            signature: Signature::zero_with_depth(signature_dim, depth),
+            deltas: Vec::new(),
+            scratch: Signature::zero_with_depth(signature_dim, depth),
+        }
+    }
+
+    pub fn push(&mut self, dt: f64, dx: &[f64]) {
+        let step = self.increment(dt, dx);
+        self.signature.combine_assign(&step);
+        self.deltas.push((dt, dx.to_vec()));
+    }
+
+    /// Enregistre l'incrément brut (dt, dx) pour le gradient, mais fait avancer la
+    /// signature par les sous-incréments `pieces` (chacun de dimension `current().dim`).
+    pub fn push_augmented(&mut self, dt: f64, dx: &[f64], pieces: &[Vec<f64>]) {
+        let depth = self.signature.depth;
+        for piece in pieces {
+            assert_eq!(piece.len(), self.signature.dim, "Augmented increment dimension mismatch");
+            self.signature.combine_assign(&Signature::from_increment_with_depth(piece, depth));
+        }
+        self.deltas.push((dt, dx.to_vec()));
+    }
+
+    /// `try_push` pour `push_augmented`.
+    pub fn try_push_augmented(&mut self, dt: f64, dx: &[f64], pieces: &[Vec<f64>], bound: f64) -> bool {
+        let depth = self.signature.depth;
+        self.load_scratch();
+        for piece in pieces {
+            assert_eq!(piece.len(), self.signature.dim, "Augmented increment dimension mismatch");
+            self.scratch.combine_assign(&Signature::from_increment_with_depth(piece, depth));
+        }
+        self.accept_scratch(dt, dx, bound)
+    }
+
+    /// Comme `push`, sauf si un terme de la signature résultante dépasserait `bound`
+    /// alors que des incréments sont déjà accumulés : rien n'est modifié et false est
+    /// retourné (l'appelant émet alors `snapshot_and_reset` et repousse l'incrément).
+    pub fn try_push(&mut self, dt: f64, dx: &[f64], bound: f64) -> bool {
+        let step = self.increment(dt, dx);
+        self.load_scratch();
+        self.scratch.combine_assign(&step);
+        self.accept_scratch(dt, dx, bound)
+    }
+
+    fn increment(&self, dt: f64, dx: &[f64]) -> Signature {
+        assert_eq!(dx.len() + 1, self.signature.dim, "Increment dimension mismatch in stream");
+        Signature::from_segment_with_depth(dt, dx, self.signature.depth)
+    }
+
+    /// Copie la signature courante dans `scratch` en réutilisant ses allocations
+    /// (le `clone_from` dérivé de Signature réallouerait).
+    fn load_scratch(&mut self) {
+        self.scratch.dim = self.signature.dim;
+        self.scratch.depth = self.signature.depth;
+        self.scratch.levels.clone_from(&self.signature.levels);
+    }
+
+    /// Adopte le candidat `scratch` s'il respecte la borne (ou s'il est le premier incrément).
+    fn accept_scratch(&mut self, dt: f64, dx: &[f64], bound: f64) -> bool {
+        let max_abs = self.scratch.max_abs();
+        if !self.deltas.is_empty() && (max_abs > bound || max_abs.is_nan()) {
+            return false;
+        }
+        std::mem::swap(&mut self.signature, &mut self.scratch);
+        self.deltas.push((dt, dx.to_vec()));
+        true
+    }
+
+    pub fn current(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// Nombre d'incréments accumulés depuis le dernier reset.
+    pub fn increments(&self) -> usize {
+        self.deltas.len()
+    }
+
+    pub fn reset(&mut self) {
+        self.signature = Signature::zero_with_depth(self.signature.dim, self.signature.depth);
+        self.deltas.clear();
+    }
+
+    /// Vecteur fini (signature + gradient des incréments), métadonnées par défaut,
+    /// puis remise à l'identité.
+    pub fn snapshot_and_reset(&mut self) -> UniversalVector {
+        let zero = Signature::zero_with_depth(self.signature.dim, self.signature.depth);
+        let vector = UniversalVector {
+            signature: std::mem::replace(&mut self.signature, zero),
+            gradient: Gradient::update(&self.deltas),
+            metadata: Metadata::builder().build(),
+        };
+        self.deltas.clear();
+        vector
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::universal_transducer::UniversalTransducer;
+
+    #[test]
+    fn test_streaming_matches_batch_bit_for_bit() {
+        // Segment monotone sur le canal 0 : le transducer produit un seul vecteur
+        let times: Vec<f64> = (0..40).map(|i| i as f64 * 0.05).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![t * t, (t * 3.0).sin()]).collect();
+        let batch = UniversalTransducer::segment_and_process(&raw, &times).unwrap();
+        assert_eq!(batch.len(), 1);
+
+        let mut stream = StreamingSignature::new(2);
+        for i in 1..raw.len() {
+            let dx: Vec<f64> = raw[i].iter().zip(raw[i - 1].iter()).map(|(a, b)| a - b).collect();
+            stream.push(times[i] - times[i - 1], &dx);
+        }
+        assert_eq!(stream.increments(), raw.len() - 1);
+        assert_eq!(stream.current(), &batch[0].signature);
+
+        let v = stream.snapshot_and_reset();
+        assert_eq!(v.signature, batch[0].signature);
+        assert_eq!(v.gradient, batch[0].gradient);
+        assert_eq!(stream.increments(), 0);
+        assert_eq!(stream.current(), &Signature::zero(3));
+    }
+
+    #[test]
+    fn test_try_push_respects_bound() {
+        let mut stream = StreamingSignature::with_depth(1, 2);
+        assert!(stream.try_push(1.0, &[10.0], 1.0));
+        assert!(!stream.try_push(1.0, &[10.0], 1.0));
+        assert_eq!(stream.increments(), 1);
+
+        stream.reset();
+        assert_eq!(stream.increments(), 0);
+        assert_eq!(stream.current(), &Signature::zero_with_depth(2, 2));
+    }
+}
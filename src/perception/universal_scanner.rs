@@ -1,107 +1,1532 @@
-use crate::perception::adaptative_normalizer::AdaptiveNormalizer;
-use crate::perception::universal_vector::{UniversalVector, Metadata, Modality};
-use crate::perception::universal_transducer::UniversalTransducer;
+use crate::perception::adaptive_normalizer::{AdaptiveNormalizer, Normalizer, NormalizerError, NormalizerMode, NormalizerState};
+use crate::perception::error::PerceptionError;
+use crate::perception::universal_vector::{UniversalVector, Modality};
+pub use crate::perception::source::UniversalSource;
+use crate::perception::universal_transducer::{UniversalTransducer, ChunkedTransducer, TransducerOptions, SegmentationConfig, SegmentedVector, InterpKind, PreFilter, TransducerError};
 
 use std::collections::VecDeque;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
 
-pub struct UniversalScanner {
-    // Tampons pour le Transducer
-    raw_buffer: Vec<Vec<f64>>,
-    time_buffer: Vec<f64>,
+/// Erreurs conservées au plus en attente de `take_errors` (les suivantes sont
+/// seulement comptées).
+const MAX_PENDING_ERRORS: usize = 256;
+
+/// Seuil par défaut du score de dérive (z-score de la moyenne récente).
+pub const DEFAULT_DRIFT_THRESHOLD: f64 = 6.0;
+
+/// Traitement d'un échantillon contenant des valeurs NaN ou infinies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// Ignore l'échantillon (comptabilisé dans `skipped_samples`).
+    #[default]
+    Skip,
+    /// Remplace chaque valeur non finie par la moyenne courante (z-score nul).
+    Clamp,
+    /// Ignore l'échantillon, enregistre l'erreur dans `errors()` et la retourne.
+    RecordError,
+}
+
+/// Traitement des échantillons normalisés aberrants (|z| > z_max sur une dimension).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ClipPolicy {
+    #[default]
+    None,
+    /// Ramène chaque dimension dans [−z_max, z_max], indépendamment.
+    Clamp { z_max: f64 },
+    /// Ignore l'échantillon (comptabilisé dans `dropped_outliers`).
+    Drop { z_max: f64 },
+}
+
+/// Ingestion dans un tampon plein (consommateur qui ne vide plus les lots).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Retire le plus ancien échantillon du tampon.
+    #[default]
+    DropOldest,
+    /// Ignore le nouvel échantillon.
+    DropNewest,
+    /// Refuse le nouvel échantillon avec `PerceptionError::BufferOverflow`.
+    ErrorOnOverflow,
+}
+
+/// Traitement d'un échantillon dont l'instant ne dépasse pas le précédent (paquets
+/// UDP désordonnés) : le transducer refuserait un dt nul ou négatif.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimePolicy {
+    /// Ignore l'échantillon (comptabilisé dans `skipped_samples`).
+    #[default]
+    Reject,
+    /// Retient les `window` derniers échantillons dans une fenêtre triée avant de les
+    /// transmettre : un retard de moins de `window` échantillons est réordonné, un
+    /// retard plus grand (ou un instant en double) est ignoré.
+    Reorder { window: usize },
+    /// Avance l'instant à dernier + `epsilon`.
+    ClampForward { epsilon: f64 },
+}
+
+/// Taille des lots et contexte conservé d'un lot à l'autre.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScannerConfig {
+    /// Nouveaux échantillons nécessaires pour lancer le Transducer.
+    pub batch_size: usize,
+    /// Derniers échantillons d'un lot conservés en tête du suivant, comme contexte :
+    /// ils amorcent le lissage de la segmentation (`set_prefilter`) et l'interpolation
+    /// du rééchantillonnage. Ils ne sont pas retransduits : le segment inachevé est
+    /// porté par le `ChunkedTransducer`. Avec 0, le lissage repart à chaque lot.
+    pub overlap: usize,
+    /// Écrêtage appliqué après la normalisation.
+    pub clip: ClipPolicy,
+    /// Âge (en temps du flux) au-delà duquel `poll` vide le lot sans attendre
+    /// `batch_size` échantillons.
+    pub max_batch_age: Option<Duration>,
+    /// Échantillons au plus dans le tampon, contexte compris (illimité par défaut).
+    pub max_buffer: usize,
+    pub overflow: OverflowPolicy,
+    pub time_policy: TimePolicy,
+    /// Échantillons de calibration au démarrage (`calibrate_for`), 0 pour aucun.
+    pub calibration: usize,
+    /// Mode du normaliseur par défaut (`with_config`).
+    pub normalizer_mode: NormalizerMode,
+    /// Longueur minimale d'un segment, en échantillons (au moins 2).
+    pub min_points: usize,
+    /// Longueur au-delà de laquelle un segment est coupé (`batch_size` par défaut) ;
+    /// `None` : seules les ruptures naturelles le terminent.
+    pub max_segment_points: Option<usize>,
+    /// Score de dérive du normaliseur (`Normalizer::drift_score`) au-delà duquel un
+    /// événement est compté dans `ScannerStats::drift_events`.
+    pub drift_threshold: f64,
+}
+
+impl ScannerConfig {
+    pub fn new(batch_size: usize) -> Self {
+        ScannerConfig {
+            batch_size,
+            overlap: 1,
+            clip: ClipPolicy::None,
+            max_batch_age: None,
+            max_buffer: usize::MAX,
+            overflow: OverflowPolicy::DropOldest,
+            time_policy: TimePolicy::Reject,
+            calibration: 0,
+            normalizer_mode: NormalizerMode::Cumulative,
+            min_points: SegmentationConfig::default().min_points,
+            max_segment_points: Some(batch_size),
+            drift_threshold: DEFAULT_DRIFT_THRESHOLD,
+        }
+    }
+
+    /// Configuration validée champ par champ :
+    /// `ScannerConfig::builder().batch_size(64).overlap(4).build()?`.
+    pub fn builder() -> ScannerConfigBuilder {
+        ScannerConfigBuilder { config: ScannerConfig::new(0) }
+    }
+
+    /// Vérifie les contraintes entre champs.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.batch_size == 0 {
+            return Err(ConfigError::ZeroBatchSize);
+        }
+        if self.overlap >= self.batch_size {
+            return Err(ConfigError::OverlapTooLarge { overlap: self.overlap, batch_size: self.batch_size });
+        }
+        if self.max_buffer < self.batch_size {
+            return Err(ConfigError::BufferTooSmall { max_buffer: self.max_buffer, batch_size: self.batch_size });
+        }
+        if self.min_points < 2 || self.min_points > self.batch_size {
+            return Err(ConfigError::InvalidMinPoints { min_points: self.min_points, batch_size: self.batch_size });
+        }
+        if self.drift_threshold.is_nan() || self.drift_threshold <= 0.0 {
+            return Err(ConfigError::InvalidDriftThreshold(self.drift_threshold));
+        }
+        Ok(())
+    }
+}
+
+/// Construction pas à pas d'une `ScannerConfig`, validée par `build`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScannerConfigBuilder {
+    config: ScannerConfig,
+}
+
+impl ScannerConfigBuilder {
+    /// La coupure des segments suit `batch_size` tant qu'elle n'est pas imposée.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        if self.config.max_segment_points == Some(self.config.batch_size) {
+            self.config.max_segment_points = Some(batch_size);
+        }
+        self.config.batch_size = batch_size;
+        self
+    }
+
+    pub fn overlap(mut self, overlap: usize) -> Self {
+        self.config.overlap = overlap;
+        self
+    }
+
+    pub fn clip(mut self, clip: ClipPolicy) -> Self {
+        self.config.clip = clip;
+        self
+    }
+
+    pub fn max_batch_age(mut self, max_age: Duration) -> Self {
+        self.config.max_batch_age = Some(max_age);
+        self
+    }
+
+    pub fn max_buffer(mut self, max_buffer: usize, overflow: OverflowPolicy) -> Self {
+        self.config.max_buffer = max_buffer;
+        self.config.overflow = overflow;
+        self
+    }
+
+    pub fn time_policy(mut self, time_policy: TimePolicy) -> Self {
+        self.config.time_policy = time_policy;
+        self
+    }
+
+    pub fn calibration(mut self, n_samples: usize) -> Self {
+        self.config.calibration = n_samples;
+        self
+    }
+
+    pub fn normalizer_mode(mut self, mode: NormalizerMode) -> Self {
+        self.config.normalizer_mode = mode;
+        self
+    }
+
+    pub fn min_points(mut self, min_points: usize) -> Self {
+        self.config.min_points = min_points;
+        self
+    }
+
+    pub fn max_segment_points(mut self, max_points: Option<usize>) -> Self {
+        self.config.max_segment_points = max_points;
+        self
+    }
+
+    pub fn drift_threshold(mut self, threshold: f64) -> Self {
+        self.config.drift_threshold = threshold;
+        self
+    }
+
+    pub fn build(self) -> Result<ScannerConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Configuration de scanner incohérente.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigError {
+    ZeroBatchSize,
+    /// Le contexte conservé doit laisser place à au moins un nouvel échantillon.
+    OverlapTooLarge { overlap: usize, batch_size: usize },
+    /// Le tampon doit pouvoir contenir un lot complet.
+    BufferTooSmall { max_buffer: usize, batch_size: usize },
+    /// Un segment valide doit tenir dans un lot (et compter au moins 2 points).
+    InvalidMinPoints { min_points: usize, batch_size: usize },
+    InvalidDriftThreshold(f64),
+    /// Le pas de rééchantillonnage doit être fini et strictement positif.
+    InvalidResamplingStep(f64),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::ZeroBatchSize => write!(f, "batch_size must be positive"),
+            ConfigError::OverlapTooLarge { overlap, batch_size } => {
+                write!(f, "overlap ({}) must be smaller than batch_size ({})", overlap, batch_size)
+            }
+            ConfigError::BufferTooSmall { max_buffer, batch_size } => {
+                write!(f, "max_buffer ({}) must hold at least one batch ({})", max_buffer, batch_size)
+            }
+            ConfigError::InvalidMinPoints { min_points, batch_size } => {
+                write!(f, "min_points ({}) must be in 2..={} (batch_size)", min_points, batch_size)
+            }
+            ConfigError::InvalidDriftThreshold(threshold) => write!(f, "drift_threshold must be positive, got {}", threshold),
+            ConfigError::InvalidResamplingStep(dt) => write!(f, "resampling step must be finite and positive, got {}", dt),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Phase du scanner : pendant la calibration, les échantillons n'alimentent que
+/// le normaliseur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScannerPhase {
+    Calibrating { remaining: usize },
+    #[default]
+    Running,
+}
+
+/// Compteurs cumulés depuis la création du scanner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScannerStats {
+    /// Échantillons passés à `ingest`, rejetés compris.
+    pub ingested: usize,
+    /// Lots transduits (`process_and_flush`, `poll`, `drain`).
+    pub flushed_batches: usize,
+    /// Vecteurs retournés par ces lots.
+    pub produced_vectors: usize,
+    /// Échantillons écartés par `ClipPolicy::Drop`.
+    pub dropped_outliers: usize,
+    /// Erreurs enregistrées (échantillons ou lots rejetés).
+    pub errors: usize,
+    /// Ingestions dans un tampon plein (`max_buffer`).
+    pub overflowed: usize,
+    /// Échantillons arrivés avec un instant non croissant (`TimePolicy`).
+    pub out_of_order: usize,
+    /// Passages du score de dérive au-dessus de `drift_threshold`.
+    pub drift_events: usize,
+}
+
+/// Sort du segment inachevé du transducer lors d'un flush.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tail {
+    /// Conservé pour le lot suivant.
+    Keep,
+    /// Émis, l'état du transducer continuant (`poll`).
+    Cut,
+    /// Émis, et le transducer remis à zéro (`drain`).
+    Finish,
+}
+
+/// Le normaliseur est un `AdaptiveNormalizer` par défaut ; tout `Normalizer` convient
+/// (`with_normalizer`), par exemple un `WhiteningNormalizer`.
+pub struct UniversalScanner<N: Normalizer = AdaptiveNormalizer> {
+    // Tampons circulaires pour le Transducer : `carried` échantillons de contexte,
+    // puis les nouveaux
+    raw_buffer: VecDeque<Vec<f64>>,
+    time_buffer: VecDeque<f64>,
+    carried: usize,
+    // Conserve le segment inachevé et la direction courante d'un lot à l'autre
+    transducer: ChunkedTransducer,
 
     // Composants internes
-    normalizer: AdaptiveNormalizer,
-    batch_size: usize,
+    normalizer: N,
+    config: ScannerConfig,
 
     // Option: pour geler l'apprentissage après une période de calibration ?
     learning_enabled: bool,
+    phase: ScannerPhase,
+    // Gèle l'apprentissage à la fin de la calibration
+    freeze_after_calibration: bool,
+    // Longueur de la dernière calibration demandée, reprise par `reset_all`
+    calibration_len: usize,
+
+    // Modalité de la dernière source ingérée, reportée dans les métadonnées, ou
+    // modalité imposée à toutes les sources
+    modality: Modality,
+    fixed_modality: Option<Modality>,
+    // Plus faible fiabilité déclarée par les sources depuis le dernier flush
+    batch_reliability: f64,
+
+    // Robustesse aux valeurs non finies
+    nan_policy: NanPolicy,
+    skipped_samples: usize,
+    errors: Vec<PerceptionError>,
+    stats: ScannerStats,
+
+    // Déduplication des segments consécutifs identiques (capteur bloqué)
+    dedup_tolerance: Option<f64>,
+    last_fingerprint: Option<u64>,
+    duplicates_dropped: usize,
+
+    // Rééchantillonnage des lots (pas, interpolation) et prochain instant de la grille
+    resampling: Option<(f64, InterpKind)>,
+    next_grid_t: Option<f64>,
+
+    // Plus grand instant accepté, dernier instant transmis au tampon et fenêtre de
+    // réordonnancement (`TimePolicy::Reorder`)
+    latest_t: Option<f64>,
+    committed_t: Option<f64>,
+    reorder: VecDeque<(f64, Vec<f64>)>,
+
+    // Abonnés servis automatiquement par `ingest` à chaque lot complet
+    subscribers: Vec<Sender<Vec<UniversalVector>>>,
 }
 
 impl UniversalScanner {
     pub fn new(batch_size: usize) -> Self {
-        UniversalScanner {
-            raw_buffer: Vec::with_capacity(batch_size),
-            time_buffer: Vec::with_capacity(batch_size),
-            normalizer: AdaptiveNormalizer::new(),
-            batch_size,
+        Self::with_config(ScannerConfig::new(batch_size))
+    }
+
+    /// Constructeur principal ; `new(batch_size)` en est le raccourci. La
+    /// configuration n'est pas revalidée (voir `ScannerConfig::builder`).
+    pub fn with_config(config: ScannerConfig) -> Self {
+        Self::with_normalizer(config, AdaptiveNormalizer::with_mode(config.normalizer_mode))
+    }
+
+    /// Scanner dont tous les vecteurs portent `modality`, quelle que soit celle
+    /// déclarée par les sources.
+    pub fn new_with_modality(batch_size: usize, modality: Modality) -> Self {
+        let mut scanner = Self::new(batch_size);
+        scanner.set_modality(Some(modality));
+        scanner
+    }
+
+    /// Repart d'un normaliseur vierge dans le mode donné.
+    pub fn set_normalizer_mode(&mut self, mode: NormalizerMode) {
+        self.normalizer = AdaptiveNormalizer::with_mode(mode);
+    }
+
+    /// Statistiques du normaliseur, à sauvegarder après une calibration.
+    pub fn export_normalizer(&self) -> NormalizerState {
+        self.normalizer.to_state()
+    }
+
+    /// Injecte un état calibré, avant l'ingestion de préférence. Refusé s'il est
+    /// incohérent ou si des échantillons d'une autre dimension sont déjà en attente.
+    pub fn import_normalizer(&mut self, state: NormalizerState) -> Result<(), NormalizerError> {
+        let normalizer = AdaptiveNormalizer::from_state(state)?;
+        match (normalizer.dim(), self.raw_buffer.back()) {
+            (Some(expected), Some(pending)) if pending.len() != expected => {
+                return Err(NormalizerError::DimensionMismatch { expected, got: pending.len() });
+            }
+            _ => {}
+        }
+        self.normalizer = normalizer;
+        Ok(())
+    }
+}
+
+impl<N: Normalizer> UniversalScanner<N> {
+    pub fn with_normalizer(config: ScannerConfig, normalizer: N) -> Self {
+        let capacity = config.batch_size + config.overlap;
+        let mut scanner = UniversalScanner {
+            raw_buffer: VecDeque::with_capacity(capacity),
+            time_buffer: VecDeque::with_capacity(capacity),
+            carried: 0,
+            transducer: ChunkedTransducer::new(TransducerOptions {
+                segmentation: SegmentationConfig {
+                    min_points: config.min_points,
+                    max_points: config.max_segment_points,
+                    ..SegmentationConfig::default()
+                },
+                ..TransducerOptions::default()
+            }),
+            normalizer,
+            config,
             learning_enabled: true,
+            phase: ScannerPhase::Running,
+            freeze_after_calibration: false,
+            calibration_len: 0,
+            modality: Modality::Sensor,
+            fixed_modality: None,
+            batch_reliability: 1.0,
+            nan_policy: NanPolicy::default(),
+            skipped_samples: 0,
+            errors: Vec::new(),
+            stats: ScannerStats::default(),
+            dedup_tolerance: None,
+            last_fingerprint: None,
+            duplicates_dropped: 0,
+            resampling: None,
+            next_grid_t: None,
+            latest_t: None,
+            committed_t: None,
+            reorder: VecDeque::new(),
+            subscribers: Vec::new(),
+        };
+        scanner.calibrate_for(config.calibration);
+        scanner
+    }
+
+    pub fn config(&self) -> &ScannerConfig {
+        &self.config
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.config.batch_size
+    }
+
+    /// Échantillons dans le tampon, contexte compris.
+    pub fn buffer_len(&self) -> usize {
+        self.raw_buffer.len()
+    }
+
+    pub fn learning_enabled(&self) -> bool {
+        self.learning_enabled
+    }
+
+    pub fn stats(&self) -> ScannerStats {
+        self.stats
+    }
+
+    pub fn normalizer(&self) -> &N {
+        &self.normalizer
+    }
+
+    /// Score de dérive courant du normaliseur (voir `AdaptiveNormalizer::track_drift`).
+    pub fn drift_score(&self) -> f64 {
+        self.normalizer.drift_score()
+    }
+
+    /// Le score de dérive dépasse `drift_threshold` : la distribution d'entrée a
+    /// changé (capteur déplacé), une recalibration (`reset_all`) peut s'imposer.
+    pub fn is_drifting(&self) -> bool {
+        self.normalizer.drift_score() > self.config.drift_threshold
+    }
+
+    pub fn phase(&self) -> ScannerPhase {
+        self.phase
+    }
+
+    /// Les `n_samples` prochains échantillons valides calibrent le normaliseur sans
+    /// être transmis au transducer (pas de z-scores calculés sur trop peu de données),
+    /// puis le scanner passe en `Running`.
+    pub fn calibrate_for(&mut self, n_samples: usize) {
+        self.calibration_len = n_samples;
+        self.phase = match n_samples {
+            0 => ScannerPhase::Running,
+            remaining => ScannerPhase::Calibrating { remaining },
+        };
+    }
+
+    /// Si vrai, l'apprentissage est gelé (`stop_learning`) à la fin de la calibration.
+    pub fn set_freeze_after_calibration(&mut self, freeze: bool) {
+        self.freeze_after_calibration = freeze;
+    }
+
+    /// Impose la modalité des vecteurs produits ; `None` reprend celle déclarée par
+    /// chaque source (`UniversalSource::modality`).
+    pub fn set_modality(&mut self, modality: Option<Modality>) {
+        self.fixed_modality = modality;
+    }
+
+    pub fn modality(&self) -> Option<&Modality> {
+        self.fixed_modality.as_ref()
+    }
+
+    /// Livraison poussée : dès qu'un lot est complet, `ingest` le traite et en envoie
+    /// une copie des vecteurs (s'il y en a) à chaque abonné. Un abonné dont le
+    /// récepteur a été abandonné est simplement retiré.
+    pub fn subscribe(&mut self) -> Receiver<Vec<UniversalVector>> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Active (Some(pas)) ou désactive le rééchantillonnage de chaque lot sur une grille
+    /// régulière avant transduction. La grille se poursuit d'un lot à l'autre (en
+    /// interpolant depuis le contexte : `overlap` ≥ 1) ; les indices des segments
+    /// comptent alors les points de la grille. Un pas non fini ou non positif est
+    /// refusé sans rien modifier.
+    pub fn set_resampling(&mut self, target_dt: Option<f64>, kind: InterpKind) -> Result<(), ConfigError> {
+        if let Some(dt) = target_dt.filter(|dt| !(dt.is_finite() && *dt > 0.0)) {
+            return Err(ConfigError::InvalidResamplingStep(dt));
+        }
+        self.resampling = target_dt.map(|dt| (dt, kind));
+        self.next_grid_t = None;
+        Ok(())
+    }
+
+    /// Écart entre deux échantillons au-delà duquel le segment courant est coupé
+    /// (et le rééchantillonnage n'interpole pas).
+    pub fn set_gap_threshold(&mut self, gap_threshold: Option<f64>) {
+        self.transducer.options_mut().segmentation.gap_threshold = gap_threshold;
+    }
+
+    /// Lissage des échantillons de détection des ruptures (voir `PreFilter`), amorcé
+    /// à chaque lot par les `overlap` échantillons de contexte.
+    pub fn set_prefilter(&mut self, prefilter: PreFilter) {
+        self.transducer.options_mut().segmentation.prefilter = prefilter;
+    }
+
+    /// Active (Some(tolérance)) ou désactive la suppression des vecteurs consécutifs
+    /// de même `Signature::fingerprint`, y compris d'un flush au suivant.
+    pub fn set_dedup(&mut self, tolerance: Option<f64>) {
+        self.dedup_tolerance = tolerance;
+        self.last_fingerprint = None;
+    }
+
+    /// Vecteurs supprimés par la déduplication.
+    pub fn duplicates_dropped(&self) -> usize {
+        self.duplicates_dropped
+    }
+
+    pub fn set_nan_policy(&mut self, policy: NanPolicy) {
+        self.nan_policy = policy;
+    }
+
+    /// Échantillons écartés (valeurs non finies).
+    pub fn skipped_samples(&self) -> usize {
+        self.skipped_samples
+    }
+
+    /// Échantillons écartés comme aberrants (`ClipPolicy::Drop`).
+    pub fn dropped_outliers(&self) -> usize {
+        self.stats.dropped_outliers
+    }
+
+    /// Erreurs en attente : échantillons rejetés à l'ingestion (dimension, politique
+    /// `NanPolicy::RecordError`) et lots rejetés par le transducer.
+    pub fn errors(&self) -> &[PerceptionError] {
+        &self.errors
+    }
+
+    /// Vide les erreurs en attente, à consulter après `process_and_flush`.
+    pub fn take_errors(&mut self) -> Vec<PerceptionError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Erreurs enregistrées depuis la création du scanner.
+    pub fn error_count(&self) -> usize {
+        self.stats.errors
+    }
+
+    pub fn last_error(&self) -> Option<&PerceptionError> {
+        self.errors.last()
+    }
+
+    /// Consigne une erreur survenue en amont du scanner (trame réseau illisible...)
+    /// avec celles de l'ingestion.
+    pub fn report_error(&mut self, error: PerceptionError) {
+        self.record_error(error);
+    }
+
+    fn record_error(&mut self, error: PerceptionError) -> PerceptionError {
+        self.stats.errors += 1;
+        if self.errors.len() < MAX_PENDING_ERRORS {
+            self.errors.push(error.clone());
+        } else if let Some(last) = self.errors.last_mut() {
+            *last = error.clone();
         }
+        error
     }
 
     /// L'entrée principale : accepte n'importe quoi, apprend, normalise et stocke.
-    pub fn ingest<T: UniversalSource>(&mut self, data: &T) {
-        let raw_features = data.to_features();
-        let timestamp = data.timestamp();
+    /// Un échantillon malformé est écarté et l'erreur, enregistrée, est retournée : le
+    /// flux continue avec les échantillons suivants.
+    pub fn ingest<T: UniversalSource>(&mut self, data: &T) -> Result<(), PerceptionError> {
+        let mut raw_features = data.to_features();
+        let mut timestamp = data.timestamp();
+        self.stats.ingested += 1;
 
-        // 1. Apprentissage (Welford Update)
+        // 0. Filtrage des valeurs non finies (un NaN empoisonnerait le normaliseur et les signatures)
+        if !timestamp.is_finite() || raw_features.iter().any(|x| !x.is_finite()) {
+            match self.nan_policy {
+                NanPolicy::Clamp if timestamp.is_finite() => {
+                    let fallback = self.normalizer.mean();
+                    for (i, x) in raw_features.iter_mut().enumerate() {
+                        if !x.is_finite() {
+                            *x = fallback.get(i).copied().unwrap_or(0.0);
+                        }
+                    }
+                }
+                NanPolicy::RecordError => {
+                    self.skipped_samples += 1;
+                    return Err(self.record_error(PerceptionError::NonFinite { t: timestamp }));
+                }
+                _ => {
+                    self.skipped_samples += 1;
+                    return Ok(());
+                }
+            }
+        }
+
+        // 0 bis. Instants non croissants
+        if let Some(latest) = self.latest_t.filter(|latest| timestamp <= *latest) {
+            self.stats.out_of_order += 1;
+            match self.config.time_policy {
+                TimePolicy::ClampForward { epsilon } => timestamp = latest + epsilon,
+                TimePolicy::Reorder { .. }
+                    if self.committed_t.is_none_or(|committed| timestamp > committed)
+                        && self.reorder.iter().all(|(t, _)| *t != timestamp) => {}
+                _ => {
+                    self.skipped_samples += 1;
+                    return Ok(());
+                }
+            }
+        }
+        self.latest_t = Some(self.latest_t.map_or(timestamp, |latest| latest.max(timestamp)));
+        self.modality = self.fixed_modality.clone().unwrap_or_else(|| data.modality());
+        self.batch_reliability = self.batch_reliability.min(data.reliability().clamp(0.0, 1.0));
+
+        // 1. Apprentissage (Welford Update) ; un échantillon d'une autre dimension que
+        // les statistiques (état importé incompatible) est écarté
+        if let Some(expected) = self.normalizer.dim().filter(|d| *d != raw_features.len()) {
+            self.skipped_samples += 1;
+            let error = NormalizerError::DimensionMismatch { expected, got: raw_features.len() };
+            return Err(self.record_error(error.into()));
+        }
+        let was_drifting = self.is_drifting();
+        self.normalizer.track_drift(&raw_features);
+        if self.is_drifting() && !was_drifting {
+            self.stats.drift_events += 1;
+        }
         if self.learning_enabled {
-            self.normalizer.update(&raw_features);
+            // Dimension déjà vérifiée
+            let _ = self.normalizer.try_update_at(&raw_features, timestamp);
+        }
+        if let ScannerPhase::Calibrating { remaining } = self.phase {
+            self.phase = match remaining - 1 {
+                0 => {
+                    if self.freeze_after_calibration {
+                        self.stop_learning();
+                    }
+                    ScannerPhase::Running
+                }
+                remaining => ScannerPhase::Calibrating { remaining },
+            };
+            return Ok(());
         }
 
         // 2. Normalisation immédiate
         // Note : Au tout début, cela retourne le brut tant que n < 2
-        let processed_features = self.normalizer.normalize(&raw_features);
+        let mut processed_features = self.normalizer.normalize(&raw_features);
 
-        // 3. Stockage
-        self.raw_buffer.push(processed_features);
-        self.time_buffer.push(timestamp);
+        // 3. Écrêtage des valeurs aberrantes
+        match self.config.clip {
+            ClipPolicy::None => {}
+            ClipPolicy::Clamp { z_max } => {
+                for z in &mut processed_features {
+                    *z = z.clamp(-z_max, z_max);
+                }
+            }
+            ClipPolicy::Drop { z_max } => {
+                if processed_features.iter().any(|z| z.abs() > z_max) {
+                    self.stats.dropped_outliers += 1;
+                    return Ok(());
+                }
+            }
+        }
+
+        // 4. Stockage, éventuellement après passage par la fenêtre de réordonnancement
+        match self.config.time_policy {
+            TimePolicy::Reorder { window } => {
+                let position = self.reorder.partition_point(|(t, _)| *t < timestamp);
+                self.reorder.insert(position, (timestamp, processed_features));
+                while self.reorder.len() > window {
+                    if let Some((t, features)) = self.reorder.pop_front() {
+                        self.commit(features, t)?;
+                    }
+                }
+            }
+            _ => self.commit(processed_features, timestamp)?,
+        }
+
+        // 5. Livraison aux abonnés
+        if !self.subscribers.is_empty() && self.is_ready() {
+            let vectors = self.process_and_flush();
+            if !vectors.is_empty() {
+                self.subscribers.retain(|subscriber| subscriber.send(vectors.clone()).is_ok());
+            }
+        }
+        Ok(())
+    }
+
+    /// Ajoute un échantillon normalisé au tampon, dans la limite de `max_buffer`.
+    fn commit(&mut self, features: Vec<f64>, timestamp: f64) -> Result<(), PerceptionError> {
+        if self.raw_buffer.len() >= self.config.max_buffer {
+            self.stats.overflowed += 1;
+            match self.config.overflow {
+                OverflowPolicy::DropOldest => {
+                    // Retrait en tête : les instants restent croissants
+                    self.raw_buffer.pop_front();
+                    self.time_buffer.pop_front();
+                    self.carried = self.carried.saturating_sub(1);
+                }
+                OverflowPolicy::DropNewest => return Ok(()),
+                OverflowPolicy::ErrorOnOverflow => {
+                    return Err(self.record_error(PerceptionError::BufferOverflow { capacity: self.config.max_buffer }));
+                }
+            }
+        }
+        self.raw_buffer.push_back(features);
+        self.time_buffer.push_back(timestamp);
+        self.committed_t = Some(timestamp);
+        Ok(())
+    }
+
+    /// Ingère toute une source (fichier, enregistrement), en traitant les lots au fil
+    /// de l'eau. Les échantillons rejetés sont consignés (`errors`, `stats`) sans
+    /// interrompre la lecture ; le segment inachevé reste en attente de `drain`.
+    pub fn ingest_all<T: UniversalSource, I: IntoIterator<Item = T>>(&mut self, source: I) -> Vec<UniversalVector> {
+        let mut vectors = Vec::new();
+        for sample in source {
+            let _ = self.ingest(&sample);
+            if self.is_ready() {
+                vectors.extend(self.process_and_flush());
+            }
+        }
+        vectors
     }
 
-    /// Vérifie si on a assez de données pour lancer le Transducer
+    /// Vérifie si on a assez de nouveaux échantillons (hors contexte) pour lancer le Transducer
     pub fn is_ready(&self) -> bool {
-        self.raw_buffer.len() >= self.batch_size
+        self.raw_buffer.len() - self.carried >= self.config.batch_size
     }
 
-    /// Génère les UniversalVectors et prépare le buffer suivant
+    /// Génère les UniversalVectors et prépare le buffer suivant. Les erreurs
+    /// d'ingestion accumulées depuis et un éventuel lot rejeté sont dans `take_errors`.
     pub fn process_and_flush(&mut self) -> Vec<UniversalVector> {
+        self.process_and_flush_indexed()
+            .into_iter()
+            .map(|s| s.vector)
+            .collect()
+    }
+
+    /// Comme `process_and_flush`, avec la provenance de chaque vecteur. Les indices
+    /// comptent les échantillons acceptés depuis la création du scanner.
+    pub fn process_and_flush_indexed(&mut self) -> Vec<SegmentedVector> {
         if !self.is_ready() { return vec![]; }
+        self.flush(Tail::Keep)
+    }
+
+    /// Échantillons pas encore émis dans un vecteur : nouveaux échantillons du tampon
+    /// et segment inachevé du transducer.
+    pub fn pending_samples(&self) -> usize {
+        self.raw_buffer.len() - self.carried + self.transducer.pending() + self.reorder.len()
+    }
 
-        // Appel au Transducer sur les données DÉJÀ normalisées
-        let vectors = UniversalTransducer::segment_and_process(&self.raw_buffer, &self.time_buffer);
+    /// Traite tout ce qui reste (à l'arrêt), puis vide les tampons, contexte compris.
+    /// Le scanner reste utilisable ; seules les statistiques du normaliseur sont gardées.
+    pub fn drain(&mut self) -> Vec<UniversalVector> {
+        while let Some((t, features)) = self.reorder.pop_front() {
+            // Une erreur de débordement est déjà consignée
+            let _ = self.commit(features, t);
+        }
+        let vectors = self.flush(Tail::Finish);
+        self.raw_buffer.clear();
+        self.time_buffer.clear();
+        self.carried = 0;
+        self.next_grid_t = None;
+        vectors.into_iter().map(|s| s.vector).collect()
+    }
+
+    /// Vide le lot si son plus ancien échantillon est plus vieux que `max_batch_age`
+    /// à l'instant `now` (même horloge que les timestamps), quel que soit son nombre
+    /// d'échantillons, s'il y a de quoi former un segment (`min_points`). Le segment
+    /// inachevé est alors émis aussi ; le contexte `overlap` est conservé.
+    pub fn poll(&mut self, now: f64) -> Option<Vec<UniversalVector>> {
+        let max_age = self.config.max_batch_age?;
+        let oldest = *self.time_buffer.get(self.carried)?;
+        if now - oldest < max_age.as_secs_f64() || self.pending_samples() < self.transducer.options().segmentation.min_points {
+            return None;
+        }
+        Some(self.flush(Tail::Cut).into_iter().map(|s| s.vector).collect())
+    }
 
-        // Gestion du chevauchement (Overlap)
-        // On garde le dernier point pour assurer la continuité des dérivées (dX)
-        if let (Some(last_raw), Some(last_time)) = (self.raw_buffer.last(), self.time_buffer.last()) {
-            let last_r = last_raw.clone();
-            let last_t = *last_time;
+    /// Transduit les nouveaux échantillons, puis traite le segment inachevé selon `tail`.
+    fn flush(&mut self, tail: Tail) -> Vec<SegmentedVector> {
+        // Appel au Transducer sur les données DÉJÀ normalisées ; il garde le segment
+        // inachevé et la direction courante jusqu'au lot suivant
+        self.transducer.options_mut().modality = self.modality.clone();
+        let carried = self.carried;
+        self.stats.flushed_batches += 1;
+        let result = match self.resampling {
+            Some((target_dt, kind)) => {
+                match self.resample_batch(target_dt, kind) {
+                    Ok((raw, times)) => self.transducer.push(&raw, &times),
+                    Err(e) => Err(e),
+                }
+            }
+            None => {
+                let raw = self.raw_buffer.make_contiguous();
+                let times = self.time_buffer.make_contiguous();
+                // Le contexte reporté amorce le lissage des ruptures
+                self.transducer.prime_filter(&raw[..carried]);
+                self.transducer.push(&raw[carried..], &times[carried..])
+            }
+        };
+        // Seuls les `overlap` derniers échantillons restent, comme contexte du lot suivant
+        let dropped = self.raw_buffer.len().saturating_sub(self.config.overlap);
+        self.raw_buffer.drain(..dropped);
+        self.time_buffer.drain(..dropped);
+        self.carried = self.raw_buffer.len();
+        let mut vectors = match result {
+            Ok(mut vectors) => {
+                match tail {
+                    Tail::Keep => {}
+                    Tail::Cut => vectors.extend(self.transducer.cut()),
+                    Tail::Finish => vectors.extend(self.transducer.finish()),
+                }
+                vectors
+            }
+            Err(e) => {
+                // Lot rejeté en entier ; l'erreur est consignée plutôt que de paniquer
+                self.record_error(e.into());
+                return vec![];
+            }
+        };
 
-            self.raw_buffer.clear();
-            self.time_buffer.clear();
+        // La fiabilité de la source pondère celle calculée par le transducer
+        let source_reliability = std::mem::replace(&mut self.batch_reliability, 1.0);
+        for s in &mut vectors {
+            s.vector.metadata.reliability *= source_reliability;
+        }
 
-            self.raw_buffer.push(last_r);
-            self.time_buffer.push(last_t);
-        } else {
-            self.raw_buffer.clear();
-            self.time_buffer.clear();
+        if let Some(tolerance) = self.dedup_tolerance {
+            let before = vectors.len();
+            vectors.retain(|s| {
+                let fingerprint = s.vector.signature.fingerprint(tolerance);
+                let duplicate = self.last_fingerprint == Some(fingerprint);
+                self.last_fingerprint = Some(fingerprint);
+                !duplicate
+            });
+            self.duplicates_dropped += before - vectors.len();
         }
+        self.stats.produced_vectors += vectors.len();
 
         vectors
     }
 
+    /// Grille des nouveaux échantillons, interpolée depuis le contexte du lot précédent.
+    fn resample_batch(&mut self, target_dt: f64, kind: InterpKind) -> Result<(Vec<Vec<f64>>, Vec<f64>), TransducerError> {
+        let raw = self.raw_buffer.make_contiguous();
+        let times = self.time_buffer.make_contiguous();
+        let start = self.next_grid_t.or(times.get(self.carried).copied()).unwrap_or(0.0);
+        let gap_threshold = self.transducer.options().segmentation.gap_threshold;
+        let (raw, grid) = UniversalTransducer::resample_from(raw, times, start, target_dt, kind, gap_threshold)?;
+        if let Some(last) = grid.last() {
+            self.next_grid_t = Some(last + target_dt);
+        }
+        Ok((raw, grid))
+    }
+
     // Utile si on veut arrêter d'ajuster la moyenne/variance après un temps
     pub fn stop_learning(&mut self) {
         self.learning_enabled = false;
     }
+
+    pub fn resume_learning(&mut self) {
+        self.learning_enabled = true;
+    }
+
+    /// Oublie les échantillons en attente, le contexte et le segment inachevé, pour
+    /// repartir sur un nouveau flux (les instants peuvent recommencer à zéro). Le
+    /// normaliseur, la phase (une calibration en cours garde son décompte), les
+    /// réglages, les compteurs et les erreurs sont conservés.
+    pub fn reset_buffers(&mut self) {
+        self.raw_buffer.clear();
+        self.time_buffer.clear();
+        self.carried = 0;
+        self.transducer = ChunkedTransducer::new(self.transducer.options().clone());
+        self.batch_reliability = 1.0;
+        self.last_fingerprint = None;
+        self.next_grid_t = None;
+        self.latest_t = None;
+        self.committed_t = None;
+        self.reorder.clear();
+    }
+
+    /// Comme `reset_buffers`, avec un normaliseur vierge (`Normalizer::reset`) :
+    /// l'apprentissage reprend et la dernière calibration demandée (`calibrate_for`)
+    /// recommence.
+    pub fn reset_all(&mut self) {
+        self.reset_buffers();
+        self.normalizer.reset();
+        self.learning_enabled = true;
+        self.calibrate_for(self.calibration_len);
+    }
 }
 
-/// Le contrat que tout objet doit remplir pour être ingéré par le système.
-pub trait UniversalSource {
-    /// Retourne le moment de l'événement (en secondes, ou unité monotone croissante).
-    fn timestamp(&self) -> f64;
 
-    /// Extrait les caractéristiques brutes (features).
-    /// C'est ici que vous convertissez votre donnée métier en mathématiques.
-    /// Exemple : Un pixel RGB -> vec![r, g, b]
-    fn to_features(&self) -> Vec<f64>;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Reading {
+        t: f64,
+        value: f64,
+    }
+
+    impl UniversalSource for Reading {
+        fn timestamp(&self) -> f64 { self.t }
+        fn to_features(&self) -> Vec<f64> { vec![self.value, self.value.cos()] }
+    }
+
+    /// Horloge en rampe et canal bloqué.
+    struct Stuck {
+        t: f64,
+    }
+
+    impl UniversalSource for Stuck {
+        fn timestamp(&self) -> f64 { self.t }
+        fn to_features(&self) -> Vec<f64> { vec![self.t, 5.0] }
+    }
+
+    fn stream(scanner: &mut UniversalScanner) -> Vec<UniversalVector> {
+        let mut vectors = Vec::new();
+        for i in 0..200 {
+            let t = i as f64 * 0.05;
+            let value = if i == 77 { f64::NAN } else { (t * 3.0).sin() };
+            let _ = scanner.ingest(&Reading { t, value });
+            if scanner.is_ready() {
+                vectors.extend(scanner.process_and_flush());
+            }
+        }
+        vectors
+    }
+
+    #[test]
+    fn test_nan_mid_stream_stays_finite() {
+        for policy in [NanPolicy::Skip, NanPolicy::Clamp, NanPolicy::RecordError] {
+            let mut scanner = UniversalScanner::new(50);
+            scanner.set_nan_policy(policy);
+            let vectors = stream(&mut scanner);
+
+            assert!(vectors.len() > 4);
+            assert!(vectors.iter().all(|v| v.signature.is_finite()));
+            assert!(!scanner.normalizer.is_poisoned());
+            let skipped = if policy == NanPolicy::Clamp { 0 } else { 1 };
+            assert_eq!(scanner.skipped_samples(), skipped);
+            assert_eq!(scanner.errors().len(), (policy == NanPolicy::RecordError) as usize);
+        }
+    }
+
+    #[test]
+    fn test_dedup_drops_stuck_segments() {
+        // Horloge en rampe + canal bloqué : chaque flush produit le même segment
+        let run = |dedup: Option<f64>| {
+            let mut scanner = UniversalScanner::new(20);
+            scanner.set_dedup(dedup);
+            let mut vectors = Vec::new();
+            for i in 0..200 {
+                let t = i as f64 * 0.1;
+                scanner.ingest(&Stuck { t }).unwrap();
+                if i == 40 {
+                    scanner.stop_learning();
+                }
+                if scanner.is_ready() {
+                    vectors.extend(scanner.process_and_flush());
+                }
+            }
+            (vectors, scanner.duplicates_dropped())
+        };
+
+        let (all, none_dropped) = run(None);
+        let (kept, dropped) = run(Some(1e-6));
+        assert_eq!(none_dropped, 0);
+        assert!(all.len() >= 9);
+        assert!(kept.len() <= 4 && dropped >= 6, "kept {}, dropped {}", kept.len(), dropped);
+        assert_eq!(kept.len() + dropped, all.len());
+    }
+
+    #[test]
+    fn test_indexed_flush_uses_stream_indices() {
+        let mut scanner = UniversalScanner::new(30);
+        let mut segments = Vec::new();
+        for i in 0..150 {
+            scanner.ingest(&Reading { t: i as f64 * 0.05, value: (i as f64 * 0.2).sin() }).unwrap();
+            if scanner.is_ready() {
+                segments.extend(scanner.process_and_flush_indexed());
+            }
+        }
+        assert!(segments.len() > 3);
+        for pair in segments.windows(2) {
+            assert!(pair[1].start_idx >= pair[0].end_idx);
+        }
+        for s in &segments {
+            assert!((s.t_start - s.start_idx as f64 * 0.05).abs() < 1e-9);
+            assert!((s.t_end - s.end_idx as f64 * 0.05).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_resampled_batches_follow_grid_and_gaps() {
+        // Rampe à horodatage irrégulier (~10 Hz), interrompue par un trou de 2 s
+        let mut scanner = UniversalScanner::new(25);
+        for dt in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            assert!(matches!(scanner.set_resampling(Some(dt), InterpKind::Linear), Err(ConfigError::InvalidResamplingStep(_))));
+        }
+        scanner.set_resampling(Some(0.1), InterpKind::Linear).unwrap();
+        scanner.set_gap_threshold(Some(0.5));
+        let mut segments = Vec::new();
+        for i in 0..200 {
+            let jitter = if i % 3 == 0 { 0.03 } else { 0.0 };
+            let t = i as f64 * 0.1 + jitter + if i >= 100 { 2.0 } else { 0.0 };
+            scanner.ingest(&Reading { t, value: (t * 0.7).sin() }).unwrap();
+            if scanner.is_ready() {
+                segments.extend(scanner.process_and_flush_indexed());
+            }
+        }
+
+        assert!(segments.len() >= 2);
+        assert!(scanner.errors().is_empty());
+        let on_grid = |t: f64, origin: f64| ((t - origin) / 0.1 - ((t - origin) / 0.1).round()).abs() < 1e-6;
+        for s in &segments {
+            // Aucun segment ne franchit le trou entre 9.93 s et 12.0 s ; la grille
+            // part du premier échantillon de chaque côté
+            assert!(s.t_end <= 9.93 || s.t_start >= 12.0);
+            let origin = if s.t_start >= 12.0 { 12.0 } else { 0.03 };
+            assert!(on_grid(s.t_start, origin) && on_grid(s.t_end, origin), "{:?}", (s.t_start, s.t_end));
+        }
+        assert!(segments.iter().any(|s| s.t_start >= 12.0));
+    }
+
+    #[test]
+    fn test_samples_carry_modality_and_reliability() {
+        use crate::perception::source::Sample;
+        let samples: Vec<Sample> = (0..120)
+            .map(|i| {
+                let t = i as f64 * 0.05;
+                Sample { t, features: vec![(t * 2.0).sin(), t], modality: Modality::Audio, reliability: 0.5 }
+            })
+            .collect();
+
+        let mut scanner = UniversalScanner::new(40);
+        let mut vectors = Vec::new();
+        for sample in &samples {
+            scanner.ingest(sample).unwrap();
+            if scanner.is_ready() {
+                vectors.extend(scanner.process_and_flush());
+            }
+        }
+        assert!(!vectors.is_empty());
+        for v in &vectors {
+            assert_eq!(v.metadata.modality, Modality::Audio);
+            assert!(v.metadata.reliability <= 0.5 && v.metadata.reliability > 0.0);
+        }
+
+        // Les tuples (instant, valeur) sont des sources de capteur fiables
+        let mut scanner = UniversalScanner::new(40);
+        for i in 0..40 {
+            let t = i as f64 * 0.05;
+            scanner.ingest(&(t, (t * 2.0).sin())).unwrap();
+        }
+        let vectors = scanner.process_and_flush();
+        assert!(vectors.iter().all(|v| v.metadata.modality == Modality::Sensor));
+        assert_eq!((1.0, vec![2.0, 3.0]).metadata().reliability, 1.0);
+    }
+
+    #[test]
+    fn test_overlap_keeps_one_shot_boundaries() {
+        // Oscillation lente (ruptures toutes les ~70 échantillons, à cheval sur les lots
+        // de 64) et bruit rapide que seul le lissage empêche de couper
+        let times: Vec<f64> = (0..1000).map(|i| i as f64 * 0.01).collect();
+        let values: Vec<f64> = (0..1000)
+            .map(|i| (i as f64 * 0.045).sin() + if i % 2 == 0 { 0.02 } else { -0.02 })
+            .collect();
+        let prefilter = PreFilter::MovingAverage { window: 4 };
+
+        let stream = |overlap: usize| {
+            let config = ScannerConfig::builder().batch_size(64).overlap(overlap).max_segment_points(None).build().unwrap();
+            let mut scanner = UniversalScanner::with_config(config);
+            scanner.set_prefilter(prefilter);
+            // Sans apprentissage, le normaliseur laisse passer les données brutes
+            scanner.stop_learning();
+            let mut streamed = Vec::new();
+            for (t, value) in times.iter().zip(values.iter()) {
+                scanner.ingest(&Reading { t: *t, value: *value }).unwrap();
+                if scanner.is_ready() {
+                    streamed.extend(scanner.process_and_flush_indexed());
+                }
+                // Les lots ne rétrécissent pas malgré le contexte conservé
+                assert!(scanner.raw_buffer.len() <= config.batch_size + config.overlap);
+            }
+            streamed
+        };
 
-    /// Fournit le contexte (Quelle modalité ? Quelle fiabilité ?).
-    /// Si la fiabilité n'est pas connue, renvoyer 1.0 par défaut.
-    fn metadata(&self) -> Metadata {
-        // Implémentation par défaut pratique
-        Metadata {
-            timestamp: self.timestamp(),
-            modality: Modality::Sensor, // Valeur par défaut, à surcharger
-            reliability: 1.0,
+        let raw: Vec<Vec<f64>> = values.iter().map(|v| vec![*v, v.cos()]).collect();
+        let mut options = TransducerOptions::default();
+        options.segmentation.prefilter = prefilter;
+        let one_shot = UniversalTransducer::segment_and_process_indexed(&raw[..960], &times[..960], &options).unwrap();
+
+        let bounds = |segments: &[SegmentedVector]| segments.iter().map(|s| (s.start_idx, s.end_idx)).collect::<Vec<_>>();
+        // Le dernier segment du one-shot est clos par la fin des données, pas par une rupture
+        let expected = bounds(&one_shot[..one_shot.len() - 1]);
+        assert!(expected.len() > 5);
+        assert!(expected.iter().any(|(start, end)| start / 64 != end / 64));
+        assert_eq!(bounds(&stream(4)), expected);
+        // Sans contexte, le lissage repart à chaque lot : des ruptures parasites apparaissent
+        assert_ne!(bounds(&stream(0)), expected);
+    }
+
+    #[test]
+    fn test_import_calibrated_normalizer() {
+        let mut calibration = UniversalScanner::new(50);
+        for i in 0..500 {
+            calibration.ingest(&Reading { t: i as f64 * 0.01, value: (i as f64 * 0.03).sin() }).unwrap();
         }
+        let state = calibration.export_normalizer();
+
+        let mut scanner = UniversalScanner::new(50);
+        scanner.import_normalizer(state.clone()).unwrap();
+        scanner.stop_learning();
+        let probe = [0.4, 0.4f64.cos()];
+        assert_eq!(scanner.normalizer().normalize(&probe), calibration.normalizer().normalize(&probe));
+
+        // Un état de dimension 3 pour une source à 2 canaux : erreurs, pas de panique
+        let mut mismatched = UniversalScanner::new(50);
+        let state = NormalizerState { count: 10, mean: vec![0.0; 3], m2: vec![1.0; 3], initialized: true, ..Default::default() };
+        mismatched.import_normalizer(state).unwrap();
+        assert!(mismatched.ingest(&Reading { t: 0.0, value: 1.0 }).is_err());
+        assert_eq!(mismatched.skipped_samples(), 1);
+        assert_eq!(mismatched.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_clip_policies() {
+        // Signal calibré puis une pointe à ~90 sigmas sur la première dimension (cos(x) reste dans sa plage)
+        let run = |clip: ClipPolicy| {
+            let mut scanner = UniversalScanner::with_config(ScannerConfig { clip, ..ScannerConfig::new(1000) });
+            for i in 0..300 {
+                scanner.ingest(&Reading { t: i as f64 * 0.01, value: (i as f64 * 0.1).sin() }).unwrap();
+            }
+            scanner.stop_learning();
+            scanner.ingest(&Reading { t: 3.0, value: 10.0 * std::f64::consts::TAU + 0.64 }).unwrap();
+            scanner
+        };
+
+        let plain = run(ClipPolicy::None);
+        let spike = plain.raw_buffer.back().unwrap().clone();
+        assert!(spike[0] > 40.0 && spike[1].abs() < 3.0, "{:?}", spike);
+        assert_eq!(plain.raw_buffer.len(), 301);
+
+        // Seule la dimension aberrante est ramenée à z_max
+        let clamped = run(ClipPolicy::Clamp { z_max: 4.0 });
+        assert_eq!(clamped.raw_buffer.back().unwrap(), &vec![4.0, spike[1]]);
+        assert_eq!(clamped.dropped_outliers(), 0);
+
+        let dropped = run(ClipPolicy::Drop { z_max: 4.0 });
+        assert_eq!(dropped.raw_buffer.len(), 300);
+        assert_eq!(dropped.dropped_outliers(), 1);
+    }
+
+    #[test]
+    fn test_malformed_sample_mid_stream() {
+        let mut scanner = UniversalScanner::new(40);
+        let mut vectors = Vec::new();
+        for i in 0..200 {
+            let t = i as f64 * 0.05;
+            let sample = (t, vec![(t * 2.0).sin(), t.cos()]);
+            if i == 60 {
+                let error = scanner.ingest(&(t, vec![1.0, 2.0, 3.0])).unwrap_err();
+                assert_eq!(error, PerceptionError::Normalizer(NormalizerError::DimensionMismatch { expected: 2, got: 3 }));
+                continue;
+            }
+            scanner.ingest(&sample).unwrap();
+            if scanner.is_ready() {
+                vectors.extend(scanner.process_and_flush());
+            }
+        }
+
+        // Le lot suivant l'erreur et les autres sont produits normalement
+        assert!(vectors.len() >= 5);
+        assert!(vectors.iter().any(|v| v.metadata.timestamp > 3.0));
+        assert_eq!(scanner.error_count(), 1);
+        assert_eq!(scanner.skipped_samples(), 1);
+        assert!(scanner.last_error().is_some());
+        assert_eq!(scanner.take_errors().len(), 1);
+        assert!(scanner.errors().is_empty() && scanner.error_count() == 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_poll_flushes_stale_batches() {
+        let config = ScannerConfig { max_batch_age: Some(Duration::from_secs(10)), ..ScannerConfig::new(50) };
+        // Capteur à 1 Hz : 50 échantillons prendraient près d'une minute
+        let mut scanner = UniversalScanner::with_config(config);
+        for i in 0..2 {
+            scanner.ingest(&Reading { t: i as f64, value: i as f64 * 0.3 }).unwrap();
+        }
+        assert!(scanner.poll(30.0).is_none());
+
+        for i in 2..5 {
+            scanner.ingest(&Reading { t: i as f64, value: i as f64 * 0.3 }).unwrap();
+        }
+        assert!(scanner.poll(5.0).is_none());
+        let vectors = scanner.poll(12.0).unwrap();
+        assert_eq!(vectors.len(), 1);
+        // Le contexte est conservé, et il n'y a plus rien de nouveau à vider
+        assert_eq!(scanner.raw_buffer.len(), config.overlap);
+        assert!(scanner.poll(100.0).is_none());
+
+        // Sans limite d'âge, poll ne fait rien
+        let mut scanner = UniversalScanner::new(50);
+        scanner.ingest(&Reading { t: 0.0, value: 0.0 }).unwrap();
+        assert!(scanner.poll(1e9).is_none());
+    }
+
+    #[test]
+    fn test_drain_on_shutdown() {
+        let mut scanner = UniversalScanner::new(16);
+        for i in 0..7 {
+            scanner.ingest(&Reading { t: i as f64 * 0.1, value: i as f64 * 0.2 }).unwrap();
+        }
+        assert!(!scanner.is_ready());
+        assert_eq!(scanner.pending_samples(), 7);
+
+        let vectors = scanner.drain();
+        assert_eq!(vectors.len(), 1);
+        assert!(scanner.raw_buffer.is_empty() && scanner.time_buffer.is_empty());
+        assert_eq!(scanner.pending_samples(), 0);
+        assert!(scanner.drain().is_empty());
+
+        // Réutilisable ensuite
+        for i in 7..30 {
+            scanner.ingest(&Reading { t: i as f64 * 0.1, value: (i as f64).sin() }).unwrap();
+        }
+        assert!(scanner.is_ready());
+        assert!(!scanner.process_and_flush().is_empty());
+    }
+
+    #[test]
+    fn test_calibration_phase() {
+        let mut scanner = UniversalScanner::new(20);
+        scanner.calibrate_for(100);
+        scanner.set_freeze_after_calibration(true);
+        let reading = |i: usize| Reading { t: i as f64 * 0.01, value: 5.0 + 3.0 * (i as f64 * 0.05).sin() };
+
+        for i in 0..100 {
+            assert!(matches!(scanner.phase(), ScannerPhase::Calibrating { .. }));
+            scanner.ingest(&reading(i)).unwrap();
+            assert!(!scanner.is_ready() && scanner.process_and_flush().is_empty());
+        }
+        assert_eq!(scanner.phase(), ScannerPhase::Running);
+        assert!(scanner.raw_buffer.is_empty());
+
+        // Premier lot normalisé avec les statistiques des 100 échantillons de calibration
+        let calibrated = scanner.normalizer().clone();
+        for i in 100..120 {
+            scanner.ingest(&reading(i)).unwrap();
+        }
+        assert_eq!(scanner.normalizer().to_state(), calibrated.to_state());
+        for (i, z) in scanner.raw_buffer.iter().enumerate() {
+            let r = reading(100 + i);
+            assert_eq!(z, &calibrated.normalize(&r.to_features()));
+            assert!(z[0].abs() < 3.0);
+        }
+        assert!(!scanner.process_and_flush().is_empty());
+    }
+
+    #[test]
+    fn test_stats_follow_the_stream() {
+        let config = ScannerConfig { clip: ClipPolicy::Drop { z_max: 6.0 }, ..ScannerConfig::new(25) };
+        let mut scanner = UniversalScanner::with_config(config);
+        let mut produced = 0;
+        for i in 0..100 {
+            let t = i as f64 * 0.05;
+            let result = match i {
+                // Mauvaise dimension, puis pointe aberrante
+                40 => scanner.ingest(&(t, vec![0.0; 3])),
+                70 => scanner.ingest(&Reading { t, value: 20.0 * std::f64::consts::TAU }),
+                _ => scanner.ingest(&Reading { t, value: (t * 2.0).sin() }),
+            };
+            assert_eq!(result.is_err(), i == 40);
+            if scanner.is_ready() {
+                produced += scanner.process_and_flush().len();
+            }
+        }
+
+        let stats = scanner.stats();
+        assert_eq!(stats, ScannerStats { ingested: 100, flushed_batches: 3, produced_vectors: produced, dropped_outliers: 1, errors: 1, overflowed: 0, out_of_order: 0, drift_events: 0 });
+        assert!(produced > 0);
+        assert_eq!(scanner.buffer_len(), config.overlap + 23);
+        assert_eq!(scanner.batch_size(), 25);
+        assert!(scanner.learning_enabled());
+
+        let normalizer = scanner.normalizer();
+        // La pointe écartée a tout de même été apprise
+        assert_eq!(normalizer.count(), 99);
+        assert_eq!(normalizer.dim(), Some(2));
+        assert!(normalizer.std_dev().iter().all(|s| *s > 0.0));
+    }
+
+    #[test]
+    fn test_subscribers_receive_batches() {
+        let mut scanner = UniversalScanner::new(30);
+        let first = scanner.subscribe();
+        let second = scanner.subscribe();
+        let dropped = scanner.subscribe();
+        drop(dropped);
+
+        for i in 0..200 {
+            scanner.ingest(&Reading { t: i as f64 * 0.05, value: (i as f64 * 0.15).sin() }).unwrap();
+            // Le lot est traité par ingest lui-même
+            assert!(!scanner.is_ready());
+        }
+
+        let batches: Vec<Vec<UniversalVector>> = first.try_iter().collect();
+        assert!(batches.len() >= 5);
+        assert_eq!(batches, second.try_iter().collect::<Vec<_>>());
+        assert_eq!(scanner.subscribers.len(), 2);
+        assert_eq!(scanner.stats().produced_vectors, batches.iter().map(|b| b.len()).sum::<usize>());
+
+        // Tous les abonnés partis : le scanner continue
+        drop((first, second));
+        for i in 200..300 {
+            scanner.ingest(&Reading { t: i as f64 * 0.05, value: (i as f64 * 0.15).sin() }).unwrap();
+        }
+        assert!(scanner.subscribers.is_empty());
+        assert!(scanner.errors().is_empty());
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        // Consommateur bloqué : 50 échantillons sans aucun flush, tampon borné à 20
+        let run = |overflow: OverflowPolicy| {
+            let config = ScannerConfig { max_buffer: 20, overflow, ..ScannerConfig::new(10) };
+            let mut scanner = UniversalScanner::with_config(config);
+            let failures = (0..50)
+                .filter(|i| scanner.ingest(&Reading { t: *i as f64 * 0.1, value: (*i as f64).sin() }).is_err())
+                .count();
+            (scanner, failures)
+        };
+        let first_time = |scanner: &UniversalScanner| (scanner.time_buffer[0] / 0.1).round() as usize;
+
+        let (oldest, failures) = run(OverflowPolicy::DropOldest);
+        assert_eq!((oldest.buffer_len(), oldest.stats().overflowed, failures), (20, 30, 0));
+        assert_eq!(first_time(&oldest), 30);
+        assert!(oldest.time_buffer.iter().zip(oldest.time_buffer.iter().skip(1)).all(|(a, b)| a < b));
+
+        let (newest, failures) = run(OverflowPolicy::DropNewest);
+        assert_eq!((newest.buffer_len(), newest.stats().overflowed, failures), (20, 30, 0));
+        assert_eq!(first_time(&newest), 0);
+
+        let (mut strict, failures) = run(OverflowPolicy::ErrorOnOverflow);
+        assert_eq!((strict.buffer_len(), strict.stats().overflowed, failures), (20, 30, 30));
+        assert_eq!(strict.last_error(), Some(&PerceptionError::BufferOverflow { capacity: 20 }));
+        // Le consommateur repart : le tampon se vide et l'ingestion reprend
+        strict.process_and_flush();
+        assert_eq!(strict.buffer_len(), strict.config().overlap);
+        assert!(strict.ingest(&Reading { t: 10.0, value: 0.0 }).is_ok());
+    }
+
+    #[test]
+    fn test_time_policies_on_swapped_pair() {
+        let run = |time_policy: TimePolicy| {
+            let mut scanner = UniversalScanner::with_config(ScannerConfig { time_policy, ..ScannerConfig::new(20) });
+            let mut segments = Vec::new();
+            for i in 0..100usize {
+                // Les échantillons 41 et 42 arrivent inversés
+                let k = match i { 41 => 42, 42 => 41, _ => i };
+                let t = k as f64 * 0.05;
+                scanner.ingest(&Reading { t, value: (t * 2.0).sin() }).unwrap();
+                let times = scanner.time_buffer.make_contiguous();
+                assert!(times.windows(2).all(|w| w[1] > w[0]), "{:?}", time_policy);
+                if scanner.is_ready() {
+                    segments.extend(scanner.process_and_flush_indexed());
+                }
+            }
+            // La fenêtre de réordonnancement est transmise elle aussi
+            scanner.drain();
+            // Un lot refusé par le transducer (dt ≤ 0) serait consigné en erreur
+            assert!(scanner.errors().is_empty());
+            assert!(segments.windows(2).all(|w| w[1].t_start >= w[0].t_end));
+            (scanner.stats().out_of_order, scanner.skipped_samples(), scanner.stats().ingested)
+        };
+
+        assert_eq!(run(TimePolicy::Reject), (1, 1, 100));
+        assert_eq!(run(TimePolicy::ClampForward { epsilon: 1e-3 }), (1, 0, 100));
+        assert_eq!(run(TimePolicy::Reorder { window: 2 }), (1, 0, 100));
+        // Fenêtre trop petite : le retardataire est écarté
+        assert_eq!(run(TimePolicy::Reorder { window: 0 }), (1, 1, 100));
+    }
+
+    #[test]
+    fn test_reset_keeps_or_restarts_calibration() {
+        let mut scanner = UniversalScanner::new(20);
+        scanner.calibrate_for(50);
+        scanner.set_freeze_after_calibration(true);
+        let feed = |scanner: &mut UniversalScanner, n: usize| {
+            for i in 0..n {
+                scanner.ingest(&Reading { t: i as f64 * 0.1, value: 2.0 + (i as f64 * 0.3).sin() }).unwrap();
+            }
+        };
+
+        // Pendant la calibration, reset_buffers garde le décompte et les statistiques
+        feed(&mut scanner, 30);
+        scanner.reset_buffers();
+        assert_eq!(scanner.phase(), ScannerPhase::Calibrating { remaining: 20 });
+        assert_eq!(scanner.normalizer().count(), 30);
+
+        // Les instants recommencent à zéro sans être rejetés
+        feed(&mut scanner, 45);
+        assert_eq!(scanner.phase(), ScannerPhase::Running);
+        assert!(!scanner.learning_enabled());
+        assert_eq!(scanner.buffer_len(), 25);
+        let calibrated = scanner.normalizer().to_state();
+        scanner.reset_buffers();
+        assert_eq!(scanner.buffer_len(), 0);
+        assert_eq!(scanner.normalizer().to_state(), calibrated);
+        feed(&mut scanner, 25);
+        assert_eq!(scanner.buffer_len(), 25);
+        assert_eq!(scanner.skipped_samples(), 0);
+
+        scanner.resume_learning();
+        assert!(scanner.learning_enabled());
+        scanner.stop_learning();
+
+        // reset_all : normaliseur vierge, apprentissage repris, calibration recommencée
+        scanner.reset_all();
+        assert_eq!(scanner.phase(), ScannerPhase::Calibrating { remaining: 50 });
+        assert!(scanner.learning_enabled());
+        assert_eq!(scanner.normalizer().count(), 0);
+        assert_eq!(scanner.buffer_len(), 0);
+    }
+
+    #[test]
+    fn test_config_builder_validation() {
+        let config = ScannerConfig::builder()
+            .batch_size(64)
+            .overlap(4)
+            .clip(ClipPolicy::Clamp { z_max: 6.0 })
+            .calibration(100)
+            .normalizer_mode(NormalizerMode::Robust)
+            .build()
+            .unwrap();
+        assert_eq!(config.overlap, 4);
+        let scanner = UniversalScanner::with_config(config);
+        assert_eq!(scanner.phase(), ScannerPhase::Calibrating { remaining: 100 });
+        assert_eq!(scanner.normalizer().mode(), NormalizerMode::Robust);
+
+        assert_eq!(ScannerConfig::builder().build(), Err(ConfigError::ZeroBatchSize));
+        assert_eq!(
+            ScannerConfig::builder().batch_size(8).overlap(8).build(),
+            Err(ConfigError::OverlapTooLarge { overlap: 8, batch_size: 8 })
+        );
+        assert_eq!(
+            ScannerConfig::builder().batch_size(8).max_buffer(7, OverflowPolicy::DropNewest).build(),
+            Err(ConfigError::BufferTooSmall { max_buffer: 7, batch_size: 8 })
+        );
+        assert_eq!(
+            ScannerConfig::builder().batch_size(8).min_points(9).build(),
+            Err(ConfigError::InvalidMinPoints { min_points: 9, batch_size: 8 })
+        );
+        assert_eq!(
+            ScannerConfig::builder().batch_size(8).min_points(1).build(),
+            Err(ConfigError::InvalidMinPoints { min_points: 1, batch_size: 8 })
+        );
+        assert!(ScannerConfig::builder().batch_size(8).min_points(8).max_buffer(8, OverflowPolicy::DropOldest).build().is_ok());
+        assert!(ConfigError::OverlapTooLarge { overlap: 8, batch_size: 8 }.to_string().contains("overlap (8)"));
+    }
+
+    #[test]
+    fn test_drift_on_baseline_step() {
+        use crate::perception::source::Sample;
+
+        // Bruit gaussien (Box-Muller) sur deux canaux, marche de +3σ sur le second à i = 2000
+        let mut seed = 12345u64;
+        let mut gaussian = move || {
+            let mut uniform = || {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                ((seed >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+            };
+            let (u1, u2) = (uniform(), uniform());
+            (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+        };
+
+        let mut scanner = UniversalScanner::new(100);
+        let mut max_stationary: f64 = 0.0;
+        let mut detected_at = None;
+        for i in 0..2200 {
+            let step = if i >= 2000 { 3.0 } else { 0.0 };
+            let t = i as f64 * 0.01;
+            scanner.ingest(&Sample::new(t, vec![gaussian(), gaussian() + step])).unwrap();
+            scanner.process_and_flush();
+            if i < 2000 {
+                max_stationary = max_stationary.max(scanner.drift_score());
+            } else if detected_at.is_none() && scanner.is_drifting() {
+                detected_at = Some(i - 2000);
+            }
+        }
+        assert!(max_stationary < DEFAULT_DRIFT_THRESHOLD, "max stationary score = {}", max_stationary);
+        assert!(detected_at.is_some_and(|n| n < 30), "detected after {:?} samples", detected_at);
+        assert_eq!(scanner.stats().drift_events, 1);
+    }
+}
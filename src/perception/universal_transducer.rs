@@ -1,86 +1,1586 @@
-use crate::perception::universal_vector::{UniversalVector, Signature, Gradient, Metadata};
+use crate::perception::universal_vector::{UniversalVector, Metadata, Modality, DEFAULT_DEPTH};
+use crate::perception::streaming::StreamingSignature;
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
 
 pub struct UniversalTransducer;
 
+/// Borne par défaut sur |terme| d'une signature accumulée. Au-delà de ~1e154 les carrés
+/// débordent dans `dot`/`distance` et les résonances deviennent NaN.
+pub const DEFAULT_MAX_TERM_MAGNITUDE: f64 = 1e150;
+
+/// Seuil historique sous lequel un mouvement est ignoré par la détection de rupture.
+pub const DEFAULT_NOISE_EPS: f64 = 1e-6;
+
+// Échantillons regroupés par `process_iter` avant chaque appel au ChunkedTransducer
+// (le seuil adaptatif est estimé sur le premier de ces morceaux)
+const ITER_CHUNK: usize = 256;
+
+/// Seuil de bruit de la détection de rupture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseEps {
+    Fixed(f64),
+    /// multiplier × médiane des |incréments| du lot courant (estimation du plancher de bruit).
+    Adaptive { multiplier: f64 },
+}
+
+/// Signal observé par la détection de rupture.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelSelector {
+    /// Changement de signe des variations d'un canal (historiquement le canal 0).
+    Channel(usize),
+    /// Changement de direction du vecteur d'incrément complet : rupture quand le cosinus
+    /// entre le mouvement courant et la direction d'ouverture du segment passe sous
+    /// `min_cosine` (0.0 : virage de plus d'un quart de tour). Comparer des incréments
+    /// consécutifs ne déclencherait jamais sur une trajectoire lisse.
+    NormOfIncrement { min_cosine: f64 },
+    /// Changement de signe des variations de la projection sur un axe.
+    ProjectionOnto(Vec<f64>),
+}
+
+impl ChannelSelector {
+    /// Vérifie que le sélecteur s'applique à des échantillons de `channels` canaux.
+    fn check(&self, channels: usize) -> Result<(), TransducerError> {
+        match self {
+            ChannelSelector::Channel(c) if *c >= channels => {
+                Err(TransducerError::ChannelOutOfRange { channel: *c, channels })
+            }
+            ChannelSelector::ProjectionOnto(axis) if axis.len() != channels => {
+                Err(TransducerError::AxisLengthMismatch { axis: axis.len(), channels })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Déplacement entre deux échantillons dans l'espace observé.
+    /// Le sélecteur doit avoir été vérifié (`check`) pour ce nombre de canaux.
+    fn movement(&self, from: &[f64], to: &[f64]) -> Vec<f64> {
+        match self {
+            ChannelSelector::Channel(c) => vec![to[*c] - from[*c]],
+            ChannelSelector::NormOfIncrement { .. } => to.iter().zip(from.iter()).map(|(a, b)| a - b).collect(),
+            ChannelSelector::ProjectionOnto(axis) => {
+                vec![to.iter().zip(from.iter()).zip(axis.iter()).map(|((a, b), u)| (a - b) * u).sum()]
+            }
+        }
+    }
+
+    /// Pour un signal scalaire, cosinus ±1 : rupture au changement de signe.
+    fn min_cosine(&self) -> f64 {
+        match self {
+            ChannelSelector::NormOfIncrement { min_cosine } => *min_cosine,
+            _ => 0.0,
+        }
+    }
+}
+
+fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// Filtre causal (pas d'échantillon futur) contre le bavardage des ruptures dû à la
+/// gigue haute fréquence. Il retarde les ruptures d'environ (window − 1) / 2
+/// échantillons pour MovingAverage, (1 − alpha) / alpha pour Ema.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PreFilter {
+    #[default]
+    None,
+    /// Moyenne des `window` derniers échantillons.
+    MovingAverage { window: usize },
+    /// Moyenne exponentielle : y ← alpha · x + (1 − alpha) · y.
+    Ema { alpha: f64 },
+}
+
+/// État du `PreFilter`, conservé d'un morceau à l'autre.
+#[derive(Debug, Clone, Default)]
+struct FilterState {
+    history: VecDeque<Vec<f64>>,
+    sum: Vec<f64>,
+    ema: Option<Vec<f64>>,
+}
+
+impl FilterState {
+    fn apply(&mut self, filter: PreFilter, x: &[f64]) -> Vec<f64> {
+        match filter {
+            PreFilter::None => x.to_vec(),
+            PreFilter::MovingAverage { window } => {
+                if self.sum.len() != x.len() {
+                    self.sum = vec![0.0; x.len()];
+                }
+                self.history.push_back(x.to_vec());
+                self.sum.iter_mut().zip(x.iter()).for_each(|(s, v)| *s += v);
+                while self.history.len() > window.max(1) {
+                    if let Some(old) = self.history.pop_front() {
+                        self.sum.iter_mut().zip(old.iter()).for_each(|(s, v)| *s -= v);
+                    }
+                }
+                let n = self.history.len() as f64;
+                self.sum.iter().map(|s| s / n).collect()
+            }
+            PreFilter::Ema { alpha } => {
+                let next: Vec<f64> = match &self.ema {
+                    Some(y) if y.len() == x.len() => y.iter().zip(x.iter()).map(|(y, v)| alpha * v + (1.0 - alpha) * y).collect(),
+                    _ => x.to_vec(),
+                };
+                self.ema = Some(next.clone());
+                next
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentationConfig {
+    pub channel: ChannelSelector,
+    /// Un mouvement n'est pris en compte que s'il s'écarte de plus de ce seuil du dernier
+    /// échantillon significatif (bande morte) : le bruit autour d'un extremum ne crée
+    /// pas de micro-segments.
+    pub noise_eps: NoiseEps,
+    /// Segments plus courts (en échantillons) écartés et comptés (au moins 2).
+    pub min_points: usize,
+    /// Un segment qui atteint ce nombre d'échantillons est émis et le suivant repart
+    /// de son dernier échantillon.
+    pub max_points: Option<usize>,
+    /// Idem pour la durée couverte : le segment est coupé avant l'échantillon qui la
+    /// dépasserait.
+    pub max_duration: Option<f64>,
+    /// Un écart entre deux instants consécutifs supérieur à ce seuil (trou dans le
+    /// flux) termine le segment ; le suivant repart après le trou, sans pivot commun.
+    pub gap_threshold: Option<f64>,
+    /// Lissage appliqué aux seuls échantillons de détection des ruptures ; les
+    /// signatures restent calculées sur les données brutes.
+    pub prefilter: PreFilter,
+}
+
+impl Default for SegmentationConfig {
+    fn default() -> Self {
+        SegmentationConfig {
+            channel: ChannelSelector::Channel(0),
+            noise_eps: NoiseEps::Fixed(DEFAULT_NOISE_EPS),
+            min_points: 3,
+            max_points: None,
+            max_duration: None,
+            gap_threshold: None,
+            prefilter: PreFilter::None,
+        }
+    }
+}
+
+impl SegmentationConfig {
+    /// Seuil effectif pour un lot donné.
+    pub fn noise_threshold(&self, raw: &[Vec<f64>]) -> f64 {
+        match self.noise_eps {
+            NoiseEps::Fixed(eps) => eps,
+            NoiseEps::Adaptive { multiplier } => {
+                let mut increments: Vec<f64> = raw.windows(2).map(|w| norm(&self.channel.movement(&w[0], &w[1]))).collect();
+                if increments.is_empty() { return DEFAULT_NOISE_EPS; }
+                let mid = increments.len() / 2;
+                let (_, median, _) = increments.select_nth_unstable_by(mid, |a, b| a.total_cmp(b));
+                (multiplier * *median).max(DEFAULT_NOISE_EPS)
+            }
+        }
+    }
+}
+
+/// Instant du segment reporté dans `Metadata.timestamp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampAnchor {
+    /// Premier échantillon du segment.
+    Start,
+    /// Milieu de l'intervalle de temps couvert.
+    Midpoint,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransducerError {
+    EmptyInput,
+    LengthMismatch { raw: usize, times: usize },
+    /// Instant non strictement supérieur au précédent (dt nul ou négatif).
+    NonMonotonicTime { index: usize },
+    /// Instant NaN ou infini.
+    NonFiniteTime { index: usize },
+    TooShort { len: usize, min: usize },
+    /// Un échantillon n'a pas le même nombre de canaux que les précédents.
+    DimensionMismatch { index: usize, expected: usize, got: usize },
+    /// `ChannelSelector::Channel` désigne un canal absent du flux.
+    ChannelOutOfRange { channel: usize, channels: usize },
+    /// L'axe de `ChannelSelector::ProjectionOnto` n'a pas la dimension du flux.
+    AxisLengthMismatch { axis: usize, channels: usize },
+    /// Pas de rééchantillonnage NaN, infini, nul ou négatif.
+    InvalidResamplingStep { step: f64 },
+}
+
+impl fmt::Display for TransducerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransducerError::EmptyInput => write!(f, "Transducer input is empty"),
+            TransducerError::LengthMismatch { raw, times } => {
+                write!(f, "{} samples but {} timestamps", raw, times)
+            }
+            TransducerError::NonMonotonicTime { index } => {
+                write!(f, "Timestamp at index {} does not increase strictly", index)
+            }
+            TransducerError::NonFiniteTime { index } => write!(f, "Timestamp at index {} is not finite", index),
+            TransducerError::TooShort { len, min } => {
+                write!(f, "Input has {} samples, at least {} are required", len, min)
+            }
+            TransducerError::DimensionMismatch { index, expected, got } => {
+                write!(f, "Sample {} has {} channels, expected {}", index, got, expected)
+            }
+            TransducerError::ChannelOutOfRange { channel, channels } => {
+                write!(f, "Segmentation channel {} is out of range ({} channels)", channel, channels)
+            }
+            TransducerError::AxisLengthMismatch { axis, channels } => {
+                write!(f, "Projection axis has {} terms, the stream has {} channels", axis, channels)
+            }
+            TransducerError::InvalidResamplingStep { step } => {
+                write!(f, "Resampling step must be finite and positive, got {}", step)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TransducerError {}
+
+/// Présence du temps comme premier canal du chemin signé.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeChannel {
+    #[default]
+    Include,
+    /// Signature des seuls canaux : insensible à la vitesse de parcours.
+    Exclude,
+}
+
+/// Chemin effectivement signé pour chaque segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignatureOf {
+    /// Les positions brutes.
+    #[default]
+    Path,
+    /// La vitesse par différences finies, v = dx / dt, placée au milieu de chaque
+    /// intervalle (dt divisé avant de différencier : correct en échantillonnage
+    /// irrégulier). Le profil de vitesse compte, pas la position.
+    Derivative,
+    /// Position (au milieu des intervalles) et vitesse concaténées : 2 × channels canaux.
+    Both,
+}
+
+impl SignatureOf {
+    /// Points et instants du chemin signé.
+    fn path<'a>(&self, raw: &'a [Vec<f64>], times: &'a [f64]) -> (Cow<'a, [Vec<f64>]>, Cow<'a, [f64]>) {
+        if *self == SignatureOf::Path {
+            return (Cow::Borrowed(raw), Cow::Borrowed(times));
+        }
+        let points = raw.windows(2).zip(times.windows(2))
+            .map(|(x, t)| {
+                let dt = t[1] - t[0];
+                let velocity = x[1].iter().zip(x[0].iter()).map(|(b, a)| (b - a) / dt);
+                match self {
+                    SignatureOf::Both => x[0].iter().zip(x[1].iter()).map(|(a, b)| (a + b) / 2.0).chain(velocity).collect(),
+                    _ => velocity.collect(),
+                }
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let midpoints = times.windows(2).map(|t| (t[0] + t[1]) / 2.0).collect::<Vec<f64>>();
+        (Cow::Owned(points), Cow::Owned(midpoints))
+    }
+}
+
+/// Interpolation utilisée par `UniversalTransducer::resample`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InterpKind {
+    /// Interpolation linéaire entre les deux échantillons encadrants.
+    #[default]
+    Linear,
+    /// Valeur du dernier échantillon reçu (bloqueur d'ordre zéro).
+    Hold,
+}
+
+/// Segments produits et nombre de segments écartés car plus courts que `min_points`
+/// (utile pour régler le seuil de bruit).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentationReport {
+    pub segments: Vec<SegmentedVector>,
+    pub discarded: usize,
+}
+
+/// Statistiques d'un segment, calculées sur les échantillons qu'il couvre.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentStats {
+    /// Échantillons couverts (pivots compris).
+    pub samples: usize,
+    /// (min, max) de chaque canal.
+    pub amplitude: Vec<(f64, f64)>,
+    /// Moyenne quadratique des mouvements entre échantillons, au sens du
+    /// `ChannelSelector` de segmentation.
+    pub increment_rms: f64,
+    pub mean_dt: f64,
+    pub max_dt: f64,
+    /// Seuil de bruit effectif au moment de la segmentation.
+    pub noise_eps: f64,
+    /// `SegmentationConfig::min_points` en vigueur.
+    pub min_points: usize,
+}
+
+impl SegmentStats {
+    fn measure(raw: &[Vec<f64>], times: &[f64], options: &TransducerOptions, noise_eps: f64) -> Self {
+        let dim = raw.first().map_or(0, |x| x.len());
+        let amplitude = (0..dim)
+            .map(|c| raw.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| (lo.min(x[c]), hi.max(x[c]))))
+            .collect();
+        let steps = raw.len().saturating_sub(1).max(1) as f64;
+        let square_sum: f64 = raw.windows(2)
+            .map(|w| norm(&options.segmentation.channel.movement(&w[0], &w[1])).powi(2))
+            .sum();
+        let max_dt = times.windows(2).map(|w| w[1] - w[0]).fold(0.0, f64::max);
+        let mean_dt = match (times.first(), times.last()) {
+            (Some(first), Some(last)) => (last - first) / steps,
+            _ => 0.0,
+        };
+        SegmentStats {
+            samples: raw.len(),
+            amplitude,
+            increment_rms: (square_sum / steps).sqrt(),
+            mean_dt,
+            max_dt,
+            noise_eps,
+            min_points: options.segmentation.min_points,
+        }
+    }
+}
+
+/// Fiabilité d'un segment dans [0, 1], inscrite dans `Metadata::reliability`.
+pub trait ReliabilityScorer: fmt::Debug + Send + Sync {
+    fn score(&self, stats: &SegmentStats) -> f64;
+}
+
+/// Produit de trois facteurs :
+/// - longueur : 1 − exp(−samples / (4 · min_points)), un segment à peine plus long que
+///   le minimum est suspect ;
+/// - régularité : mean_dt / max_dt au-delà d'un rapport 3 (trou comblé ou échantillon
+///   manquant) ;
+/// - rapport signal/bruit r = increment_rms / noise_eps : r² / (1 + r²).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultReliabilityScorer;
+
+impl ReliabilityScorer for DefaultReliabilityScorer {
+    fn score(&self, stats: &SegmentStats) -> f64 {
+        let length = 1.0 - (-(stats.samples as f64) / (4.0 * stats.min_points.max(1) as f64)).exp();
+        let regularity = if stats.max_dt > 3.0 * stats.mean_dt { 3.0 * stats.mean_dt / stats.max_dt } else { 1.0 };
+        let snr = if stats.noise_eps > 0.0 { stats.increment_rms / stats.noise_eps } else { f64::INFINITY };
+        let signal = if snr.is_finite() { snr * snr / (1.0 + snr * snr) } else { 1.0 };
+        (length * regularity * signal).clamp(0.0, 1.0)
+    }
+}
+
+/// Un vecteur et sa provenance dans le flux brut : il couvre exactement
+/// `raw[start_idx..=end_idx]`, et partage son dernier échantillon (pivot) avec le suivant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentedVector {
+    pub vector: UniversalVector,
+    pub start_idx: usize,
+    pub end_idx: usize,
+    pub t_start: f64,
+    pub t_end: f64,
+    pub stats: SegmentStats,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransducerOptions {
+    /// Profondeur de troncature des signatures (2..=4).
+    pub depth: usize,
+    /// Si un terme de la signature accumulée dépasse cette borne, le vecteur courant est
+    /// émis et l'accumulation repart de l'identité (protège les très longs segments
+    /// contre la perte de précision et les inf/NaN). `None` : pas de borne.
+    pub max_term_magnitude: Option<f64>,
+    /// Modalité inscrite dans les métadonnées des vecteurs produits.
+    pub modality: Modality,
+    pub timestamp_anchor: TimestampAnchor,
+    pub segmentation: SegmentationConfig,
+    /// Transformation lead-lag : chaque canal est dédoublé (lead, lag) et chaque
+    /// incrément devient deux pas, d'abord sur lead puis sur lag (le temps avance de
+    /// dt/2 à chacun). Capture la variation quadratique.
+    pub lead_lag: bool,
+    /// Ajoute un premier pas depuis l'origine jusqu'au premier échantillon du segment
+    /// (sans avance du temps) : la signature voit la position absolue.
+    pub basepoint: bool,
+    pub time_channel: TimeChannel,
+    pub signature_of: SignatureOf,
+    /// Calcule `Metadata::reliability` de chaque vecteur à partir de `SegmentStats`.
+    pub reliability: Arc<dyn ReliabilityScorer>,
+}
+
+impl TransducerOptions {
+    /// Dimension des signatures produites pour `channels` canaux bruts :
+    /// (1 si temps inclus) + channels × (2 si lead-lag, sinon 1) × (2 si `SignatureOf::Both`).
+    pub fn signature_dim(&self, channels: usize) -> usize {
+        let time = if self.time_channel == TimeChannel::Include { 1 } else { 0 };
+        let channels = if self.signature_of == SignatureOf::Both { 2 * channels } else { channels };
+        time + channels * if self.lead_lag { 2 } else { 1 }
+    }
+
+    /// Sous-incréments du chemin transformé pour un incrément brut (dt, dx).
+    fn augment(&self, dt: f64, dx: &[f64]) -> Vec<Vec<f64>> {
+        let time = self.time_channel == TimeChannel::Include;
+        if !self.lead_lag {
+            let mut piece = Vec::with_capacity(dx.len() + 1);
+            if time { piece.push(dt); }
+            piece.extend_from_slice(dx);
+            return vec![piece];
+        }
+        let zeros = vec![0.0; dx.len()];
+        [(dx, zeros.as_slice()), (zeros.as_slice(), dx)].iter()
+            .map(|(lead, lag)| {
+                let mut piece = Vec::with_capacity(2 * dx.len() + 1);
+                if time { piece.push(dt / 2.0); }
+                piece.extend_from_slice(lead);
+                piece.extend_from_slice(lag);
+                piece
+            })
+            .collect()
+    }
+
+    /// Pas initial depuis l'origine jusqu'à x0 (option `basepoint`).
+    fn basepoint_piece(&self, x0: &[f64]) -> Vec<f64> {
+        let mut piece = Vec::with_capacity(1 + 2 * x0.len());
+        if self.time_channel == TimeChannel::Include { piece.push(0.0); }
+        piece.extend_from_slice(x0);
+        if self.lead_lag { piece.extend_from_slice(x0); }
+        piece
+    }
+}
+
+/// Égalité champ à champ ; deux scoreurs sont égaux s'ils sont la même instance
+/// (`Arc::ptr_eq`), celui par défaut étant partagé.
+impl PartialEq for TransducerOptions {
+    fn eq(&self, other: &Self) -> bool {
+        self.depth == other.depth
+            && self.max_term_magnitude == other.max_term_magnitude
+            && self.modality == other.modality
+            && self.timestamp_anchor == other.timestamp_anchor
+            && self.segmentation == other.segmentation
+            && self.lead_lag == other.lead_lag
+            && self.basepoint == other.basepoint
+            && self.time_channel == other.time_channel
+            && self.signature_of == other.signature_of
+            && Arc::ptr_eq(&self.reliability, &other.reliability)
+    }
+}
+
+/// Instance partagée par toutes les options par défaut.
+fn default_reliability() -> Arc<dyn ReliabilityScorer> {
+    static DEFAULT: OnceLock<Arc<dyn ReliabilityScorer>> = OnceLock::new();
+    DEFAULT.get_or_init(|| Arc::new(DefaultReliabilityScorer)).clone()
+}
+
+impl Default for TransducerOptions {
+    fn default() -> Self {
+        TransducerOptions {
+            depth: DEFAULT_DEPTH,
+            max_term_magnitude: Some(DEFAULT_MAX_TERM_MAGNITUDE),
+            modality: Modality::Sensor,
+            timestamp_anchor: TimestampAnchor::Start,
+            segmentation: SegmentationConfig::default(),
+            lead_lag: false,
+            basepoint: false,
+            time_channel: TimeChannel::Include,
+            signature_of: SignatureOf::Path,
+            reliability: default_reliability(),
+        }
+    }
+}
+
 impl UniversalTransducer {
-    pub fn segment_and_process(raw: &[Vec<f64>], times: &Vec<f64>) -> Vec<UniversalVector> {
-        if raw.len() < 2 { return vec![]; }
+    pub fn segment_and_process(raw: &[Vec<f64>], times: &[f64]) -> Result<Vec<UniversalVector>, TransducerError> {
+        Self::segment_and_process_with_options(raw, times, &TransducerOptions::default())
+    }
+
+    /// Comme `segment_and_process`, avec une profondeur de troncature explicite (2..=4).
+    /// Niveau 2 suffit pour le réflexe ; niveau 4 pour la discrimination fine.
+    pub fn segment_and_process_with_depth(raw: &[Vec<f64>], times: &[f64], depth: usize) -> Result<Vec<UniversalVector>, TransducerError> {
+        let options = TransducerOptions { depth, ..TransducerOptions::default() };
+        Self::segment_and_process_with_options(raw, times, &options)
+    }
+
+    pub fn segment_and_process_with_options(raw: &[Vec<f64>], times: &[f64], options: &TransducerOptions) -> Result<Vec<UniversalVector>, TransducerError> {
+        Ok(Self::segment_and_process_indexed(raw, times, options)?
+            .into_iter()
+            .map(|s| s.vector)
+            .collect())
+    }
+
+    /// Signal scalaire : chaque échantillon devient un vecteur à un canal, puis tout
+    /// passe par le chemin N-dimensionnel (même segmentation, même accumulation de Chen,
+    /// même `SegmentationConfig`).
+    pub fn process_1d(raw: &[f64], times: &[f64], options: &TransducerOptions) -> Result<Vec<UniversalVector>, TransducerError> {
+        let wrapped: Vec<Vec<f64>> = raw.iter().map(|&x| vec![x]).collect();
+        Ok(Self::segment_and_process_indexed(&wrapped, times, options)?
+            .into_iter()
+            .map(|s| s.vector)
+            .collect())
+    }
+
+    /// Un vecteur par segment de chaque canal, segmenté indépendamment (ses propres
+    /// ruptures) : `result[c]` contient les vecteurs du canal c, chacun avec
+    /// `metadata.channel == Some(c)`. Le sélecteur de canal de `options` est ignoré.
+    pub fn process_channels(raw: &[Vec<f64>], times: &[f64], options: &TransducerOptions) -> Result<Vec<Vec<UniversalVector>>, TransducerError> {
+        if raw.is_empty() { return Err(TransducerError::EmptyInput); }
+        let channels = raw[0].len();
+        if let Some(index) = raw.iter().position(|x| x.len() != channels) {
+            return Err(TransducerError::DimensionMismatch { index, expected: channels, got: raw[index].len() });
+        }
+        let mut single = options.clone();
+        single.segmentation.channel = ChannelSelector::Channel(0);
+
+        (0..channels)
+            .map(|c| {
+                let column: Vec<Vec<f64>> = raw.iter().map(|x| vec![x[c]]).collect();
+                Ok(Self::segment_and_process_indexed(&column, times, &single)?
+                    .into_iter()
+                    .map(|s| {
+                        let mut vector = s.vector;
+                        vector.metadata.channel = Some(c);
+                        vector
+                    })
+                    .collect())
+            })
+            .collect()
+    }
+
+    /// Comme `segment_and_process_with_options`, en conservant les indices et instants
+    /// de début/fin de chaque segment dans `raw`.
+    /// Erreurs : entrée vide, moins de deux échantillons, longueurs différentes,
+    /// instants non strictement croissants.
+    pub fn segment_and_process_indexed(raw: &[Vec<f64>], times: &[f64], options: &TransducerOptions) -> Result<Vec<SegmentedVector>, TransducerError> {
+        Ok(Self::segment_with_report(raw, times, options)?.segments)
+    }
+
+    /// Comme `segment_and_process_indexed`, avec le compte des segments écartés.
+    pub fn segment_with_report(raw: &[Vec<f64>], times: &[f64], options: &TransducerOptions) -> Result<SegmentationReport, TransducerError> {
+        if raw.is_empty() { return Err(TransducerError::EmptyInput); }
+        if raw.len() < 2 { return Err(TransducerError::TooShort { len: raw.len(), min: 2 }); }
+        // Frontières détectées en série, puis segments transduits indépendamment
+        let mut chunked = ChunkedTransducer::new(options.clone());
+        chunked.spans = Some(Vec::new());
+        chunked.push(raw, times)?;
+        let noise_eps = chunked.noise_eps.unwrap_or(DEFAULT_NOISE_EPS);
+        chunked.finish();
+        let spans = chunked.spans.take().unwrap_or_default();
+        Ok(SegmentationReport {
+            segments: Self::transduce_spans(raw, times, &spans, options, noise_eps),
+            discarded: chunked.discarded(),
+        })
+    }
+
+    /// Transduit chaque segment [start, end] (inclus) ; en parallèle avec la feature
+    /// `parallel`, dans l'ordre d'origine et avec les mêmes valeurs.
+    fn transduce_spans(raw: &[Vec<f64>], times: &[f64], spans: &[(usize, usize)], options: &TransducerOptions, noise_eps: f64) -> Vec<SegmentedVector> {
+        let one = |&(start, end): &(usize, usize)| {
+            Self::create_vector_from_slice(&raw[start..=end], &times[start..=end], options, start, noise_eps)
+        };
+
+        #[cfg(feature = "parallel")]
+        let per_segment: Vec<Vec<SegmentedVector>> = {
+            use rayon::prelude::*;
+            spans.par_iter().map(one).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let per_segment: Vec<Vec<SegmentedVector>> = spans.iter().map(one).collect();
+
+        per_segment.into_iter().flatten().collect()
+    }
+
+    /// Transduction paresseuse d'un flux (lecteur de fichier, socket) : les vecteurs
+    /// sont produits au fil des ruptures, sans jamais matérialiser l'entrée ; la mémoire
+    /// reste de l'ordre d'un segment. Un morceau invalide produit une erreur et est
+    /// ignoré, le flux continue.
+    pub fn process_iter<I>(iter: I, options: TransducerOptions) -> impl Iterator<Item = Result<UniversalVector, TransducerError>>
+    where
+        I: IntoIterator<Item = (f64, Vec<f64>)>,
+    {
+        let mut source = iter.into_iter();
+        let mut chunked = ChunkedTransducer::new(options);
+        let mut ready: VecDeque<Result<UniversalVector, TransducerError>> = VecDeque::new();
+        let mut done = false;
+        let (mut raw, mut times) = (Vec::with_capacity(ITER_CHUNK), Vec::with_capacity(ITER_CHUNK));
+
+        std::iter::from_fn(move || loop {
+            if let Some(next) = ready.pop_front() {
+                return Some(next);
+            }
+            if done {
+                return None;
+            }
+            for (t, x) in source.by_ref().take(ITER_CHUNK) {
+                times.push(t);
+                raw.push(x);
+            }
+            let segments = if raw.is_empty() {
+                done = true;
+                Ok(chunked.finish())
+            } else {
+                chunked.push(&raw, &times)
+            };
+            raw.clear();
+            times.clear();
+            match segments {
+                Ok(segments) => ready.extend(segments.into_iter().map(|s| Ok(s.vector))),
+                Err(e) => ready.push_back(Err(e)),
+            }
+        })
+    }
+
+    /// Rééchantillonne sur une grille régulière de pas `target_dt` partant de `times[0]`,
+    /// pour que les signatures de capteurs à horodatage irrégulier restent comparables.
+    /// Erreur si `target_dt` n'est pas fini et positif ou si les longueurs diffèrent.
+    pub fn resample(raw: &[Vec<f64>], times: &[f64], target_dt: f64, kind: InterpKind) -> Result<(Vec<Vec<f64>>, Vec<f64>), TransducerError> {
+        Self::resample_with_gaps(raw, times, target_dt, kind, None)
+    }
+
+    /// Comme `resample`, sans interpoler à travers les écarts supérieurs à `gap_threshold` :
+    /// la grille repart du premier échantillon après le trou (avec le même
+    /// `gap_threshold` dans `SegmentationConfig`, le trou devient une frontière de segment).
+    pub fn resample_with_gaps(raw: &[Vec<f64>], times: &[f64], target_dt: f64, kind: InterpKind, gap_threshold: Option<f64>) -> Result<(Vec<Vec<f64>>, Vec<f64>), TransducerError> {
+        let start = times.first().copied().unwrap_or(0.0);
+        Self::resample_from(raw, times, start, target_dt, kind, gap_threshold)
+    }
+
+    /// Grille start, start + target_dt, ... jusqu'au dernier instant (le scanner y
+    /// reprend la grille du lot précédent).
+    pub(crate) fn resample_from(raw: &[Vec<f64>], times: &[f64], start: f64, target_dt: f64, kind: InterpKind, gap_threshold: Option<f64>) -> Result<(Vec<Vec<f64>>, Vec<f64>), TransducerError> {
+        if !(target_dt.is_finite() && target_dt > 0.0) {
+            return Err(TransducerError::InvalidResamplingStep { step: target_dt });
+        }
+        if raw.len() != times.len() {
+            return Err(TransducerError::LengthMismatch { raw: raw.len(), times: times.len() });
+        }
+        let (mut out_raw, mut out_times) = (Vec::new(), Vec::new());
+        let Some(&end) = times.last() else { return Ok((out_raw, out_times)) };
+        let is_gap = |j: usize| gap_threshold.is_some_and(|gap| times[j + 1] - times[j] > gap);
+
+        let (mut origin, mut k, mut j) = (start, 0usize, 0usize);
+        loop {
+            let t = origin + k as f64 * target_dt;
+            if t > end { break; }
+            while j + 1 < times.len() && times[j + 1] <= t && !is_gap(j) {
+                j += 1;
+            }
+            if j + 1 < times.len() && t > times[j] && is_gap(j) {
+                // Pas d'interpolation à travers le trou : la grille repart après lui
+                j += 1;
+                origin = times[j];
+                k = 0;
+                continue;
+            }
+
+            let value = match kind {
+                InterpKind::Linear if j + 1 < times.len() && t > times[j] => {
+                    let w = (t - times[j]) / (times[j + 1] - times[j]);
+                    raw[j].iter().zip(raw[j + 1].iter()).map(|(a, b)| a * (1.0 - w) + b * w).collect()
+                }
+                _ => raw[j].clone(),
+            };
+            out_raw.push(value);
+            out_times.push(t);
+            k += 1;
+        }
+        Ok((out_raw, out_times))
+    }
+
+    /// Un segment produit normalement un seul vecteur, plusieurs si la borne
+    /// `max_term_magnitude` est atteinte en cours d'accumulation.
+    /// `offset` : index de `raw[0]` dans le flux complet ; `noise_eps` : seuil de bruit
+    /// effectif, reporté dans les statistiques.
+    fn create_vector_from_slice(raw: &[Vec<f64>], sample_times: &[f64], options: &TransducerOptions, offset: usize, noise_eps: f64) -> Vec<SegmentedVector> {
+        // Entrées déjà validées par `validate` ou le `ChunkedTransducer`
+        debug_assert!(!raw.is_empty(), "Raw data cannot be empty");
+        debug_assert_eq!(sample_times.len(), raw.len());
+        let dim = raw[0].len();
+        let finish = |mut vector: UniversalVector, start: usize, end: usize| {
+            let timestamp = match options.timestamp_anchor {
+                TimestampAnchor::Start => sample_times[start],
+                TimestampAnchor::Midpoint => (sample_times[start] + sample_times[end]) / 2.0,
+            };
+            let stats = SegmentStats::measure(&raw[start..=end], &sample_times[start..=end], options, noise_eps);
+            vector.metadata = Metadata::builder()
+                .timestamp(timestamp)
+                .modality(options.modality.clone())
+                .reliability(options.reliability.score(&stats))
+                .build();
+            SegmentedVector {
+                vector,
+                start_idx: offset + start,
+                end_idx: offset + end,
+                t_start: sample_times[start],
+                t_end: sample_times[end],
+                stats,
+            }
+        };
+
+        // 1. Chemin signé et ses incréments multidimensionnels (Deltas). Le point j d'un
+        // chemin dérivé est le milieu de raw[j..=j + 1].
+        let (path, path_times) = options.signature_of.path(raw, sample_times);
+        let shift = if options.signature_of == SignatureOf::Path { 0 } else { 1 };
+        let raw_index = |j: usize| if j == 0 { 0 } else { j + shift };
+        // (calculés au fil de l'accumulation : le flux garde sa propre copie pour le gradient)
+        let deltas = path.windows(2).zip(path_times.windows(2))
+            .map(|(w_raw, w_time)| {
+                let dt = w_time[1] - w_time[0];
+                let dx: Vec<f64> = w_raw[1].iter().zip(w_raw[0].iter())
+                                 .map(|(x1, x0)| x1 - x0)
+                                 .collect();
+                (dt, dx)
+            });
+
+        // 2. Accumulation via l'identité de Chen, sur le chemin transformé
+        // (dimension `options.signature_dim(dim)` ; dim + 1 par défaut : canaux + temps)
+        let mut stream = StreamingSignature::augmented(options.signature_dim(dim), options.depth);
         let mut vectors = Vec::new();
-        let mut start_idx = 0;
-        let mut current_sign = 0.0; 
+        let mut piece_start = 0;
+
+        for (i, (dt, dx)) in deltas.enumerate() {
+            let mut pieces = options.augment(dt, &dx);
+            if i == 0 && options.basepoint {
+                pieces.insert(0, options.basepoint_piece(&path[0]));
+            }
+            let accepted = match options.max_term_magnitude {
+                Some(bound) => stream.try_push_augmented(dt, &dx, &pieces, bound),
+                None => { stream.push_augmented(dt, &dx, &pieces); true }
+            };
+            if !accepted {
+                // Émet le morceau accumulé et repart de l'identité avec cet incrément
+                vectors.push(finish(stream.snapshot_and_reset(), raw_index(piece_start), raw_index(i)));
+                piece_start = i;
+                stream.push_augmented(dt, &dx, &pieces);
+            }
+        }
+
+        vectors.push(finish(stream.snapshot_and_reset(), raw_index(piece_start), raw.len() - 1));
+        vectors
+    }
+}
 
-        for i in 1..raw.len() {
-            let dx = raw[i][0] - raw[i-1][0];
-            if dx.abs() < 1e-6 { continue; }
-            let sign = dx.signum();
 
-            if current_sign == 0.0 { current_sign = sign; }
+/// Transduction par morceaux d'un flux continu : la direction courante, la bande morte
+/// et le segment inachevé survivent d'un appel à l'autre, si bien qu'une rupture à
+/// cheval sur deux morceaux est vue exactement comme dans un traitement en un bloc.
+/// Un segment n'est émis qu'à sa rupture, quand il atteint `max_points` ou
+/// `max_duration`, ou par `finish`.
+///
+/// Avec `NoiseEps::Adaptive`, le seuil est estimé sur le premier morceau d'au moins
+/// deux échantillons, puis figé.
+#[derive(Debug, Clone)]
+pub struct ChunkedTransducer {
+    options: TransducerOptions,
+    noise_eps: Option<f64>,
+    discarded: usize,
+    // Segment inachevé
+    raw: Vec<Vec<f64>>,
+    times: Vec<f64>,
+    // Index dans le flux de raw[0]
+    offset: usize,
+    // Dernier échantillon significatif (index dans raw)
+    reference: usize,
+    // Direction d'ouverture du segment courant
+    direction: Option<(Vec<f64>, f64)>,
+    // Si présent, les segments sont seulement délimités (indices du flux), pas transduits
+    spans: Option<Vec<(usize, usize)>>,
+    // Échantillons lissés alignés sur raw (vide sans PreFilter) et état du filtre
+    filtered: Vec<Vec<f64>>,
+    filter: FilterState,
+}
+
+impl ChunkedTransducer {
+    pub fn new(options: TransducerOptions) -> Self {
+        ChunkedTransducer {
+            options,
+            noise_eps: None,
+            discarded: 0,
+            raw: Vec::new(),
+            times: Vec::new(),
+            offset: 0,
+            reference: 0,
+            direction: None,
+            spans: None,
+            filtered: Vec::new(),
+            filter: FilterState::default(),
+        }
+    }
+
+    pub fn options(&self) -> &TransducerOptions {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut TransducerOptions {
+        &mut self.options
+    }
+
+    /// Échantillons du segment inachevé.
+    pub fn pending(&self) -> usize {
+        self.raw.len()
+    }
 
-            if sign != current_sign {
-                let segment_raw = &raw[start_idx..i];
-                let segment_times = &times[start_idx..i];
-                if segment_raw.len() >= 3 {
-                    vectors.push(Self::create_vector_from_slice(segment_raw, Some(segment_times.to_vec())));
+    /// Segments écartés depuis la création car plus courts que `min_points`.
+    pub fn discarded(&self) -> usize {
+        self.discarded
+    }
+
+    /// Ingère un morceau et retourne les segments terminés. Les indices des
+    /// `SegmentedVector` comptent les échantillons depuis le début du flux.
+    /// Un morceau invalide (longueurs, dimension, instants non croissants, y compris
+    /// par rapport au morceau précédent) est rejeté en entier sans modifier l'état.
+    pub fn push(&mut self, raw: &[Vec<f64>], times: &[f64]) -> Result<Vec<SegmentedVector>, TransducerError> {
+        self.validate(raw, times)?;
+        if self.noise_eps.is_none() && raw.len() >= 2 {
+            self.noise_eps = Some(self.options.segmentation.noise_threshold(raw));
+        }
+        let noise_eps = self.noise_eps.unwrap_or(DEFAULT_NOISE_EPS);
+
+        let mut vectors = Vec::new();
+        for (sample, t) in raw.iter().zip(times.iter()) {
+            self.raw.push(sample.clone());
+            self.times.push(*t);
+            let prefilter = self.options.segmentation.prefilter;
+            if prefilter != PreFilter::None {
+                self.filtered.push(self.filter.apply(prefilter, sample));
+            }
+            let mut i = self.raw.len() - 1;
+            if i == 0 { continue; }
+
+            if self.options.segmentation.gap_threshold.is_some_and(|gap| self.times[i] - self.times[i - 1] > gap) {
+                // Le segment s'arrête avant le trou ; le suivant commence après
+                vectors.extend(self.emit(i));
+                self.raw.remove(0);
+                self.times.remove(0);
+                if !self.filtered.is_empty() {
+                    // Pas de lissage à travers le trou
+                    self.filter = FilterState::default();
+                    self.filtered = vec![self.filter.apply(prefilter, &self.raw[0])];
                 }
-                start_idx = i - 1;
-                current_sign = sign;
+                self.offset += 1;
+                self.reference = 0;
+                self.direction = None;
+                continue;
+            }
+
+            let max_duration = self.options.segmentation.max_duration;
+            if i >= 2 && max_duration.is_some_and(|max| self.times[i] - self.times[0] > max) {
+                vectors.extend(self.emit(i));
+                i = self.raw.len() - 1;
+            }
+
+            let segmentation = &self.options.segmentation;
+            let track = if self.filtered.is_empty() { &self.raw } else { &self.filtered };
+            let movement = segmentation.channel.movement(&track[self.reference], &track[i]);
+            let length = norm(&movement);
+            if length >= noise_eps {
+                self.reference = i;
+
+                let (opening, opening_length) = self.direction.get_or_insert_with(|| (movement.clone(), length));
+                let cosine = opening.iter().zip(movement.iter()).map(|(a, b)| a * b).sum::<f64>() / (*opening_length * length);
+
+                if cosine < segmentation.channel.min_cosine() {
+                    vectors.extend(self.emit(i));
+                    self.direction = Some((movement, length));
+                    continue;
+                }
+            }
+
+            if self.options.segmentation.max_points.is_some_and(|max| self.raw.len() >= max.max(2)) {
+                vectors.extend(self.emit(self.raw.len()));
             }
         }
+        Ok(vectors)
+    }
 
-        if start_idx < raw.len() - 1 {
-            let segment_raw = &raw[start_idx..];
-            let segment_times = &times[start_idx..];
-            if segment_raw.len() >= 3 {
-                vectors.push(Self::create_vector_from_slice(segment_raw, Some(segment_times.to_vec())));
+    fn validate(&self, raw: &[Vec<f64>], times: &[f64]) -> Result<(), TransducerError> {
+        if raw.len() != times.len() {
+            return Err(TransducerError::LengthMismatch { raw: raw.len(), times: times.len() });
+        }
+        let channels = self.raw.first().or(raw.first()).map_or(0, |x| x.len());
+        let stream_start = self.offset + self.raw.len();
+        if let Some(i) = raw.iter().position(|x| x.len() != channels) {
+            return Err(TransducerError::DimensionMismatch { index: stream_start + i, expected: channels, got: raw[i].len() });
+        }
+        if !raw.is_empty() {
+            self.options.segmentation.channel.check(channels)?;
+        }
+        let mut previous = self.times.last().copied();
+        for (i, t) in times.iter().enumerate() {
+            if !t.is_finite() {
+                return Err(TransducerError::NonFiniteTime { index: stream_start + i });
+            }
+            if previous.is_some_and(|p| *t <= p) {
+                return Err(TransducerError::NonMonotonicTime { index: stream_start + i });
             }
+            previous = Some(*t);
         }
+        Ok(())
+    }
 
+    /// Émet le segment inachevé (s'il a au moins `min_points` échantillons) et remet l'état à zéro ;
+    /// le flux peut ensuite reprendre, ses indices continuant à la suite.
+    pub fn finish(&mut self) -> Vec<SegmentedVector> {
+        let vectors = if self.raw.len() >= 2 { self.emit(self.raw.len()) } else { vec![] };
+        self.offset += self.raw.len();
+        self.raw.clear();
+        self.times.clear();
+        self.filtered.clear();
+        self.filter = FilterState::default();
+        self.reference = 0;
+        self.direction = None;
+        self.noise_eps = None;
         vectors
     }
 
-    fn create_vector_from_slice(raw: &[Vec<f64>], times: Option<Vec<f64>>) -> UniversalVector {
-        assert!(!raw.is_empty(), "Raw data cannot be empty");
-        let dim = raw[0].len();
+    /// Remplace l'état du `PreFilter` par celui obtenu en lissant `context`, les
+    /// derniers échantillons déjà ingérés : la continuité du lissage d'un morceau au
+    /// suivant est alors portée par l'appelant. Exact pour `MovingAverage` si
+    /// `context` couvre la fenêtre ; sans contexte, le lissage repart de zéro.
+    pub fn prime_filter(&mut self, context: &[Vec<f64>]) {
+        let prefilter = self.options.segmentation.prefilter;
+        self.filter = FilterState::default();
+        if prefilter != PreFilter::None {
+            for sample in context {
+                self.filter.apply(prefilter, sample);
+            }
+        }
+    }
 
-        // 1. Calcul des incréments multidimensionnels (Deltas)
-        let deltas: Vec<(f64, Vec<f64>)> = match times {
-            Some(t) => {
-                assert_eq!(t.len(), raw.len());
-                raw.windows(2).zip(t.windows(2))
-                    .map(|(w_raw, w_time)| {
-                        let dt = w_time[1] - w_time[0];
-                        let dX = w_raw[1].iter().zip(w_raw[0].iter())
-                                         .map(|(x1, x0)| x1 - x0)
-                                         .collect();
-                        (dt, dX)
-                    })
-                    .collect()
+    /// Émet le segment inachevé sans réinitialiser l'état, comme une coupure à
+    /// `max_points` : son dernier échantillon reste le pivot du segment suivant.
+    pub fn cut(&mut self) -> Vec<SegmentedVector> {
+        if self.raw.len() >= 2 { self.emit(self.raw.len()) } else { vec![] }
+    }
+
+    /// Transduit raw[..end] et ne garde que le pivot raw[end - 1] et la suite.
+    fn emit(&mut self, end: usize) -> Vec<SegmentedVector> {
+        let vectors = if end >= self.options.segmentation.min_points.max(2) {
+            match self.spans.as_mut() {
+                Some(spans) => {
+                    spans.push((self.offset, self.offset + end - 1));
+                    vec![]
+                }
+                None => {
+                    let noise_eps = self.noise_eps.unwrap_or(DEFAULT_NOISE_EPS);
+                    UniversalTransducer::create_vector_from_slice(&self.raw[..end], &self.times[..end], &self.options, self.offset, noise_eps)
+                }
+            }
+        } else {
+            // Un seul échantillon (deux ruptures consécutives) n'est pas un segment
+            if end >= 2 { self.discarded += 1; }
+            vec![]
+        };
+        let kept_from = end - 1;
+        self.raw.drain(..kept_from);
+        self.times.drain(..kept_from);
+        if !self.filtered.is_empty() {
+            self.filtered.drain(..kept_from);
+        }
+        self.offset += kept_from;
+        self.reference = self.reference.saturating_sub(kept_from);
+        vectors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_path_stays_finite() {
+        // 10^6 incréments énormes dans un seul segment monotone
+        let n = 1_000_000;
+        let raw: Vec<Vec<f64>> = (0..n).map(|i| vec![i as f64 * 1e46]).collect();
+        let times: Vec<f64> = (0..n).map(|i| i as f64).collect();
+
+        let unbounded = TransducerOptions { max_term_magnitude: None, ..TransducerOptions::default() };
+        let naive = UniversalTransducer::segment_and_process_with_options(&raw, &times, &unbounded).unwrap();
+        assert_eq!(naive.len(), 1);
+        assert!(naive[0].resonance_directional(&naive[0]).is_nan());
+
+        let vectors = UniversalTransducer::segment_and_process(&raw, &times).unwrap();
+        assert!(vectors.len() > 1);
+        let total: usize = vectors.iter().map(|v| v.gradient.len()).sum();
+        assert_eq!(total, n - 1);
+        for v in &vectors {
+            assert!(v.signature.is_finite());
+            assert!(v.signature.max_abs() <= DEFAULT_MAX_TERM_MAGNITUDE);
+            for score in [
+                v.resonance_directional(&vectors[0]),
+                v.resonance_structural(&vectors[0], 1.0),
+                v.resonance_full(&vectors[0], 1.0),
+            ] {
+                assert!(score.is_finite());
+            }
+        }
+    }
+
+    #[test]
+    fn test_segment_metadata() {
+        let times: Vec<f64> = (0..60).map(|i| 10.0 + i as f64 * 0.05).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * 4.0).sin()]).collect();
+
+        let vectors = UniversalTransducer::segment_and_process(&raw, &times).unwrap();
+        assert!(vectors.len() >= 3);
+        assert_eq!(vectors[0].metadata.timestamp, 10.0);
+        for pair in vectors.windows(2) {
+            assert!(pair[1].metadata.timestamp > pair[0].metadata.timestamp);
+        }
+        assert!(vectors.iter().all(|v| v.metadata.modality == Modality::Sensor));
+
+        let options = TransducerOptions {
+            modality: Modality::Audio,
+            timestamp_anchor: TimestampAnchor::Midpoint,
+            ..TransducerOptions::default()
+        };
+        let mid = UniversalTransducer::segment_and_process_with_options(&raw, &times, &options).unwrap();
+        for (start, centered) in vectors.iter().zip(mid.iter()) {
+            let half_span = centered.gradient.duration() / 2.0;
+            assert!((centered.metadata.timestamp - (start.metadata.timestamp + half_span)).abs() < 1e-9);
+            assert_eq!(centered.metadata.modality, Modality::Audio);
+        }
+    }
+
+    #[test]
+    fn test_adaptive_noise_threshold() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+
+        // 3 périodes de cosinus bruité : 6 demi-périodes monotones
+        let n = 2000;
+        let periods = 3.0;
+        let times: Vec<f64> = (0..n).map(|i| i as f64 / n as f64).collect();
+        let raw: Vec<Vec<f64>> = times.iter()
+            .map(|t| vec![(2.0 * std::f64::consts::PI * periods * t).cos() + rng.gen_range(-0.01..0.01)])
+            .collect();
+
+        let fixed = UniversalTransducer::segment_and_process(&raw, &times).unwrap();
+        let options = TransducerOptions {
+            segmentation: SegmentationConfig { noise_eps: NoiseEps::Adaptive { multiplier: 3.0 }, ..SegmentationConfig::default() },
+            ..TransducerOptions::default()
+        };
+        let adaptive = UniversalTransducer::segment_and_process_with_options(&raw, &times, &options).unwrap();
+
+        assert!(fixed.len() > 100, "only {} spurious segments", fixed.len());
+        assert_eq!(adaptive.len(), 6);
+    }
+
+    #[test]
+    fn test_norm_of_increment_quarter_turns() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(6);
+
+        // Un tour de cercle légèrement bruité
+        let n = 400;
+        let times: Vec<f64> = (0..=n).map(|i| i as f64).collect();
+        let raw: Vec<Vec<f64>> = times.iter()
+            .map(|i| {
+                let theta = 2.0 * std::f64::consts::PI * i / n as f64;
+                vec![theta.cos() + rng.gen_range(-2e-3..2e-3), theta.sin() + rng.gen_range(-2e-3..2e-3)]
+            })
+            .collect();
+
+        // Le canal 0 seul sur-segmente autour de ses extrema (θ = 0, π)
+        let channel0 = UniversalTransducer::segment_and_process(&raw, &times).unwrap();
+        assert!(channel0.len() > 4, "channel 0 found {} segments", channel0.len());
+
+        let options = TransducerOptions {
+            segmentation: SegmentationConfig {
+                channel: ChannelSelector::NormOfIncrement { min_cosine: 0.0 },
+                noise_eps: NoiseEps::Adaptive { multiplier: 3.0 },
+                ..SegmentationConfig::default()
             },
-            None => raw.windows(2).map(|w| {
-                let dX = w[1].iter().zip(w[0].iter())
-                             .map(|(x1, x0)| x1 - x0)
-                             .collect();
-                (1.0, dX)
-            }).collect(),
+            ..TransducerOptions::default()
+        };
+        let quarters = UniversalTransducer::segment_and_process_with_options(&raw, &times, &options).unwrap();
+        assert_eq!(quarters.len(), 4);
+        for (q, v) in quarters.iter().enumerate() {
+            let expected = (q * n / 4) as f64;
+            assert!((v.metadata.timestamp - expected).abs() <= (n / 20) as f64, "quarter {} starts at {}", q, v.metadata.timestamp);
+        }
+
+        // Projection sur l'axe y : même découpage que le canal 1
+        let projected = TransducerOptions {
+            segmentation: SegmentationConfig { channel: ChannelSelector::ProjectionOnto(vec![0.0, 1.0]), ..SegmentationConfig::default() },
+            ..TransducerOptions::default()
+        };
+        let by_y = UniversalTransducer::segment_and_process_with_options(&raw, &times, &projected).unwrap();
+        let channel1 = TransducerOptions {
+            segmentation: SegmentationConfig { channel: ChannelSelector::Channel(1), ..SegmentationConfig::default() },
+            ..TransducerOptions::default()
         };
+        assert_eq!(by_y, UniversalTransducer::segment_and_process_with_options(&raw, &times, &channel1).unwrap());
+    }
 
-        // 2. Accumulation via l'identité de Chen
-        // Fix: Use dim + 1 because we often augment with time, 
-        // but `Signature::zero` expects the signature dimension.
-        // Based on `from_segment`, the signature dimension is (dim + 1).
-        let mut current_signature = Signature::zero(dim + 1);
+    #[test]
+    fn test_indexed_segments_share_pivots() {
+        let times: Vec<f64> = (0..120).map(|i| i as f64 * 0.05).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * 2.0).sin(), t.cos()]).collect();
+        let options = TransducerOptions::default();
 
-        for (dt, dX) in deltas.iter() {
-            let segment_signature = Signature::from_segment(*dt, dX);
-            current_signature = current_signature.combine(&segment_signature);
+        let indexed = UniversalTransducer::segment_and_process_indexed(&raw, &times, &options).unwrap();
+        assert!(indexed.len() >= 3);
+        assert_eq!(indexed[0].start_idx, 0);
+        assert_eq!(indexed.last().unwrap().end_idx, raw.len() - 1);
+        for pair in indexed.windows(2) {
+            assert_eq!(pair[0].end_idx, pair[1].start_idx);
         }
 
-        let gradient = Gradient::update(&deltas);
+        let plain = UniversalTransducer::segment_and_process_with_options(&raw, &times, &options).unwrap();
+        for (seg, v) in indexed.iter().zip(plain.iter()) {
+            assert_eq!(&seg.vector, v);
+            assert_eq!((seg.t_start, seg.t_end), (times[seg.start_idx], times[seg.end_idx]));
 
-        UniversalVector {
-            signature: current_signature,
-            gradient,
-            metadata: Metadata::zero(),
+            // Le vecteur est exactement celui de la tranche brute
+            let slice = &raw[seg.start_idx..=seg.end_idx];
+            let alone = UniversalTransducer::segment_and_process_with_options(slice, &times[seg.start_idx..=seg.end_idx], &options).unwrap();
+            assert_eq!(alone.len(), 1);
+            assert_eq!(alone[0].signature, v.signature);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_chunked_matches_one_shot() {
+        let times: Vec<f64> = (0..500).map(|i| i as f64 * 0.01).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * 7.0).sin() + 0.3 * (t * 23.0).sin(), t.cos()]).collect();
+        let options = TransducerOptions::default();
+
+        let one_shot = UniversalTransducer::segment_and_process_indexed(&raw, &times, &options).unwrap();
+        assert!(one_shot.len() > 10);
+
+        let mut chunked = ChunkedTransducer::new(options.clone());
+        let mut streamed = Vec::new();
+        for (r, t) in raw.chunks(7).zip(times.chunks(7)) {
+            streamed.extend(chunked.push(r, t).unwrap());
+        }
+        assert!(chunked.pending() > 0);
+        streamed.extend(chunked.finish());
+        assert_eq!(chunked.pending(), 0);
+        assert_eq!(streamed, one_shot);
+
+        // Longueur maximale : les segments sont coupés mais restent jointifs
+        let mut capped_options = options;
+        capped_options.segmentation.max_points = Some(10);
+        let mut capped = ChunkedTransducer::new(capped_options);
+        let mut pieces = capped.push(&raw, &times).unwrap();
+        pieces.extend(capped.finish());
+        assert!(pieces.len() > one_shot.len());
+        assert!(pieces.iter().all(|s| s.end_idx - s.start_idx < 10));
+        for pair in pieces.windows(2) {
+            assert_eq!(pair[0].end_idx, pair[1].start_idx);
+        }
+    }
+
+    #[test]
+    fn test_lead_lag_distinguishes_orderings() {
+        // Mêmes incréments dans deux ordres différents, canal unique sans temps :
+        // la signature simple ne voit que l'incrément total
+        let path = |values: &[f64]| -> Vec<Vec<f64>> { values.iter().map(|&x| vec![x]).collect() };
+        let a = path(&[0.0, 1.0, 1.5, 3.5]);
+        let b = path(&[0.0, 2.0, 2.5, 3.5]);
+        let times: Vec<f64> = (0..4).map(|i| i as f64).collect();
+
+        let plain = TransducerOptions { time_channel: TimeChannel::Exclude, ..TransducerOptions::default() };
+        let (pa, pb) = (
+            UniversalTransducer::segment_and_process_with_options(&a, &times, &plain).unwrap(),
+            UniversalTransducer::segment_and_process_with_options(&b, &times, &plain).unwrap(),
+        );
+        assert_eq!(pa[0].signature.dim, plain.signature_dim(1));
+        assert!(pa[0].signature.distance(&pb[0].signature) < 1e-12);
+
+        let lead_lag = TransducerOptions { lead_lag: true, ..plain.clone() };
+        let (la, lb) = (
+            UniversalTransducer::segment_and_process_with_options(&a, &times, &lead_lag).unwrap(),
+            UniversalTransducer::segment_and_process_with_options(&b, &times, &lead_lag).unwrap(),
+        );
+        assert_eq!(la[0].signature.dim, 2);
+        assert!(la[0].signature.distance(&lb[0].signature) > 0.1);
+        // La variation quadratique apparaît dans l'aire de Lévy lead/lag
+        let qv: f64 = [1.0f64, 0.5, 2.0].iter().map(|d| d * d).sum();
+        assert!((la[0].signature.levy_area(0, 1).unwrap() - qv / 2.0).abs() < 1e-12);
+
+        // Basepoint : même forme, position absolue différente
+        let shifted = path(&[10.0, 11.0, 11.5, 13.5]);
+        let based = TransducerOptions { basepoint: true, ..TransducerOptions::default() };
+        let (sa, ss) = (
+            UniversalTransducer::segment_and_process_with_options(&a, &times, &based).unwrap(),
+            UniversalTransducer::segment_and_process_with_options(&shifted, &times, &based).unwrap(),
+        );
+        assert_eq!(sa[0].signature.dim, 2);
+        assert!(sa[0].signature.distance(&ss[0].signature) > 1.0);
+        assert_eq!(
+            UniversalTransducer::segment_and_process(&a, &times).unwrap()[0].signature,
+            UniversalTransducer::segment_and_process(&shifted, &times).unwrap()[0].signature,
+        );
+    }
+
+    #[test]
+    fn test_invalid_input_errors() {
+        let options = TransducerOptions::default();
+        let raw: Vec<Vec<f64>> = (0..5).map(|i| vec![i as f64]).collect();
+
+        assert_eq!(UniversalTransducer::segment_and_process(&[], &[]), Err(TransducerError::EmptyInput));
+        assert_eq!(
+            UniversalTransducer::segment_and_process(&raw[..1], &[0.0]),
+            Err(TransducerError::TooShort { len: 1, min: 2 })
+        );
+        assert_eq!(
+            UniversalTransducer::segment_and_process(&raw, &[0.0, 1.0]),
+            Err(TransducerError::LengthMismatch { raw: 5, times: 2 })
+        );
+        assert_eq!(
+            UniversalTransducer::segment_and_process(&raw, &[0.0, 1.0, 1.0, 2.0, 3.0]),
+            Err(TransducerError::NonMonotonicTime { index: 2 })
+        );
+        // Un premier instant NaN ne doit pas désactiver le contrôle de monotonie
+        assert_eq!(
+            UniversalTransducer::segment_and_process(&raw[..4], &[f64::NAN, 1.0, 2.0, 3.0]),
+            Err(TransducerError::NonFiniteTime { index: 0 })
+        );
+        assert_eq!(
+            UniversalTransducer::segment_and_process(&raw[..4], &[0.0, 1.0, f64::INFINITY, 3.0]),
+            Err(TransducerError::NonFiniteTime { index: 2 })
+        );
+
+        // En flux : l'instant est comparé au morceau précédent, et l'état est intact
+        let mut chunked = ChunkedTransducer::new(options);
+        chunked.push(&raw[..3], &[0.0, 1.0, 2.0]).unwrap();
+        assert_eq!(chunked.push(&raw[3..], &[1.5, 3.0]), Err(TransducerError::NonMonotonicTime { index: 3 }));
+        assert_eq!(
+            chunked.push(&[vec![1.0, 2.0]], &[3.0]),
+            Err(TransducerError::DimensionMismatch { index: 3, expected: 1, got: 2 })
+        );
+        assert_eq!(chunked.pending(), 3);
+        chunked.push(&raw[3..], &[3.0, 4.0]).unwrap();
+        assert_eq!(chunked.finish().len(), 1);
+
+        // Sélecteur incompatible avec le nombre de canaux du flux
+        let times = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let with_channel = |channel| TransducerOptions {
+            segmentation: SegmentationConfig { channel, ..SegmentationConfig::default() },
+            ..TransducerOptions::default()
+        };
+        assert_eq!(
+            UniversalTransducer::segment_and_process_with_options(&raw, &times, &with_channel(ChannelSelector::Channel(1))),
+            Err(TransducerError::ChannelOutOfRange { channel: 1, channels: 1 })
+        );
+        assert_eq!(
+            UniversalTransducer::segment_and_process_with_options(&raw, &times, &with_channel(ChannelSelector::ProjectionOnto(vec![1.0, 0.0]))),
+            Err(TransducerError::AxisLengthMismatch { axis: 2, channels: 1 })
+        );
+        let mut chunked = ChunkedTransducer::new(with_channel(ChannelSelector::Channel(2)));
+        assert_eq!(chunked.push(&raw[..2], &times[..2]), Err(TransducerError::ChannelOutOfRange { channel: 2, channels: 1 }));
+        assert_eq!(chunked.pending(), 0);
+    }
+
+    #[test]
+    fn test_segment_length_limits() {
+        // Une longue dérive monotone (0.1 s par échantillon) et un creux bref
+        let mut values: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        values.extend([98.0, 99.5]);
+        let raw: Vec<Vec<f64>> = values.iter().map(|&x| vec![x]).collect();
+        let times: Vec<f64> = (0..raw.len()).map(|i| i as f64 * 0.1).collect();
+        let with = |f: &dyn Fn(&mut SegmentationConfig)| {
+            let mut options = TransducerOptions::default();
+            f(&mut options.segmentation);
+            UniversalTransducer::segment_with_report(&raw, &times, &options).unwrap()
+        };
+
+        // Défaut : un segment géant, les deux segments de 2 points sont écartés
+        let plain = with(&|_| {});
+        assert_eq!(plain.segments.len(), 1);
+        assert_eq!(plain.discarded, 2);
+        assert!(with(&|s| s.min_points = 2).segments.len() == 3);
+
+        let by_points = with(&|s| s.max_points = Some(25));
+        assert!(by_points.segments.iter().all(|s| s.end_idx - s.start_idx < 25));
+        assert_eq!(by_points.segments.len(), 5);
+
+        let by_duration = with(&|s| s.max_duration = Some(3.0));
+        assert!(by_duration.segments.iter().all(|s| s.t_end - s.t_start <= 3.0 + 1e-9));
+        assert_eq!(by_duration.segments[0].end_idx, 30);
+
+        // Interaction : chaque morceau d'une coupe dépasse min_points
+        let both = with(&|s| { s.max_points = Some(40); s.min_points = 10; });
+        assert_eq!(both.segments.len(), 3);
+        assert!(both.segments.iter().all(|s| s.end_idx - s.start_idx + 1 >= 10));
+        for pair in both.segments.windows(2) {
+            assert_eq!(pair[0].end_idx, pair[1].start_idx);
+        }
+    }
+
+    #[test]
+    fn test_resample_linear_ramp_is_exact() {
+        let times = vec![0.0, 0.13, 0.31, 0.35, 0.62, 0.9, 1.05];
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![2.0 * t - 1.0, -t]).collect();
+
+        let (linear, grid) = UniversalTransducer::resample(&raw, &times, 0.1, InterpKind::Linear).unwrap();
+        assert_eq!(grid.len(), 11);
+        for (x, t) in linear.iter().zip(grid.iter()) {
+            assert!((x[0] - (2.0 * t - 1.0)).abs() < 1e-12 && (x[1] + t).abs() < 1e-12);
+        }
+
+        let (held, _) = UniversalTransducer::resample(&raw, &times, 0.1, InterpKind::Hold).unwrap();
+        assert_eq!(held[3], raw[1]);
+        assert_eq!(held[4], raw[3]);
+
+        for step in [0.0, -0.1, f64::INFINITY] {
+            assert_eq!(
+                UniversalTransducer::resample(&raw, &times, step, InterpKind::Linear),
+                Err(TransducerError::InvalidResamplingStep { step })
+            );
+        }
+        assert!(matches!(
+            UniversalTransducer::resample(&raw, &times, f64::NAN, InterpKind::Linear),
+            Err(TransducerError::InvalidResamplingStep { step }) if step.is_nan()
+        ));
+        assert_eq!(
+            UniversalTransducer::resample(&raw[1..], &times, 0.1, InterpKind::Linear),
+            Err(TransducerError::LengthMismatch { raw: 6, times: 7 })
+        );
+        assert_eq!(UniversalTransducer::resample(&[], &[], 0.1, InterpKind::Linear), Ok((vec![], vec![])));
+    }
+
+    #[test]
+    fn test_gap_forces_segment_boundary() {
+        // Rampe à 10 Hz interrompue par un trou de 2 s
+        let times: Vec<f64> = (0..30).map(|i| i as f64 * 0.1).chain((0..30).map(|i| 4.9 + i as f64 * 0.1)).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![*t]).collect();
+
+        let (resampled, grid) = UniversalTransducer::resample_with_gaps(&raw, &times, 0.05, InterpKind::Linear, Some(0.5)).unwrap();
+        assert!(!grid.iter().any(|t| *t > 2.9 + 1e-9 && *t < 4.9 - 1e-9));
+
+        let mut options = TransducerOptions::default();
+        assert_eq!(UniversalTransducer::segment_and_process_indexed(&resampled, &grid, &options).unwrap().len(), 1);
+        options.segmentation.gap_threshold = Some(0.5);
+        let segments = UniversalTransducer::segment_and_process_indexed(&resampled, &grid, &options).unwrap();
+        assert_eq!(segments.len(), 2);
+        assert!(segments[0].t_end <= 2.9 + 1e-9);
+        assert!((segments[1].t_start - 4.9).abs() < 1e-9);
+        assert_eq!(segments[0].end_idx + 1, segments[1].start_idx);
+    }
+
+    #[test]
+    fn test_batch_matches_streaming_transduction() {
+        // Le chemin batch (parallèle avec la feature `parallel`) doit reproduire
+        // exactement le chemin incrémental, y compris les découpes par magnitude
+        let times: Vec<f64> = (0..5000).map(|i| i as f64 * 0.01).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * 2.0).sin() * 1e30, (t * 5.0).cos()]).collect();
+        let options = TransducerOptions { max_term_magnitude: Some(1e80), ..TransducerOptions::default() };
+
+        let batch = UniversalTransducer::segment_with_report(&raw, &times, &options).unwrap();
+        let mut chunked = ChunkedTransducer::new(options);
+        let mut streamed = chunked.push(&raw, &times).unwrap();
+        streamed.extend(chunked.finish());
+
+        assert!(batch.segments.len() > 10);
+        assert_eq!(batch.segments, streamed);
+        assert_eq!(batch.discarded, chunked.discarded());
+    }
+
+    #[test]
+    fn test_process_1d_matches_nd_path() {
+        // Fixture : montée puis descente, deux segments de deux incréments (dt, dx).
+        // Valeurs attendues calculées indépendamment de `Signature` : pour un chemin
+        // linéaire par morceaux d1 puis d2, S^k = Σ_{a+b=k} d1^{⊗a}/a! ⊗ d2^{⊗b}/b!.
+        let raw = [0.0, 1.0, 3.0, 2.0, 0.5];
+        let times = [0.0, 0.5, 1.0, 1.5, 2.0];
+        let pieces = [([0.5, 1.0], [0.5, 2.0]), ([0.5, -1.0], [0.5, -1.5])];
+        let power = |d: [f64; 2], k: usize| -> Vec<f64> {
+            let factorial: f64 = (1..=k).map(|i| i as f64).product();
+            (0..1usize << k)
+                .map(|word| (0..k).map(|p| d[(word >> (k - 1 - p)) & 1]).product::<f64>() / factorial)
+                .collect()
+        };
+        let reference = |d1: [f64; 2], d2: [f64; 2], k: usize| -> Vec<f64> {
+            let mut level = vec![0.0; 1 << k];
+            for a in 0..=k {
+                let (left, right) = (power(d1, a), power(d2, k - a));
+                for (i, l) in left.iter().enumerate() {
+                    for (j, r) in right.iter().enumerate() {
+                        level[(i << (k - a)) | j] += l * r;
+                    }
+                }
+            }
+            level
+        };
+
+        let options = TransducerOptions::default();
+        let vectors = UniversalTransducer::process_1d(&raw, &times, &options).unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0].signature.levels[0], vec![1.0, 3.0]);
+        assert_eq!(vectors[1].signature.levels[0], vec![1.0, -2.5]);
+        // Niveau 2 développé à la main : chemin (temps, valeur), dimension 2
+        let expected_l2 = [[0.5, 1.75, 1.25, 4.5], [0.5, -1.375, -1.125, 3.125]];
+        for ((v, (d1, d2)), l2) in vectors.iter().zip(pieces).zip(expected_l2) {
+            assert_eq!(reference(d1, d2, 2), l2);
+            for k in 1..=options.depth {
+                let level = v.signature.level(k);
+                let expected = reference(d1, d2, k);
+                assert_eq!(level.len(), expected.len());
+                for (x, e) in level.iter().zip(&expected) {
+                    assert!((x - e).abs() < 1e-12, "level {}: {} vs {}", k, x, e);
+                }
+            }
+        }
+
+        // Le point d'entrée N-d partage le même chemin
+        let wrapped: Vec<Vec<f64>> = raw.iter().map(|&x| vec![x]).collect();
+        let nd = UniversalTransducer::segment_and_process_with_options(&wrapped, &times, &options).unwrap();
+        assert_eq!(vectors, nd);
+    }
+
+    #[test]
+    fn test_process_iter_streams_lazily() {
+        // 10^7 échantillons générés à la volée, jamais collectés
+        let n = 10_000_000usize;
+        let samples = (0..n).map(|i| {
+            let t = i as f64 * 1e-3;
+            (t, vec![(t * 50.0).sin()])
+        });
+        let options = TransducerOptions { depth: 2, ..TransducerOptions::default() };
+        let (mut count, mut increments) = (0usize, 0usize);
+        for v in UniversalTransducer::process_iter(samples, options) {
+            let v = v.unwrap();
+            increments += v.gradient.len();
+            count += 1;
+        }
+        // Une demi-période de 0.0628 s par segment, soit ~63 échantillons
+        assert!((count as f64 - n as f64 / 62.83).abs() < 10.0, "{}", count);
+        assert_eq!(increments, n - 1);
+
+        // Même découpe que le chemin batch ; une rupture d'ordre temporel est signalée
+        let times: Vec<f64> = (0..1000).map(|i| i as f64 * 0.01).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * 4.0).sin()]).collect();
+        let lazy: Vec<UniversalVector> = UniversalTransducer::process_iter(times.iter().copied().zip(raw.iter().cloned()), TransducerOptions::default())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(lazy, UniversalTransducer::segment_and_process(&raw, &times).unwrap());
+
+        let broken = vec![(0.0, vec![1.0]), (1.0, vec![2.0]), (0.5, vec![3.0])];
+        assert!(UniversalTransducer::process_iter(broken, TransducerOptions::default()).any(|r| r.is_err()));
+    }
+
+    #[test]
+    fn test_reliability_from_segment_stats() {
+        // Montée propre de 60 échantillons
+        let times: Vec<f64> = (0..60).map(|i| i as f64 * 0.01).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * 2.0).sin(), 2.0]).collect();
+        let clean = UniversalTransducer::segment_and_process_indexed(&raw, &times, &TransducerOptions::default()).unwrap();
+        assert_eq!(clean.len(), 1);
+        assert_eq!(clean[0].stats.samples, 60);
+        assert_eq!(clean[0].stats.amplitude[1], (2.0, 2.0));
+        assert!(clean[0].vector.metadata.reliability > 0.95, "{}", clean[0].vector.metadata.reliability);
+
+        // Trois points bruités, à peine au-dessus du seuil de bruit
+        let noisy_options = TransducerOptions {
+            segmentation: SegmentationConfig { noise_eps: NoiseEps::Fixed(0.05), ..SegmentationConfig::default() },
+            ..TransducerOptions::default()
+        };
+        let raw = vec![vec![0.0, 2.0], vec![0.06, 1.9], vec![0.12, 2.1]];
+        let noisy = UniversalTransducer::segment_and_process_indexed(&raw, &[0.0, 0.01, 0.02], &noisy_options).unwrap();
+        assert_eq!(noisy[0].stats.amplitude[0], (0.0, 0.12));
+        assert!(noisy[0].vector.metadata.reliability < 0.5, "{}", noisy[0].vector.metadata.reliability);
+
+        // Un scorer personnalisé remplace le défaut
+        #[derive(Debug)]
+        struct Constant;
+        impl ReliabilityScorer for Constant {
+            fn score(&self, _: &SegmentStats) -> f64 { 0.25 }
+        }
+        let custom = TransducerOptions { reliability: Arc::new(Constant), ..TransducerOptions::default() };
+        let vectors = UniversalTransducer::segment_and_process_with_options(&raw, &[0.0, 0.01, 0.02], &custom).unwrap();
+        assert_eq!(vectors[0].metadata.reliability, 0.25);
+
+        // Égalité : même scorer partagé, ou non
+        assert_eq!(TransducerOptions::default(), TransducerOptions::default());
+        assert_eq!(custom.clone(), custom);
+        assert_ne!(custom, TransducerOptions::default());
+        assert_ne!(TransducerOptions { depth: 4, ..TransducerOptions::default() }, TransducerOptions::default());
+    }
+
+    #[test]
+    fn test_derivative_signature_option() {
+        // Parabole x = t² sur une montée, échantillonnage irrégulier
+        let times: Vec<f64> = (0..40).map(|i| i as f64 * 0.05 + if i % 2 == 1 { 0.01 } else { 0.0 }).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![t * t]).collect();
+        let run = |signature_of: SignatureOf| {
+            let options = TransducerOptions { signature_of, ..TransducerOptions::default() };
+            let segments = UniversalTransducer::segment_and_process_indexed(&raw, &times, &options).unwrap();
+            assert_eq!(segments.len(), 1);
+            assert_eq!((segments[0].start_idx, segments[0].end_idx), (0, raw.len() - 1));
+            segments[0].vector.signature.clone()
+        };
+
+        let (path, derivative, both) = (run(SignatureOf::Path), run(SignatureOf::Derivative), run(SignatureOf::Both));
+        // Aux milieux des intervalles la vitesse vaut exactement 2t, même irrégulière :
+        // Δv = 2 (dernier milieu − premier milieu), alors que Δx = t_fin²
+        let span = (times[38] + times[39]) / 2.0 - (times[0] + times[1]) / 2.0;
+        assert!((derivative.levels[0][1] - 2.0 * span).abs() < 1e-9, "{}", derivative.levels[0][1]);
+        assert!((path.levels[0][1] - times[39] * times[39]).abs() < 1e-12);
+        assert!(path.distance(&derivative) > 0.5);
+
+        assert_eq!(both.dim, 3);
+        assert_eq!(both.levels[0][2], derivative.levels[0][1]);
+        assert_eq!(TransducerOptions { signature_of: SignatureOf::Both, lead_lag: true, ..TransducerOptions::default() }.signature_dim(2), 9);
+    }
+
+    #[test]
+    fn test_prefilter_removes_chatter() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(21);
+        // 5 périodes de 200 échantillons depuis zéro : un quart, 9 demi-périodes, un quart
+        let times: Vec<f64> = (0..1000).map(|i| i as f64 * 0.01).collect();
+        let raw: Vec<Vec<f64>> = times.iter()
+            .map(|t| vec![(t * std::f64::consts::PI).sin() + rng.gen_range(-0.01..0.01)])
+            .collect();
+        let run = |prefilter: PreFilter| {
+            let options = TransducerOptions {
+                segmentation: SegmentationConfig { prefilter, noise_eps: NoiseEps::Fixed(0.01), ..SegmentationConfig::default() },
+                ..TransducerOptions::default()
+            };
+            let batch = UniversalTransducer::segment_and_process_indexed(&raw, &times, &options).unwrap();
+
+            // Même découpe par morceaux de 7 : l'état du filtre traverse les morceaux
+            let mut chunked = ChunkedTransducer::new(options);
+            let mut streamed = Vec::new();
+            for (x, t) in raw.chunks(7).zip(times.chunks(7)) {
+                streamed.extend(chunked.push(x, t).unwrap());
+            }
+            streamed.extend(chunked.finish());
+            assert_eq!(batch, streamed);
+            batch
+        };
+
+        assert!(run(PreFilter::None).len() > 11);
+        let smoothed = run(PreFilter::MovingAverage { window: 5 });
+        assert_eq!(smoothed.len(), 11);
+        // Les signatures restent celles des données brutes
+        let first = &smoothed[0];
+        let dx = raw[first.end_idx][0] - raw[first.start_idx][0];
+        assert!((first.vector.signature.levels[0][1] - dx).abs() < 1e-12);
+        assert_eq!(run(PreFilter::Ema { alpha: 0.3 }).len(), 11);
+    }
+
+    #[test]
+    fn test_process_channels_segments_independently() {
+        // Canal 0 : 3 changements de direction ; canal 1 : 7
+        let times: Vec<f64> = (0..400).map(|i| i as f64 * 0.01).collect();
+        let raw: Vec<Vec<f64>> = times.iter().map(|t| vec![(t * 2.0).sin(), (t * 6.0).cos()]).collect();
+        let per_channel = UniversalTransducer::process_channels(&raw, &times, &TransducerOptions::default()).unwrap();
+
+        assert_eq!(per_channel.len(), 2);
+        assert_eq!(per_channel[0].len(), 4);
+        assert_eq!(per_channel[1].len(), 8);
+        for (c, vectors) in per_channel.iter().enumerate() {
+            assert!(vectors.iter().all(|v| v.metadata.channel == Some(c) && v.signature.dim == 2));
+        }
+        let joint = UniversalTransducer::segment_and_process(&raw, &times).unwrap();
+        assert!(joint.iter().all(|v| v.metadata.channel.is_none()));
+
+        assert!(UniversalTransducer::process_channels(&[], &[], &TransducerOptions::default()).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::f64;
+use std::ops::{Add, AddAssign, Mul, Sub};
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, PartialEq, Clone, Serialize,  Deserialize)]
@@ -11,9 +12,119 @@ pub struct UniversalVector {
 #[derive(Debug, PartialEq, Clone,  Serialize,  Deserialize)]
 pub struct Signature {
     pub dim: usize,
-    pub level1: Vec<f64>,
-    pub level2: Vec<Vec<f64>>,
-    pub level3: Vec<Vec<Vec<f64>>>,
+    /// Profondeur de troncature (niveau maximal calculé), entre 2 et 4.
+    pub depth: usize,
+    // Tenseurs aplatis (row-major) : levels[k-1] contient les d^k termes du niveau k,
+    // ex. niveau 2 en [i*d + j], niveau 3 en [(i*d + j)*d + k]
+    pub levels: Vec<Vec<f64>>,
+}
+
+/// Profondeur de troncature par défaut (niveaux 1 à 3).
+pub const DEFAULT_DEPTH: usize = 3;
+pub const MIN_DEPTH: usize = 2;
+pub const MAX_DEPTH: usize = 4;
+
+/// Quelques descripteurs de forme bon marché tirés des niveaux 1 et 2 de la signature,
+/// pour la voie réflexe qui n'a pas le temps d'une comparaison complète.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct SignatureFeatures {
+    /// Norme du déplacement net sur les canaux spatiaux (hors canal temps 0).
+    pub total_displacement: f64,
+    /// Échelle homogène du chemin : moyenne des (k!·||S_k||)^{1/k}, linéaire en la taille du chemin.
+    pub path_length_proxy: f64,
+    /// Aire de Lévy totale : sqrt(Σ_{i<j} A_ij²), nulle pour un segment rectiligne.
+    pub levy_area: f64,
+    /// Aire rapportée au carré de l'échelle : sans dimension, mesure la courbure.
+    pub curvature_ratio: f64,
+}
+
+/// Écarts maximaux tolérés par `UniversalVector::quick_match`, descripteur par descripteur.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct FeatureTolerances {
+    pub total_displacement: f64,
+    pub path_length_proxy: f64,
+    pub levy_area: f64,
+    pub curvature_ratio: f64,
+}
+
+/// Métrique de comparaison de signatures (ou de leurs aplatissements).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum SignatureMetric {
+    /// Euclidienne, sensible à l'échelle (câblage de topologie).
+    #[default]
+    L2,
+    /// Manhattan, plus robuste aux valeurs aberrantes (détection d'anomalies).
+    L1,
+    /// 1 - cosinus, invariante à l'échelle (dictionnaire).
+    Cosine,
+    /// Euclidienne entre versions normalisées à la sphère unité.
+    NormalizedL2,
+}
+
+impl SignatureMetric {
+    /// Distance entre deux vecteurs aplatis de même longueur.
+    pub fn between(&self, a: &[f64], b: &[f64]) -> f64 {
+        let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        match self {
+            SignatureMetric::L2 => a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt(),
+            SignatureMetric::L1 => a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum(),
+            SignatureMetric::Cosine => {
+                let (na, nb) = (norm(a), norm(b));
+                if na == 0.0 || nb == 0.0 {
+                    return if na == nb { 0.0 } else { 1.0 };
+                }
+                let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+                (1.0 - dot / (na * nb)).max(0.0)
+            }
+            SignatureMetric::NormalizedL2 => {
+                let (na, nb) = (norm(a), norm(b));
+                let (sa, sb) = (if na > 0.0 { 1.0 / na } else { 0.0 }, if nb > 0.0 { 1.0 / nb } else { 0.0 });
+                a.iter().zip(b).map(|(x, y)| (x * sa - y * sb).powi(2)).sum::<f64>().sqrt()
+            }
+        }
+    }
+
+    /// Variante f32 pour les vecteurs de poids des PNUs.
+    pub fn between_f32(&self, a: &[f32], b: &[f32]) -> f32 {
+        let widen = |v: &[f32]| v.iter().map(|&x| x as f64).collect::<Vec<f64>>();
+        self.between(&widen(a), &widen(b)) as f32
+    }
+}
+
+/// Combinaison des fiabilités de deux vecteurs pour atténuer leur résonance.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ReliabilityPolicy {
+    /// Le maillon le plus faible décide : min(r_a, r_b).
+    Min,
+    /// Les incertitudes se cumulent : r_a · r_b.
+    Product,
+    /// Fiabilité ignorée (score brut).
+    Ignore,
+}
+
+impl ReliabilityPolicy {
+    pub fn combine(&self, a: f64, b: f64) -> f64 {
+        let (a, b) = (a.clamp(0.0, 1.0), b.clamp(0.0, 1.0));
+        match self {
+            ReliabilityPolicy::Min => a.min(b),
+            ReliabilityPolicy::Product => a * b,
+            ReliabilityPolicy::Ignore => 1.0,
+        }
+    }
+}
+
+/// Pondération par niveau pour comparer deux signatures.
+/// Les termes de niveau k décroissent comme |x|^k / k!, donc une distance plate
+/// est dominée par le niveau 1 et ignore la forme (niveaux supérieurs).
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum LevelWeighting {
+    /// Distance euclidienne plate (comportement historique).
+    Uniform,
+    /// Chaque terme de niveau k est multiplié par k! avant comparaison.
+    Factorial,
+    /// Poids appliqués aux carrés des écarts de chaque niveau (weights[k-1] pour le niveau k,
+    /// 1.0 pour les niveaux non renseignés).
+    Custom(Vec<f64>),
 }
 
 #[derive(Debug, PartialEq, Clone,  Serialize,  Deserialize)]
@@ -27,6 +138,8 @@ pub enum Modality {
     Vision,
     Audio,
     Memory,
+    /// Canal défini par l'utilisateur.
+    Custom(u16),
 }
 
 #[derive(Debug, PartialEq, Clone,  Serialize,  Deserialize)]
@@ -34,6 +147,9 @@ pub struct Metadata {
     pub timestamp: f64,
     pub modality: Modality,
     pub reliability: f64,
+    /// Canal source quand le vecteur signe un seul canal (`process_channels`).
+    #[serde(default)]
+    pub channel: Option<usize>,
 }
 
 impl UniversalVector {
@@ -53,6 +169,29 @@ impl UniversalVector {
         (- (dist * dist) / (2.0 * sigma * sigma)).exp()
     }
 
+    /// Comme `resonance_structural`, avec une pondération des niveaux de la signature.
+    pub fn resonance_structural_weighted(&self, other: &UniversalVector, sigma: f64, weighting: &LevelWeighting) -> f64 {
+        let dist = self.signature.level_weighted_distance(&other.signature, weighting);
+        (- (dist * dist) / (2.0 * sigma * sigma)).exp()
+    }
+
+    /// `resonance_structural` sans sigma explicite : sigma est la moyenne géométrique
+    /// des deux magnitudes, ce qui rend le score insensible à la longueur des chemins.
+    pub fn resonance_structural_auto(&self, other: &UniversalVector) -> f64 {
+        let sigma = (self.signature.magnitude() * other.signature.magnitude()).sqrt();
+        if sigma <= f64::EPSILON {
+            // Deux signatures nulles sont identiques ; une seule nulle ne résonne pas
+            return if self.signature.distance(&other.signature) <= f64::EPSILON { 1.0 } else { 0.0 };
+        }
+        self.resonance_structural(other, sigma)
+    }
+
+    /// `resonance_structural` avec une métrique de distance au choix.
+    pub fn resonance_structural_with_metric(&self, other: &UniversalVector, sigma: f64, metric: SignatureMetric) -> f64 {
+        let dist = self.signature.distance_with(&other.signature, metric);
+        (- (dist * dist) / (2.0 * sigma * sigma)).exp()
+    }
+
     pub fn resonance_full(&self, other: &UniversalVector, sensitivity: f64) -> f64 {
         let dir = self.resonance_directional(other);
         let struc = self.resonance_structural(other, sensitivity);
@@ -60,6 +199,39 @@ impl UniversalVector {
         (dir_clamped * struc).sqrt()
     }
 
+    /// `resonance_full` atténuée par la fiabilité combinée des deux métadonnées.
+    pub fn resonance_weighted(&self, other: &UniversalVector, sensitivity: f64, policy: ReliabilityPolicy) -> f64 {
+        let reliability = policy.combine(self.metadata.reliability, other.metadata.reliability);
+        self.resonance_full(other, sensitivity) * reliability
+    }
+
+    /// Meilleur candidat (index, score) selon `resonance_weighted`.
+    /// À score égal, l'exemplaire le plus fiable l'emporte, puis le premier rencontré.
+    pub fn best_match(&self, candidates: &[UniversalVector], sensitivity: f64, policy: ReliabilityPolicy) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        for (i, c) in candidates.iter().enumerate() {
+            let score = self.resonance_weighted(c, sensitivity, policy);
+            let better = match best {
+                None => true,
+                Some((j, s)) => score > s
+                    || (score == s && c.metadata.reliability > candidates[j].metadata.reliability),
+            };
+            if better {
+                best = Some((i, score));
+            }
+        }
+        best
+    }
+
+    /// Comparaison O(1) sur les descripteurs de forme (voie réflexe).
+    pub fn quick_match(&self, other: &UniversalVector, tolerances: &FeatureTolerances) -> bool {
+        let (a, b) = (self.signature.features(), other.signature.features());
+        (a.total_displacement - b.total_displacement).abs() <= tolerances.total_displacement
+            && (a.path_length_proxy - b.path_length_proxy).abs() <= tolerances.path_length_proxy
+            && (a.levy_area - b.levy_area).abs() <= tolerances.levy_area
+            && (a.curvature_ratio - b.curvature_ratio).abs() <= tolerances.curvature_ratio
+    }
+
     pub fn normalize(&mut self) {
         self.signature.normalize();
     }
@@ -70,8 +242,35 @@ impl UniversalVector {
         copy
     }
 
+    /// Distance insensible à l'échelle et à la vitesse du geste : seuls les canaux
+    /// spatiaux de chaque signature sont gardés (`without_time`, invariante par
+    /// reparamétrisation), puis normalisés par la longueur spatiale du chemin
+    /// (`Gradient::spatial_length`).
+    pub fn shape_distance(&self, other: &UniversalVector) -> f64 {
+        let shape = |v: &UniversalVector| {
+            let mut sig = v.signature.without_time();
+            sig.normalize_by_length(v.gradient.spatial_length());
+            sig
+        };
+        shape(self).distance(&shape(other))
+    }
+
     pub fn blend(&mut self, target: &UniversalVector, alpha: f64) {
+        self.blend_with(target, alpha, BlendPolicy::default());
+    }
+
+    /// Comme `blend`, en choisissant le sort du gradient (voir `BlendPolicy`).
+    pub fn blend_with(&mut self, target: &UniversalVector, alpha: f64, policy: BlendPolicy) {
         self.signature.blend(&target.signature, alpha);
+        match policy {
+            BlendPolicy::SignatureOnly => {}
+            BlendPolicy::Full { gradient_alpha } => {
+                self.gradient = Gradient::lerp(&self.gradient, &target.gradient, gradient_alpha);
+            }
+            BlendPolicy::Resampled { n } => {
+                self.gradient = Gradient::lerp(&self.gradient.resample(n), &target.gradient.resample(n), alpha);
+            }
+        }
     }
 
     pub fn zero() -> Self {
@@ -84,125 +283,623 @@ impl UniversalVector {
     }
 }
 
-impl Signature {
-    pub fn from_segment(dt: f64, dX: &[f64]) -> Self {
-        // Pour inclure le temps comme une dimension, on l'ajoute souvent au vecteur
-        let mut d = vec![dt];
-        d.extend_from_slice(dX);
-        let actual_dim = d.len();
+/// Traitement du gradient lors d'un `UniversalVector::blend_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BlendPolicy {
+    /// Seule la signature est mélangée (comportement historique de `blend`).
+    #[default]
+    SignatureOnly,
+    /// Le gradient est aussi interpolé, avec son propre coefficient ; si les longueurs
+    /// diffèrent, les deux sont d'abord rééchantillonnés à la plus longue.
+    Full { gradient_alpha: f64 },
+    /// Les deux gradients sont rééchantillonnés à `n` incréments puis interpolés avec alpha.
+    Resampled { n: usize },
+}
+
+/// Moyenne incrémentale de signatures (espérance de la signature d'une famille de chemins).
+/// Moyenne courante par composante, m += (x - m) / n, numériquement stable.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct SignatureAccumulator {
+    count: u64,
+    mean: Option<Signature>,
+}
+
+impl SignatureAccumulator {
+    pub fn new() -> Self {
+        SignatureAccumulator::default()
+    }
 
-        let mut sig = Signature {
-            dim: actual_dim, // Fix: Added missing field `dim`
-            level1: d.clone(), // Fix: Changed from tuple (dt, dX[0]) to Vec<f64>
-            level2: vec![vec![0.0; actual_dim]; actual_dim],
-            level3: vec![vec![vec![0.0; actual_dim]; actual_dim]; actual_dim],
+    pub fn push(&mut self, sig: &Signature) {
+        self.count += 1;
+        match &mut self.mean {
+            None => self.mean = Some(sig.clone()),
+            Some(mean) => {
+                assert_eq!(mean.dim, sig.dim, "Dimensions must match to accumulate signatures");
+                let n = self.count as f64;
+                for (m, x) in mean.components_mut().zip(sig.components()) {
+                    *m += (x - *m) / n;
+                }
+            }
+        }
+    }
+
+    pub fn mean(&self) -> Option<&Signature> {
+        self.mean.as_ref()
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// Sigma appris sur un lot de vecteurs (heuristique de la médiane des distances
+/// deux à deux), à réutiliser pour toutes les requêtes d'un même dictionnaire.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct SigmaEstimator {
+    pub sigma: f64,
+}
+
+impl SigmaEstimator {
+    /// Retourne None s'il y a moins de deux vecteurs ou si tous sont identiques.
+    pub fn fit(vectors: &[UniversalVector]) -> Option<Self> {
+        let mut distances = Vec::with_capacity(vectors.len() * vectors.len().saturating_sub(1) / 2);
+        for (i, a) in vectors.iter().enumerate() {
+            for b in &vectors[i + 1..] {
+                distances.push(a.signature.distance(&b.signature));
+            }
+        }
+        if distances.is_empty() {
+            return None;
+        }
+        distances.sort_by(|a, b| a.total_cmp(b));
+        let mid = distances.len() / 2;
+        let median = if distances.len() % 2 == 0 {
+            (distances[mid - 1] + distances[mid]) / 2.0
+        } else {
+            distances[mid]
         };
+        (median > f64::EPSILON).then_some(SigmaEstimator { sigma: median })
+    }
+
+    pub fn resonance(&self, a: &UniversalVector, b: &UniversalVector) -> f64 {
+        a.resonance_structural(b, self.sigma)
+    }
+}
+
+impl Signature {
+    /// Index aplati d'un terme de niveau 2 : (i, j) -> i*d + j
+    #[inline]
+    pub fn idx2(&self, i: usize, j: usize) -> usize {
+        i * self.dim + j
+    }
 
-        // Niveau 2
-        for i in 0..actual_dim {
-            for j in 0..actual_dim {
-                sig.level2[i][j] = d[i] * d[j] / 2.0;
+    /// Index aplati d'un terme de niveau 3 : (i, j, k) -> (i*d + j)*d + k
+    #[inline]
+    pub fn idx3(&self, i: usize, j: usize, k: usize) -> usize {
+        (i * self.dim + j) * self.dim + k
+    }
+
+    /// Termes du niveau `k` (1-indexé). Vide si `k` dépasse la profondeur.
+    pub fn level(&self, k: usize) -> &[f64] {
+        match k {
+            1..=MAX_DEPTH if k <= self.depth => &self.levels[k - 1],
+            _ => &[],
+        }
+    }
+
+    /// Index aplati du terme (i1, ..., ik) au niveau k = indices.len().
+    fn flat_index(&self, level: usize, indices: &[usize]) -> Option<usize> {
+        if level == 0 || level > self.depth || indices.len() != level {
+            return None;
+        }
+        indices.iter().try_fold(0, |acc, &i| (i < self.dim).then_some(acc * self.dim + i))
+    }
+
+    /// Terme S^{(i1..ik)} du niveau k ; None si le niveau ou un indice est hors bornes.
+    pub fn get(&self, level: usize, indices: &[usize]) -> Option<f64> {
+        let idx = self.flat_index(level, indices)?;
+        Some(self.levels[level - 1][idx])
+    }
+
+    /// Modifie le terme S^{(i1..ik)} ; retourne false si hors bornes.
+    pub fn set(&mut self, level: usize, indices: &[usize], value: f64) -> bool {
+        match self.flat_index(level, indices) {
+            Some(idx) => {
+                self.levels[level - 1][idx] = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Signature du chemin privé du canal temps 0 : les termes dont aucun indice
+    /// n'est 0, en dimension dim - 1. Ne dépend plus de la vitesse de parcours.
+    pub fn without_time(&self) -> Signature {
+        if self.dim == 0 {
+            return self.clone();
+        }
+        let mut spatial = Signature::zero_with_depth(self.dim - 1, self.depth);
+        for (level, indices, value) in self.iter_terms() {
+            if indices.contains(&0) {
+                continue;
+            }
+            let shifted: Vec<usize> = indices.iter().map(|i| i - 1).collect();
+            spatial.set(level, &shifted, value);
+        }
+        spatial
+    }
+
+    /// Aire de Lévy entre les canaux i et j : ½ (S^{ij} - S^{ji}).
+    /// Antisymétrique, nulle pour un segment rectiligne. None si un canal est
+    /// hors bornes, comme `get`.
+    pub fn levy_area(&self, i: usize, j: usize) -> Option<f64> {
+        Some(0.5 * (self.get(2, &[i, j])? - self.get(2, &[j, i])?))
+    }
+
+    pub fn features(&self) -> SignatureFeatures {
+        let total_displacement = self.levels[0].iter().skip(1).map(|x| x * x).sum::<f64>().sqrt();
+
+        let mut factorial = 1.0;
+        let path_length_proxy = self.levels.iter().enumerate()
+            .map(|(k, level)| {
+                factorial *= (k + 1) as f64;
+                let norm = level.iter().map(|x| x * x).sum::<f64>().sqrt();
+                (factorial * norm).powf(1.0 / (k + 1) as f64)
+            })
+            .sum::<f64>() / self.depth as f64;
+
+        let mut area_sq = 0.0;
+        for i in 0..self.dim {
+            for j in (i + 1)..self.dim {
+                area_sq += self.levy_area(i, j).unwrap_or(0.0).powi(2);
             }
         }
+        let levy_area = area_sq.sqrt();
+
+        let curvature_ratio = if path_length_proxy > f64::EPSILON {
+            levy_area / (path_length_proxy * path_length_proxy)
+        } else {
+            0.0
+        };
+
+        SignatureFeatures { total_displacement, path_length_proxy, levy_area, curvature_ratio }
+    }
 
-        // Niveau 3
-        for i in 0..actual_dim {
-            for j in 0..actual_dim {
-                for k in 0..actual_dim {
-                    sig.level3[i][j][k] = d[i] * d[j] * d[k] / 6.0;
+    /// Tous les termes sous la forme (niveau, indices, valeur), dans l'ordre aplati.
+    pub fn iter_terms(&self) -> impl Iterator<Item = (usize, Vec<usize>, f64)> + '_ {
+        let d = self.dim;
+        self.levels.iter().enumerate().flat_map(move |(k, level)| {
+            level.iter().enumerate().map(move |(idx, &v)| {
+                let mut indices = vec![0; k + 1];
+                let mut rest = idx;
+                for slot in indices.iter_mut().rev() {
+                    *slot = rest % d;
+                    rest /= d;
                 }
+                (k + 1, indices, v)
+            })
+        })
+    }
+
+    pub fn from_segment(dt: f64, dx: &[f64]) -> Self {
+        Self::from_segment_with_depth(dt, dx, DEFAULT_DEPTH)
+    }
+
+    /// Signature d'un segment linéaire : le niveau k vaut d^{⊗k} / k!
+    pub fn from_segment_with_depth(dt: f64, dx: &[f64], depth: usize) -> Self {
+        // Pour inclure le temps comme une dimension, on l'ajoute souvent au vecteur
+        let mut d = vec![dt];
+        d.extend_from_slice(dx);
+        Self::from_increment_with_depth(&d, depth)
+    }
+
+    /// Signature d'un incrément linéaire `d` pris tel quel, sans canal de temps ajouté
+    /// (dimension d.len()). `from_segment(dt, dx)` vaut `from_increment(&[dt, dx...])`.
+    pub fn from_increment(d: &[f64]) -> Self {
+        Self::from_increment_with_depth(d, DEFAULT_DEPTH)
+    }
+
+    pub fn from_increment_with_depth(d: &[f64], depth: usize) -> Self {
+        let actual_dim = d.len();
+
+        let mut sig = Signature::zero_with_depth(actual_dim, depth);
+
+        // Produits tensoriels successifs d⊗...⊗d, divisés par k! à la fin
+        let mut product = d.to_vec();
+        let mut factorial = 1.0;
+        sig.levels[0].copy_from_slice(d);
+        for k in 2..=depth {
+            factorial *= k as f64;
+            product = product.iter()
+                .flat_map(|p| d.iter().map(move |x| p * x))
+                .collect();
+            for (term, p) in sig.levels[k - 1].iter_mut().zip(product.iter()) {
+                *term = p / factorial;
             }
         }
         sig
     }
 
+    /// Identité de Chen : S(a * b) = S(a) ⊗ S(b).
+    ///
+    /// Si les profondeurs diffèrent, le résultat est tronqué à la plus petite
+    /// des deux (les niveaux supérieurs ne peuvent pas être calculés exactement).
     pub fn combine(&self, other: &Signature) -> Signature {
         assert_eq!(self.dim, other.dim, "Dimensions must match to combine signatures");
+        debug_assert!(self.is_finite() && other.is_finite(), "Non-finite signature in combine");
         let d = self.dim;
-        let mut res = Signature::zero(d);
-
-        // NIVEAU 1
-        for i in 0..d {
-            res.level1[i] = self.level1[i] + other.level1[i];
-        }
+        let depth = self.depth.min(other.depth);
+        let mut res = Signature::zero_with_depth(d, depth);
 
-        // NIVEAU 2
-        for i in 0..d {
-            for j in 0..d {
-                res.level2[i][j] = self.level2[i][j] 
-                                 + other.level2[i][j] 
-                                 + (self.level1[i] * other.level1[j]);
+        for k in 1..=depth {
+            let out = &mut res.levels[k - 1];
+            for (r, (a, b)) in out.iter_mut().zip(self.levels[k - 1].iter().zip(other.levels[k - 1].iter())) {
+                *r = a + b;
+            }
+            // Termes croisés : niveau i de self ⊗ niveau (k - i) de other
+            for i in 1..k {
+                let left = &self.levels[i - 1];
+                let right = &other.levels[k - i - 1];
+                let stride = right.len();
+                for (a_idx, a) in left.iter().enumerate() {
+                    let base = a_idx * stride;
+                    for (b_idx, b) in right.iter().enumerate() {
+                        out[base + b_idx] += a * b;
+                    }
+                }
             }
         }
 
-        // NIVEAU 3
-        for i in 0..d {
-            for j in 0..d {
-                for k in 0..d {
-                    res.level3[i][j][k] = self.level3[i][j][k] 
-                                        + other.level3[i][j][k]
-                                        + (self.level1[i] * other.level2[j][k])
-                                        + (self.level2[i][j] * other.level1[k]);
+        res
+    }
+
+    /// `combine` en place (self ← self ⊗ other), sans allouer de signature résultat.
+    /// Le niveau k dépend des niveaux 1..k−1 d'avant la mise à jour : ils sont donc
+    /// mis à jour du plus haut au plus bas. Résultat identique bit à bit à `combine`.
+    pub fn combine_assign(&mut self, other: &Signature) {
+        assert_eq!(self.dim, other.dim, "Dimensions must match to combine signatures");
+        debug_assert!(self.is_finite() && other.is_finite(), "Non-finite signature in combine");
+        let depth = self.depth.min(other.depth);
+        self.levels.truncate(depth);
+        self.depth = depth;
+
+        for k in (1..=depth).rev() {
+            let (lower, upper) = self.levels.split_at_mut(k - 1);
+            let out = &mut upper[0];
+            for (r, b) in out.iter_mut().zip(other.levels[k - 1].iter()) {
+                *r += b;
+            }
+            for i in 1..k {
+                let left = &lower[i - 1];
+                let right = &other.levels[k - i - 1];
+                let stride = right.len();
+                for (a_idx, a) in left.iter().enumerate() {
+                    let base = a_idx * stride;
+                    for (b_idx, b) in right.iter().enumerate() {
+                        out[base + b_idx] += a * b;
+                    }
                 }
             }
         }
+    }
 
-        res
+    /// Signature d'un chemin 1D linéaire par morceaux donné par ses points (t, x).
+    pub fn from_points(path: &[(f64, f64)]) -> Signature {
+        path.windows(2).fold(Signature::zero(2), |acc, w| {
+            acc.combine(&Signature::from_segment(w[1].0 - w[0].0, &[w[1].1 - w[0].1]))
+        })
     }
 
-    pub fn dot(&self, other: &Self) -> f64 {
-        let mut sum = 0.0;
-        let d = self.dim;
-        for i in 0..d {
-            sum += self.level1[i] * other.level1[i];
-            for j in 0..d {
-                sum += self.level2[i][j] * other.level2[i][j];
-                for k in 0..d {
-                    sum += self.level3[i][j][k] * other.level3[i][j][k];
+    /// Écart maximal entre la signature du chemin calculée segment par segment et
+    /// celle obtenue en coupant le chemin en chaque point intérieur puis en recombinant
+    /// les deux moitiés par `combine`. Doit rester à l'ordre de l'erreur d'arrondi.
+    pub fn check_consistency(path: &[(f64, f64)]) -> f64 {
+        if path.len() < 3 {
+            return 0.0;
+        }
+        let whole = Signature::from_points(path);
+        (1..path.len() - 1)
+            .map(|m| {
+                let split = Signature::from_points(&path[..=m]).combine(&Signature::from_points(&path[m..]));
+                whole.distance(&split)
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Vrai si aucun terme n'est NaN ou infini.
+    pub fn is_finite(&self) -> bool {
+        self.components().all(|x| x.is_finite())
+    }
+
+    /// Empreinte déterministe (FNV-1a) des termes arrondis à la grille `tolerance`.
+    /// Deux signatures différant de moins de tolerance/2 par terme ont en général la même
+    /// empreinte (sauf si un terme chevauche une frontière de la grille) ; utilisable comme
+    /// clé de déduplication, pour une comparaison exacte voir `approx_eq`.
+    pub fn fingerprint(&self, tolerance: f64) -> u64 {
+        assert!(tolerance > 0.0, "Fingerprint tolerance must be positive");
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let cells = [self.dim as i64, self.depth as i64].into_iter()
+            .chain(self.components().map(|x| (x / tolerance).round() as i64));
+        cells.fold(FNV_OFFSET, |hash, cell| {
+            cell.to_le_bytes().iter().fold(hash, |h, &b| (h ^ b as u64).wrapping_mul(FNV_PRIME))
+        })
+    }
+
+    /// Même forme et |a - b| ≤ tol pour chaque terme.
+    pub fn approx_eq(&self, other: &Signature, tol: f64) -> bool {
+        self.dim == other.dim
+            && self.depth == other.depth
+            && self.components().zip(other.components()).all(|(a, b)| (a - b).abs() <= tol)
+    }
+
+    /// Plus grand |terme| (norme infinie). NaN si un terme est NaN.
+    pub fn max_abs(&self) -> f64 {
+        self.components().fold(0.0, |m, x| if x.is_nan() || m.is_nan() { f64::NAN } else { m.max(x.abs()) })
+    }
+
+    /// Aplatissement canonique : tous les termes, niveau 1 d'abord, puis 2, 3...
+    pub fn flatten(&self) -> Vec<f64> {
+        self.components().copied().collect()
+    }
+
+    /// Signature moyenne d'une collection ; None si elle est vide.
+    pub fn mean(signatures: &[Signature]) -> Option<Signature> {
+        let mut acc = SignatureAccumulator::new();
+        for sig in signatures {
+            acc.push(sig);
+        }
+        acc.mean
+    }
+
+    /// Élément inverse pour la loi de groupe de Chen : `s.combine(&s.inverse())` est
+    /// la signature identité (zéro) aux erreurs d'arrondi près.
+    ///
+    /// Calculé par la série tronquée (1 + x)^{-1} = 1 - x + x² - ..., valable même
+    /// pour une signature qui n'est plus exactement "group-like" (après `blend` par exemple).
+    /// Permet de retirer un préfixe d'une signature accumulée : S(b) = S(a)^{-1} ⊗ S(a*b).
+    pub fn inverse(&self) -> Signature {
+        let mut inv = self.clone();
+        inv.scale(-1.0);
+
+        let mut power = self.levels.clone();
+        for n in 2..=self.depth {
+            power = Self::tensor_product(self.dim, &power, &self.levels, self.depth);
+            let sign = if n % 2 == 0 { 1.0 } else { -1.0 };
+            for (out, p) in inv.levels.iter_mut().zip(power.iter()) {
+                for (o, v) in out.iter_mut().zip(p.iter()) {
+                    *o += sign * v;
                 }
             }
         }
-        sum
+        inv
     }
 
-    pub fn distance(&self, other: &Self) -> f64 {
-        let mut sum_sq = 0.0;
+    /// Signature du chemin parcouru à l'envers : le terme (i1..ik) devient
+    /// (-1)^k · S^{(ik..i1)}. Pour une signature de chemin, coïncide avec `inverse()`.
+    pub fn reverse(&self) -> Signature {
         let d = self.dim;
-        for i in 0..d {
-            sum_sq += (self.level1[i] - other.level1[i]).powi(2);
-            for j in 0..d {
-                sum_sq += (self.level2[i][j] - other.level2[i][j]).powi(2);
-                for k in 0..d {
-                    sum_sq += (self.level3[i][j][k] - other.level3[i][j][k]).powi(2);
+        let mut rev = Signature::zero_with_depth(d, self.depth);
+        for (k, (out, level)) in rev.levels.iter_mut().zip(self.levels.iter()).enumerate() {
+            let sign = if (k + 1) % 2 == 0 { 1.0 } else { -1.0 };
+            for (idx, v) in level.iter().enumerate() {
+                // Renverse les k+1 "chiffres" en base d de l'index aplati
+                let mut rest = idx;
+                let mut reversed = 0;
+                for _ in 0..=k {
+                    reversed = reversed * d + rest % d;
+                    rest /= d;
                 }
+                out[reversed] = sign * v;
             }
         }
-        sum_sq.sqrt()
+        rev
     }
 
-    pub fn scale(&mut self, s: f64) {
-        for i in 0..self.dim {
-            self.level1[i] *= s;
-            for j in 0..self.dim {
-                self.level2[i][j] *= s;
-                for k in 0..self.dim {
-                    self.level3[i][j][k] *= s;
+    /// Produit tensoriel tronqué de deux éléments sans terme scalaire :
+    /// (a ⊗ b)_k = Σ_{i=1}^{k-1} a_i ⊗ b_{k-i}
+    fn tensor_product(dim: usize, a: &[Vec<f64>], b: &[Vec<f64>], depth: usize) -> Vec<Vec<f64>> {
+        let mut res: Vec<Vec<f64>> = (1..=depth).map(|k| vec![0.0; dim.pow(k as u32)]).collect();
+        for k in 2..=depth {
+            for i in 1..k {
+                let left = &a[i - 1];
+                let right = &b[k - i - 1];
+                let stride = right.len();
+                for (a_idx, x) in left.iter().enumerate() {
+                    for (b_idx, y) in right.iter().enumerate() {
+                        res[k - 1][a_idx * stride + b_idx] += x * y;
+                    }
                 }
             }
         }
+        res
     }
 
-    pub fn blend(&mut self, target: &Signature, alpha: f64) {
-        let a = alpha.clamp(0.0, 1.0);
-        for i in 0..self.dim {
-            self.level1[i] += (target.level1[i] - self.level1[i]) * a;
-            for j in 0..self.dim {
-                self.level2[i][j] += (target.level2[i][j] - self.level2[i][j]) * a;
-                for k in 0..self.dim {
-                    self.level3[i][j][k] += (target.level3[i][j][k] - self.level3[i][j][k]) * a;
+    /// Parcourt les termes en profondeur (i, puis (i, j), puis (i, j, k)...),
+    /// l'ordre historique de sommation de `dot` et `distance`.
+    fn fold_terms<F: FnMut(f64, f64) -> f64>(&self, other: &Self, f: &mut F) -> f64 {
+        fn visit<F: FnMut(f64, f64) -> f64>(
+            a: &Signature,
+            b: &Signature,
+            depth: usize,
+            level: usize,
+            index: usize,
+            acc: &mut f64,
+            f: &mut F,
+        ) {
+            *acc += f(a.levels[level - 1][index], b.levels[level - 1][index]);
+            if level < depth {
+                for c in 0..a.dim {
+                    visit(a, b, depth, level + 1, index * a.dim + c, acc, f);
                 }
             }
         }
+
+        let depth = self.depth.min(other.depth);
+        let mut acc = 0.0;
+        for i in 0..self.dim {
+            visit(self, other, depth, 1, i, &mut acc, f);
+        }
+        acc
+    }
+
+    fn components_mut(&mut self) -> impl Iterator<Item = &mut f64> {
+        self.levels.iter_mut().flat_map(|l| l.iter_mut())
+    }
+
+    fn components(&self) -> impl Iterator<Item = &f64> {
+        self.levels.iter().flat_map(|l| l.iter())
+    }
+
+    pub fn dot(&self, other: &Self) -> f64 {
+        self.fold_terms(other, &mut |a, b| a * b)
+    }
+
+    pub fn distance(&self, other: &Self) -> f64 {
+        debug_assert!(self.is_finite() && other.is_finite(), "Non-finite signature in distance");
+        self.fold_terms(other, &mut |a, b| (a - b).powi(2)).sqrt()
+    }
+
+    pub fn distance_with(&self, other: &Signature, metric: SignatureMetric) -> f64 {
+        match metric {
+            SignatureMetric::L2 => self.distance(other),
+            _ => metric.between(&self.flatten(), &other.flatten()),
+        }
+    }
+
+    /// Noyau de signature tronqué : K(S, T) = 1 + Σ_k (k!)² <S_k, T_k>.
+    /// Le terme constant correspond au niveau 0 ; le facteur (k!)² compense la
+    /// décroissance factorielle des niveaux, comme `normalized_by_level_distance`.
+    /// Produit scalaire de caractéristiques : le noyau est symétrique et semi-défini positif.
+    pub fn kernel(&self, other: &Signature) -> f64 {
+        let mut factorial = 1.0;
+        1.0 + self.levels.iter()
+            .zip(other.levels.iter())
+            .enumerate()
+            .map(|(k, (a, b))| {
+                factorial *= (k + 1) as f64;
+                factorial * factorial * a.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<f64>()
+            })
+            .sum::<f64>()
+    }
+
+    /// Distance pondérée : sqrt(Σ_k weights[k-1] · ||S_k - T_k||²).
+    /// Un niveau sans poids (ou au-delà de la profondeur commune) compte pour 1.0 / est ignoré.
+    pub fn weighted_distance(&self, other: &Signature, weights: &[f64]) -> f64 {
+        self.levels.iter()
+            .zip(other.levels.iter())
+            .enumerate()
+            .map(|(k, (a, b))| {
+                let w = weights.get(k).copied().unwrap_or(1.0);
+                w * a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum::<f64>()
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Distance où chaque niveau k est ramené à l'échelle du niveau 1
+    /// en compensant sa norme factorielle 1/k! (termes multipliés par k!).
+    pub fn normalized_by_level_distance(&self, other: &Signature) -> f64 {
+        let depth = self.depth.min(other.depth);
+        let weights: Vec<f64> = (1..=depth)
+            .scan(1.0, |fact, k| {
+                *fact *= k as f64;
+                Some(*fact * *fact)
+            })
+            .collect();
+        self.weighted_distance(other, &weights)
+    }
+
+    pub fn level_weighted_distance(&self, other: &Signature, weighting: &LevelWeighting) -> f64 {
+        match weighting {
+            LevelWeighting::Uniform => self.distance(other),
+            LevelWeighting::Factorial => self.normalized_by_level_distance(other),
+            LevelWeighting::Custom(weights) => self.weighted_distance(other, weights),
+        }
+    }
+
+    /// Distances de `self` à chaque signature de `others` (mêmes valeurs que `distance`).
+    pub fn distances_to(&self, others: &[Signature]) -> Vec<f64> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            others.par_iter().map(|o| self.distance(o)).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            others.iter().map(|o| self.distance(o)).collect()
+        }
+    }
+
+    /// Résonances directionnelles (cosinus) de `self` contre chaque signature de `others`.
+    /// La magnitude de `self` n'est calculée qu'une fois ; mêmes valeurs que
+    /// `UniversalVector::resonance_directional`.
+    pub fn resonances_to(&self, others: &[Signature]) -> Vec<f64> {
+        let mag_self = self.magnitude();
+        let resonance = |o: &Signature| {
+            let mag_other = o.magnitude();
+            if mag_self == 0.0 || mag_other == 0.0 {
+                return 0.0;
+            }
+            self.dot(o) / (mag_self * mag_other)
+        };
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            others.par_iter().map(resonance).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            others.iter().map(resonance).collect()
+        }
+    }
+
+    /// Divise le niveau k par length^k. Les termes de niveau k d'un chemin dilaté d'un
+    /// facteur λ valent λ^k fois ceux du chemin d'origine : après normalisation par la
+    /// longueur de chaque chemin, il ne reste que la forme. Sans effet si length ≤ 0.
+    pub fn normalize_by_length(&mut self, length: f64) {
+        if length <= f64::EPSILON || length.is_nan() { return; }
+        let mut factor = 1.0;
+        for level in self.levels.iter_mut() {
+            factor /= length;
+            for x in level.iter_mut() {
+                *x *= factor;
+            }
+        }
+    }
+
+    pub fn scale(&mut self, s: f64) {
+        for x in self.components_mut() {
+            *x *= s;
+        }
+    }
+
+    pub fn blend(&mut self, target: &Signature, alpha: f64) {
+        *self = Signature::lerp(self, target, alpha);
+    }
+
+    /// Interpolation linéaire terme à terme : a + (b - a)·t, avec t borné à [0, 1].
+    /// Arithmétique d'espace vectoriel, à ne pas confondre avec `combine` (produit de Chen).
+    pub fn lerp(a: &Signature, b: &Signature, t: f64) -> Signature {
+        let t = t.clamp(0.0, 1.0);
+        a + &(&(b - a) * t)
+    }
+
+    /// Applique `f` terme à terme sur les niveaux communs (profondeur minimale).
+    fn zip_with<F: Fn(f64, f64) -> f64>(&self, other: &Signature, f: F) -> Signature {
+        assert_eq!(self.dim, other.dim, "Dimensions must match for signature arithmetic");
+        let depth = self.depth.min(other.depth);
+        Signature {
+            dim: self.dim,
+            depth,
+            levels: self.levels.iter()
+                .zip(other.levels.iter())
+                .take(depth)
+                .map(|(a, b)| a.iter().zip(b.iter()).map(|(x, y)| f(*x, *y)).collect())
+                .collect(),
+        }
     }
 
     pub fn magnitude(&self) -> f64 {
@@ -211,7 +908,7 @@ impl Signature {
 
     pub fn normalize(&mut self) {
         let mag = self.magnitude();
-        if mag > std::f64::EPSILON {
+        if mag > f64::EPSILON {
             let inv_mag = 1.0 / mag;
             self.scale(inv_mag);
         }
@@ -226,11 +923,76 @@ impl Signature {
     }
 
     pub fn zero(dim: usize) -> Self {
+        Self::zero_with_depth(dim, DEFAULT_DEPTH)
+    }
+
+    pub fn zero_with_depth(dim: usize, depth: usize) -> Self {
+        assert!(
+            (MIN_DEPTH..=MAX_DEPTH).contains(&depth),
+            "Signature depth must be in {}..={}", MIN_DEPTH, MAX_DEPTH
+        );
         Signature {
             dim,
-            level1: vec![0.0; dim],
-            level2: vec![vec![0.0; dim]; dim],
-            level3: vec![vec![vec![0.0; dim]; dim]; dim],
+            depth,
+            levels: (1..=depth).map(|k| vec![0.0; dim.pow(k as u32)]).collect(),
+        }
+    }
+}
+
+// Arithmétique d'espace vectoriel (terme à terme sur tous les niveaux).
+// Ce n'est PAS la loi de groupe des chemins : la concaténation de chemins est `combine`.
+// Utile pour les moyennes, résidus et dérives de prototypes.
+
+impl Add<&Signature> for &Signature {
+    type Output = Signature;
+    fn add(self, rhs: &Signature) -> Signature {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl Add for Signature {
+    type Output = Signature;
+    fn add(self, rhs: Signature) -> Signature {
+        &self + &rhs
+    }
+}
+
+impl Sub<&Signature> for &Signature {
+    type Output = Signature;
+    fn sub(self, rhs: &Signature) -> Signature {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl Sub for Signature {
+    type Output = Signature;
+    fn sub(self, rhs: Signature) -> Signature {
+        &self - &rhs
+    }
+}
+
+impl Mul<f64> for &Signature {
+    type Output = Signature;
+    fn mul(self, rhs: f64) -> Signature {
+        let mut res = self.clone();
+        res.scale(rhs);
+        res
+    }
+}
+
+impl Mul<f64> for Signature {
+    type Output = Signature;
+    fn mul(mut self, rhs: f64) -> Signature {
+        self.scale(rhs);
+        self
+    }
+}
+
+impl AddAssign<&Signature> for Signature {
+    fn add_assign(&mut self, rhs: &Signature) {
+        assert_eq!(self.dim, rhs.dim, "Dimensions must match for signature arithmetic");
+        for (a, b) in self.components_mut().zip(rhs.components()) {
+            *a += b;
         }
     }
 }
@@ -250,23 +1012,812 @@ impl Gradient {
             .sqrt()
     }
 
+    /// Longueur du chemin augmenté : Σ √(dt² + |dx|²).
+    pub fn length(&self) -> f64 {
+        self.data.iter()
+            .map(|(dt, dx)| (dt.powi(2) + dx.iter().map(|x| x.powi(2)).sum::<f64>()).sqrt())
+            .sum()
+    }
+
+    /// Longueur du chemin spatial seul : Σ |dx|.
+    pub fn spatial_length(&self) -> f64 {
+        self.data.iter()
+            .map(|(_, dx)| dx.iter().map(|x| x.powi(2)).sum::<f64>().sqrt())
+            .sum()
+    }
+
+    /// Nombre d'incréments.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(f64, Vec<f64>)> {
+        self.data.iter()
+    }
+
+    pub fn as_slice(&self) -> &[(f64, Vec<f64>)] {
+        &self.data
+    }
+
+    /// Durée totale (somme des dt).
+    pub fn duration(&self) -> f64 {
+        self.data.iter().map(|(dt, _)| dt).sum()
+    }
+
+    /// Déplacement net par canal (somme des dx).
+    pub fn total_displacement(&self) -> Vec<f64> {
+        let channels = self.data.first().map_or(0, |(_, dx)| dx.len());
+        let mut total = vec![0.0; channels];
+        for (_, dx) in &self.data {
+            for (t, x) in total.iter_mut().zip(dx.iter()) {
+                *t += x;
+            }
+        }
+        total
+    }
+
+    /// Vitesse moyenne par canal (déplacement / durée) ; zéros si la durée est nulle.
+    pub fn mean_velocity(&self) -> Vec<f64> {
+        let duration = self.duration();
+        let total = self.total_displacement();
+        if duration.abs() <= f64::EPSILON {
+            return vec![0.0; total.len()];
+        }
+        total.into_iter().map(|x| x / duration).collect()
+    }
+
+    /// Plus grande norme de vitesse |dx|/dt parmi les incréments de dt > 0.
+    pub fn max_velocity(&self) -> f64 {
+        self.data.iter()
+            .filter(|(dt, _)| *dt > 0.0)
+            .map(|(dt, dx)| dx.iter().map(|x| x * x).sum::<f64>().sqrt() / dt)
+            .fold(0.0, f64::max)
+    }
+
+    /// Rééchantillonne le chemin en `n` incréments de durée égale, par interpolation
+    /// linéaire du chemin cumulé. `n = 0` donne un gradient vide, `n = 1` un seul
+    /// incrément couvrant toute la durée. Sans durée positive, le découpage se fait
+    /// uniformément sur l'index des incréments.
+    pub fn resample(&self, n: usize) -> Gradient {
+        if n == 0 || self.data.is_empty() {
+            return Gradient::zero();
+        }
+        let channels = self.data[0].1.len();
+
+        // Points cumulés du chemin : (t, x) avec un paramètre de parcours
+        let duration = self.duration();
+        let by_time = duration > f64::EPSILON;
+        let mut knots = Vec::with_capacity(self.data.len() + 1);
+        let (mut t, mut x, mut u) = (0.0, vec![0.0; channels], 0.0);
+        knots.push((u, t, x.clone()));
+        for (dt, dx) in &self.data {
+            t += dt;
+            for (xi, d) in x.iter_mut().zip(dx.iter()) {
+                *xi += d;
+            }
+            u += if by_time { *dt } else { 1.0 };
+            knots.push((u, t, x.clone()));
+        }
+        let span = u;
+
+        let point_at = |target: f64| -> (f64, Vec<f64>) {
+            let seg = knots.partition_point(|(ku, _, _)| *ku < target).clamp(1, knots.len() - 1);
+            let (u0, t0, x0) = &knots[seg - 1];
+            let (u1, t1, x1) = &knots[seg];
+            let w = if u1 - u0 > 0.0 { (target - u0) / (u1 - u0) } else { 1.0 };
+            let xt = x0.iter().zip(x1.iter()).map(|(a, b)| a + (b - a) * w).collect();
+            (t0 + (t1 - t0) * w, xt)
+        };
+
+        let mut previous = (0.0, vec![0.0; channels]);
+        let mut data = Vec::with_capacity(n);
+        for j in 1..=n {
+            let current = if j == n {
+                (t, x.clone())
+            } else {
+                point_at(span * j as f64 / n as f64)
+            };
+            let dx = current.1.iter().zip(previous.1.iter()).map(|(a, b)| a - b).collect();
+            data.push((current.0 - previous.0, dx));
+            previous = current;
+        }
+        Gradient { data }
+    }
+
+    /// Interpolation incrément par incrément : a·(1 - t) + b·t, t borné à [0, 1]
+    /// (exacte aux extrémités).
+    /// Longueurs différentes : rééchantillonnage des deux à la plus longue. Un gradient
+    /// vide n'apporte aucune information : l'autre est retourné tel quel.
+    pub fn lerp(a: &Gradient, b: &Gradient, t: f64) -> Gradient {
+        if a.is_empty() { return b.clone(); }
+        if b.is_empty() { return a.clone(); }
+        if a.len() != b.len() {
+            let n = a.len().max(b.len());
+            return Gradient::lerp(&a.resample(n), &b.resample(n), t);
+        }
+        let t = t.clamp(0.0, 1.0);
+        let data = a.data.iter()
+            .zip(b.data.iter())
+            .map(|((dta, dxa), (dtb, dxb))| {
+                let dx = dxa.iter().zip(dxb.iter()).map(|(x, y)| x * (1.0 - t) + y * t).collect();
+                (dta * (1.0 - t) + dtb * t, dx)
+            })
+            .collect();
+        Gradient { data }
+    }
+
     pub fn zero() -> Self {
         Gradient { data: Vec::new() }
     }
 }
 
 impl Metadata {
+    pub fn new(timestamp: f64, modality: Modality, reliability: f64) -> Self {
+        Metadata { timestamp, modality, reliability, channel: None }
+    }
+
+    pub fn builder() -> MetadataBuilder {
+        MetadataBuilder::default()
+    }
+
     pub fn zero() -> Self {
         Metadata {
             timestamp: 0.0,
             modality: Modality::Vision,
             reliability: 1.0,
+            channel: None,
         }
     }
 }
 
+/// Construction progressive des métadonnées.
+/// Valeurs par défaut : timestamp 0.0, `Modality::Sensor`, fiabilité 1.0, sans canal.
+#[derive(Debug, Clone)]
+pub struct MetadataBuilder {
+    timestamp: f64,
+    modality: Modality,
+    reliability: f64,
+    channel: Option<usize>,
+}
+
+impl Default for MetadataBuilder {
+    fn default() -> Self {
+        MetadataBuilder { timestamp: 0.0, modality: Modality::Sensor, reliability: 1.0, channel: None }
+    }
+}
+
+impl MetadataBuilder {
+    pub fn timestamp(mut self, timestamp: f64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn modality(mut self, modality: Modality) -> Self {
+        self.modality = modality;
+        self
+    }
+
+    pub fn reliability(mut self, reliability: f64) -> Self {
+        self.reliability = reliability;
+        self
+    }
+
+    pub fn channel(mut self, channel: usize) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    pub fn build(self) -> Metadata {
+        Metadata { channel: self.channel, ..Metadata::new(self.timestamp, self.modality, self.reliability) }
+    }
+}
+
 
 
 /*Changez en utilisant la signature standard (aplatie) combinée à une projection aléatoire (Random Projection).
 Remplacez le "trop d'allocations" par linearized signature.
-Utilisez une signature linearisée sélective. Si input d est < 10 n'utilisez aucune compression sinon Utilisez la RP.*/
\ No newline at end of file
+Utilisez une signature linearisée sélective. Si input d est < 10 n'utilisez aucune compression sinon Utilisez la RP.*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Implémentation de référence (tenseurs imbriqués) avant l'aplatissement,
+    // conservée ici pour vérifier que le cas d=2 est reproduit à l'identique.
+    struct NestedSignature {
+        level1: Vec<f64>,
+        level2: Vec<Vec<f64>>,
+        level3: Vec<Vec<Vec<f64>>>,
+    }
+
+    fn nested_from_segment(dt: f64, dx: &[f64]) -> NestedSignature {
+        let mut d = vec![dt];
+        d.extend_from_slice(dx);
+        let n = d.len();
+        let mut s = NestedSignature {
+            level1: d.clone(),
+            level2: vec![vec![0.0; n]; n],
+            level3: vec![vec![vec![0.0; n]; n]; n],
+        };
+        for i in 0..n {
+            for j in 0..n {
+                s.level2[i][j] = d[i] * d[j] / 2.0;
+                for k in 0..n {
+                    s.level3[i][j][k] = d[i] * d[j] * d[k] / 6.0;
+                }
+            }
+        }
+        s
+    }
+
+    fn nested_combine(a: &NestedSignature, b: &NestedSignature) -> NestedSignature {
+        let n = a.level1.len();
+        let mut r = NestedSignature {
+            level1: vec![0.0; n],
+            level2: vec![vec![0.0; n]; n],
+            level3: vec![vec![vec![0.0; n]; n]; n],
+        };
+        for i in 0..n {
+            r.level1[i] = a.level1[i] + b.level1[i];
+        }
+        for i in 0..n {
+            for j in 0..n {
+                r.level2[i][j] = a.level2[i][j] + b.level2[i][j] + (a.level1[i] * b.level1[j]);
+            }
+        }
+        for i in 0..n {
+            for j in 0..n {
+                for k in 0..n {
+                    r.level3[i][j][k] = a.level3[i][j][k]
+                        + b.level3[i][j][k]
+                        + (a.level1[i] * b.level2[j][k])
+                        + (a.level2[i][j] * b.level1[k]);
+                }
+            }
+        }
+        r
+    }
+
+    fn path_signature(increments: &[(f64, Vec<f64>)]) -> Signature {
+        let dim = increments[0].1.len() + 1;
+        increments.iter().fold(Signature::zero(dim), |acc, (dt, dx)| {
+            acc.combine(&Signature::from_segment(*dt, dx))
+        })
+    }
+
+    #[test]
+    fn test_flat_signature_matches_nested_2d() {
+        let increments = vec![(0.1, vec![0.5]), (0.2, vec![-0.3]), (0.15, vec![0.8]), (0.05, vec![-1.2])];
+
+        let flat = path_signature(&increments);
+        let mut nested = NestedSignature {
+            level1: vec![0.0; 2],
+            level2: vec![vec![0.0; 2]; 2],
+            level3: vec![vec![vec![0.0; 2]; 2]; 2],
+        };
+        for (dt, dx) in &increments {
+            nested = nested_combine(&nested, &nested_from_segment(*dt, dx));
+        }
+
+        assert_eq!(flat.dim, 2);
+        for i in 0..2 {
+            assert_eq!(flat.level(1)[i], nested.level1[i]);
+            for j in 0..2 {
+                assert_eq!(flat.level(2)[flat.idx2(i, j)], nested.level2[i][j]);
+                for k in 0..2 {
+                    assert_eq!(flat.level(3)[flat.idx3(i, j, k)], nested.level3[i][j][k]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chen_identity_three_channels() {
+        let a = vec![(0.1, vec![0.3, -0.2, 0.7]), (0.2, vec![-0.4, 0.1, 0.2])];
+        let b = vec![(0.1, vec![0.9, 0.5, -0.1]), (0.3, vec![0.0, -0.6, 0.4])];
+        let whole: Vec<_> = a.iter().chain(b.iter()).cloned().collect();
+
+        let s_whole = path_signature(&whole);
+        let s_split = path_signature(&a).combine(&path_signature(&b));
+        assert_eq!(s_whole.dim, 4);
+        assert!(s_whole.distance(&s_split) < 1e-12);
+
+        // Identité de shuffle : S^{ij} + S^{ji} = S^i * S^j
+        for i in 0..4 {
+            for j in 0..4 {
+                let l2 = s_whole.level(2);
+                let sym = l2[s_whole.idx2(i, j)] + l2[s_whole.idx2(j, i)];
+                assert!((sym - s_whole.level(1)[i] * s_whole.level(1)[j]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_depth_two_and_four() {
+        let incs = vec![(0.1, vec![0.4, -0.2]), (0.2, vec![-0.3, 0.5]), (0.1, vec![0.2, 0.1])];
+        for depth in MIN_DEPTH..=MAX_DEPTH {
+            let sig = incs.iter().fold(Signature::zero_with_depth(3, depth), |acc, (dt, dx)| {
+                acc.combine(&Signature::from_segment_with_depth(*dt, dx, depth))
+            });
+            assert_eq!(sig.levels.len(), depth);
+            assert_eq!(sig.level(depth).len(), 3usize.pow(depth as u32));
+            assert!(sig.level(depth + 1).is_empty());
+
+            // Les niveaux communs ne dépendent pas de la profondeur de troncature
+            let reference = path_signature(&incs);
+            for k in 1..=depth.min(DEFAULT_DEPTH) {
+                assert_eq!(sig.level(k), reference.level(k));
+            }
+        }
+    }
+
+    #[test]
+    fn test_depth_four_chen_identity() {
+        let a: Vec<_> = vec![(0.1, vec![0.3]), (0.2, vec![-0.7])];
+        let b: Vec<_> = vec![(0.3, vec![0.5]), (0.1, vec![0.2])];
+        let sig = |incs: &[(f64, Vec<f64>)]| incs.iter().fold(Signature::zero_with_depth(2, 4), |acc, (dt, dx)| {
+            acc.combine(&Signature::from_segment_with_depth(*dt, dx, 4))
+        });
+        let whole: Vec<_> = a.iter().chain(b.iter()).cloned().collect();
+        let left = sig(&a).combine(&sig(&b));
+        assert!(sig(&whole).distance(&left) < 1e-12);
+
+        // Pour un segment unique, le niveau 4 vaut d^{⊗4} / 4!
+        let seg = Signature::from_segment_with_depth(0.5, &[2.0], 4);
+        assert!((seg.level(4)[15] - 16.0 / 24.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_combine_mixed_depth_truncates() {
+        let deep = Signature::from_segment_with_depth(0.1, &[0.4], 4);
+        let shallow = Signature::from_segment_with_depth(0.2, &[-0.3], 2);
+        let res = deep.combine(&shallow);
+        assert_eq!(res.depth, 2);
+        assert_eq!(res.levels.len(), 2);
+        assert_eq!(shallow.combine(&deep).depth, 2);
+        // La distance ne compare que les niveaux communs
+        let truncated = deep.combine(&Signature::zero_with_depth(2, 2));
+        assert_eq!(truncated.depth, 2);
+        assert_eq!(deep.distance(&truncated), 0.0);
+    }
+
+    fn random_increments(n: usize, channels: usize, seed: u64) -> Vec<(f64, Vec<f64>)> {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| (rng.gen_range(0.01..0.5), (0..channels).map(|_| rng.gen_range(-1.0..1.0)).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_combine_assign_matches_combine() {
+        for depth in MIN_DEPTH..=MAX_DEPTH {
+            for seed in 0..10 {
+                let mut in_place = Signature::zero_with_depth(3, depth);
+                let mut fresh = Signature::zero_with_depth(3, depth);
+                for (dt, dx) in random_increments(20, 2, seed) {
+                    let step = Signature::from_segment_with_depth(dt, &dx, depth);
+                    fresh = fresh.combine(&step);
+                    in_place.combine_assign(&step);
+                }
+                assert_eq!(in_place, fresh);
+            }
+        }
+        let mut deep = Signature::from_segment_with_depth(0.1, &[0.4], 4);
+        let shallow = Signature::from_segment_with_depth(0.2, &[-0.3], 2);
+        let expected = deep.combine(&shallow);
+        deep.combine_assign(&shallow);
+        assert_eq!(deep, expected);
+    }
+
+    #[test]
+    fn test_inverse_cancels_suffix() {
+        for depth in MIN_DEPTH..=MAX_DEPTH {
+            let sig = |incs: &[(f64, Vec<f64>)]| incs.iter().fold(Signature::zero_with_depth(3, depth), |acc, (dt, dx)| {
+                acc.combine(&Signature::from_segment_with_depth(*dt, dx, depth))
+            });
+            let a = sig(&random_increments(5, 2, 1));
+            let b = sig(&random_increments(4, 2, 2));
+
+            let restored = a.combine(&b).combine(&b.inverse());
+            for k in 1..=depth {
+                for (x, y) in restored.level(k).iter().zip(a.level(k).iter()) {
+                    assert!((x - y).abs() < 1e-10, "level {} mismatch: {} vs {}", k, x, y);
+                }
+            }
+            assert!(b.combine(&b.inverse()).magnitude() < 1e-10);
+            assert!(b.inverse().combine(&b).magnitude() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_reverse_is_reversed_path() {
+        let incs = random_increments(6, 2, 3);
+        let forward = path_signature(&incs);
+        let reversed_incs: Vec<_> = incs.iter().rev()
+            .map(|(dt, dx)| (-dt, dx.iter().map(|x| -x).collect::<Vec<f64>>()))
+            .collect();
+        let backward = path_signature(&reversed_incs);
+
+        assert!(forward.reverse().distance(&backward) < 1e-10);
+        assert!(forward.reverse().distance(&forward.inverse()) < 1e-10);
+    }
+
+    #[test]
+    fn test_batch_matches_scalar() {
+        let query = path_signature(&random_increments(8, 2, 10));
+        let mut others: Vec<Signature> = (0..20).map(|i| path_signature(&random_increments(8, 2, 100 + i))).collect();
+        others.push(Signature::zero(3));
+
+        let distances = query.distances_to(&others);
+        let resonances = query.resonances_to(&others);
+        let uv = |s: &Signature| UniversalVector { signature: s.clone(), gradient: Gradient::zero(), metadata: Metadata::zero() };
+
+        for (i, other) in others.iter().enumerate() {
+            assert_eq!(distances[i], query.distance(other));
+            assert_eq!(resonances[i], uv(&query).resonance_directional(&uv(other)));
+        }
+        assert_eq!(resonances[20], 0.0);
+    }
+
+    #[test]
+    fn test_level_weighting_separates_level3() {
+        let a = path_signature(&[(0.5, vec![1.0]), (0.5, vec![-0.5]), (0.5, vec![0.8])]);
+        // Même chemin à un terme d'aire de niveau 3 près
+        let mut b = a.clone();
+        let (i, j, k) = (0, 1, 0);
+        let idx = b.idx3(i, j, k);
+        b.levels[2][idx] += 0.01;
+
+        let plain = a.distance(&b);
+        let factorial = a.normalized_by_level_distance(&b);
+        assert!(plain / a.magnitude() < 0.01);
+        assert!((factorial - 6.0 * plain).abs() < 1e-12);
+        assert_eq!(a.weighted_distance(&b, &[1.0, 1.0, 1.0]), plain);
+        assert!((a.weighted_distance(&b, &[1.0, 1.0, 100.0]) - 10.0 * plain).abs() < 1e-12);
+
+        let (ua, ub) = (
+            UniversalVector { signature: a, gradient: Gradient::zero(), metadata: Metadata::zero() },
+            UniversalVector { signature: b, gradient: Gradient::zero(), metadata: Metadata::zero() },
+        );
+        let sigma = 0.05;
+        assert!(ua.resonance_structural_weighted(&ub, sigma, &LevelWeighting::Factorial)
+            < ua.resonance_structural_weighted(&ub, sigma, &LevelWeighting::Uniform));
+        assert_eq!(ua.resonance_structural_weighted(&ub, sigma, &LevelWeighting::Uniform), ua.resonance_structural(&ub, sigma));
+    }
+
+    #[test]
+    fn test_vector_arithmetic_is_not_chen_product() {
+        let a = path_signature(&random_increments(4, 2, 20));
+        let b = path_signature(&random_increments(4, 2, 21));
+
+        // Niveau 1 : somme et concaténation coïncident ; niveau 2 : le terme croisé les sépare
+        let sum = &a + &b;
+        let chen = a.combine(&b);
+        assert_eq!(sum.level(1), chen.level(1));
+        assert!(sum.distance(&chen) > 1e-3);
+
+        assert!((&(&a + &b) - &b).distance(&a) < 1e-12);
+        assert!((&a * 2.0).distance(&(&a + &a)) < 1e-12);
+        let mut acc = a.clone();
+        acc += &b;
+        assert_eq!(acc, sum);
+    }
+
+    #[test]
+    fn test_lerp_matches_blend() {
+        let a = path_signature(&random_increments(4, 2, 22));
+        let b = path_signature(&random_increments(4, 2, 23));
+
+        let mut blended = a.clone();
+        blended.blend(&b, 0.3);
+        assert_eq!(blended, Signature::lerp(&a, &b, 0.3));
+        assert_eq!(Signature::lerp(&a, &b, 0.0), a);
+        assert!(Signature::lerp(&a, &b, 1.0).distance(&b) < 1e-12);
+        // t est borné comme l'alpha de blend
+        assert_eq!(Signature::lerp(&a, &b, 2.0), Signature::lerp(&a, &b, 1.0));
+    }
+
+    fn vector_of(incs: &[(f64, Vec<f64>)]) -> UniversalVector {
+        UniversalVector { signature: path_signature(incs), gradient: Gradient::update(incs), metadata: Metadata::zero() }
+    }
+
+    #[test]
+    fn test_auto_sigma_self_resonance() {
+        let v = vector_of(&random_increments(6, 2, 30));
+        assert_eq!(v.resonance_structural_auto(&v), 1.0);
+        assert_eq!(UniversalVector::zero().resonance_structural_auto(&UniversalVector::zero()), 1.0);
+
+        let long = vector_of(&random_increments(60, 2, 31));
+        assert_eq!(long.resonance_structural_auto(&long), 1.0);
+    }
+
+    #[test]
+    fn test_sigma_estimator_ranking() {
+        // Requête et candidats de plus en plus déformés, magnitudes comparables
+        let base = random_increments(6, 1, 32);
+        let query = vector_of(&base);
+        let candidates: Vec<UniversalVector> = (0..6)
+            .map(|n| {
+                let bent: Vec<(f64, Vec<f64>)> = base.iter().enumerate()
+                    .map(|(i, (dt, dx))| (*dt, vec![dx[0] + if i % 2 == 0 { 0.1 } else { -0.1 } * n as f64]))
+                    .collect();
+                vector_of(&bent)
+            })
+            .collect();
+
+        let estimator = SigmaEstimator::fit(&candidates).unwrap();
+        assert!(estimator.sigma > 0.0);
+        assert!(SigmaEstimator::fit(&candidates[..1]).is_none());
+
+        let rank = |score: &dyn Fn(&UniversalVector) -> f64| {
+            let mut idx: Vec<usize> = (0..candidates.len()).collect();
+            idx.sort_by(|&a, &b| score(&candidates[b]).total_cmp(&score(&candidates[a])));
+            idx
+        };
+        let manual = rank(&|c| query.resonance_structural(c, 0.5));
+        assert_eq!(manual, (0..6).collect::<Vec<_>>());
+        assert_eq!(rank(&|c| query.resonance_structural_auto(c)), manual);
+        assert_eq!(rank(&|c| estimator.resonance(&query, c)), manual);
+    }
+
+    #[test]
+    fn test_reliability_breaks_ties() {
+        let incs = random_increments(6, 2, 40);
+        let query = vector_of(&incs);
+        let mut noisy = vector_of(&incs);
+        noisy.metadata.reliability = 0.3;
+        let clean = vector_of(&incs);
+
+        assert_eq!(ReliabilityPolicy::Min.combine(0.3, 0.8), 0.3);
+        assert!((ReliabilityPolicy::Product.combine(0.5, 0.8) - 0.4).abs() < 1e-12);
+        assert_eq!(ReliabilityPolicy::Ignore.combine(0.1, 0.2), 1.0);
+
+        let candidates = vec![noisy.clone(), clean.clone()];
+        assert_eq!(query.best_match(&candidates, 1.0, ReliabilityPolicy::Min).unwrap().0, 1);
+        // Même sans atténuation, l'égalité est tranchée en faveur de l'exemplaire fiable
+        let (idx, score) = query.best_match(&candidates, 1.0, ReliabilityPolicy::Ignore).unwrap();
+        assert_eq!(idx, 1);
+        assert_eq!(score, query.resonance_full(&clean, 1.0));
+
+        assert!((query.resonance_weighted(&noisy, 1.0, ReliabilityPolicy::Min) - 0.3 * query.resonance_full(&noisy, 1.0)).abs() < 1e-12);
+        assert!(query.best_match(&[], 1.0, ReliabilityPolicy::Min).is_none());
+    }
+
+    #[test]
+    fn test_signature_mean_edge_cases() {
+        assert!(Signature::mean(&[]).is_none());
+
+        let a = path_signature(&random_increments(5, 2, 50));
+        assert_eq!(Signature::mean(std::slice::from_ref(&a)).unwrap(), a);
+        assert_eq!(Signature::mean(&vec![a.clone(); 17]).unwrap(), a);
+
+        let b = path_signature(&random_increments(5, 2, 51));
+        let mid = Signature::mean(&[a.clone(), b.clone()]).unwrap();
+        assert!(mid.distance(&(&(&a + &b) * 0.5)) < 1e-12);
+
+        let mut acc = SignatureAccumulator::new();
+        assert!(acc.mean().is_none());
+        acc.push(&a);
+        acc.push(&b);
+        assert_eq!(acc.count(), 2);
+        assert_eq!(acc.mean(), Some(&mid));
+    }
+
+    #[test]
+    fn test_term_accessors() {
+        let mut sig = path_signature(&random_increments(5, 2, 60));
+        assert_eq!(sig.get(2, &[1, 2]), Some(sig.level(2)[sig.idx2(1, 2)]));
+        assert_eq!(sig.get(3, &[2, 0, 1]), Some(sig.level(3)[sig.idx3(2, 0, 1)]));
+        assert_eq!(sig.get(2, &[3, 0]), None);
+        assert_eq!(sig.get(2, &[0]), None);
+        assert_eq!(sig.get(4, &[0, 0, 0, 0]), None);
+
+        assert!(sig.set(3, &[1, 1, 2], 42.0));
+        assert_eq!(sig.get(3, &[1, 1, 2]), Some(42.0));
+        assert!(!sig.set(0, &[], 1.0));
+
+        let terms: Vec<_> = sig.iter_terms().collect();
+        assert_eq!(terms.len(), 3 + 9 + 27);
+        for (level, indices, value) in terms {
+            assert_eq!(sig.get(level, &indices), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_levy_area() {
+        let bent = path_signature(&random_increments(6, 2, 61));
+        for i in 0..3 {
+            assert_eq!(bent.levy_area(i, i), Some(0.0));
+            for j in 0..3 {
+                assert_eq!(bent.levy_area(i, j).unwrap(), -bent.levy_area(j, i).unwrap());
+            }
+        }
+        assert!(bent.levy_area(1, 2).unwrap().abs() > 1e-6);
+
+        // Segment rectiligne monotone découpé en plusieurs incréments colinéaires
+        let straight = path_signature(&[(0.1, vec![0.2, 0.4]), (0.2, vec![0.4, 0.8]), (0.05, vec![0.1, 0.2])]);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!(straight.levy_area(i, j).unwrap().abs() < 1e-12);
+            }
+        }
+
+        // Canal hors bornes
+        assert_eq!(bent.levy_area(0, 3), None);
+        assert_eq!(bent.levy_area(3, 0), None);
+    }
+
+    #[test]
+    fn test_features_straight_vs_bounce() {
+        let straight = path_signature(&[(0.1, vec![0.5]), (0.1, vec![0.5]), (0.1, vec![0.5]), (0.1, vec![0.5])]);
+        let bounce = path_signature(&[(0.1, vec![0.5]), (0.1, vec![0.5]), (0.1, vec![-0.5]), (0.1, vec![-0.5])]);
+
+        let fs = straight.features();
+        let fb = bounce.features();
+        assert!(fs.levy_area < 1e-12);
+        assert!(fs.curvature_ratio < 1e-12);
+        assert!((fs.total_displacement - 2.0).abs() < 1e-12);
+        assert!(fb.levy_area > 0.05);
+        assert!(fb.curvature_ratio > 0.01);
+        assert!(fb.total_displacement < 1e-12);
+
+        // L'échelle homogène est linéaire : doubler le chemin double le proxy
+        let doubled = path_signature(&[(0.2, vec![1.0]), (0.2, vec![1.0]), (0.2, vec![1.0]), (0.2, vec![1.0])]);
+        assert!((doubled.features().path_length_proxy - 2.0 * fs.path_length_proxy).abs() < 1e-9);
+
+        let uv = |s: Signature| UniversalVector { signature: s, gradient: Gradient::zero(), metadata: Metadata::zero() };
+        let tol = FeatureTolerances { total_displacement: 0.1, path_length_proxy: 0.5, levy_area: 0.01, curvature_ratio: 0.01 };
+        assert!(uv(straight.clone()).quick_match(&uv(straight.clone()), &tol));
+        assert!(!uv(straight).quick_match(&uv(bounce), &tol));
+    }
+
+    #[test]
+    fn test_gradient_statistics() {
+        let g = Gradient::update(&[(0.5, vec![1.0, 0.0]), (0.5, vec![1.0, 2.0]), (1.0, vec![-1.0, 0.0])]);
+        assert_eq!(g.len(), 3);
+        assert_eq!(g.iter().count(), 3);
+        assert_eq!(g.as_slice()[1], (0.5, vec![1.0, 2.0]));
+        assert_eq!(g.duration(), 2.0);
+        assert_eq!(g.total_displacement(), vec![1.0, 2.0]);
+        assert_eq!(g.mean_velocity(), vec![0.5, 1.0]);
+        assert!((g.max_velocity() - 5.0f64.sqrt() / 0.5).abs() < 1e-12);
+        assert!(Gradient::zero().mean_velocity().is_empty());
+    }
+
+    #[test]
+    fn test_gradient_resample() {
+        let g = Gradient::update(&random_increments(12, 2, 70));
+        assert!(g.resample(0).is_empty());
+
+        let one = g.resample(1);
+        assert_eq!(one.len(), 1);
+        assert!((one.as_slice()[0].0 - g.duration()).abs() < 1e-12);
+        for (a, b) in one.total_displacement().iter().zip(g.total_displacement().iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // Un rééchantillonnage fin reproduit la signature à une tolérance près
+        let fine = g.resample(400);
+        assert_eq!(fine.len(), 400);
+        assert!((fine.duration() - g.duration()).abs() < 1e-9);
+        let original = path_signature(g.as_slice());
+        let resampled = path_signature(fine.as_slice());
+        assert!(original.distance(&resampled) < 0.05 * original.magnitude());
+    }
+
+    #[test]
+    fn test_metric_selection() {
+        let a = path_signature(&random_increments(5, 2, 80));
+        let b = path_signature(&random_increments(5, 2, 81));
+        let metrics = [SignatureMetric::L2, SignatureMetric::L1, SignatureMetric::Cosine, SignatureMetric::NormalizedL2];
+        for metric in metrics {
+            assert!(a.distance_with(&a, metric).abs() < 1e-12, "{:?} identity", metric);
+            assert!((a.distance_with(&b, metric) - b.distance_with(&a, metric)).abs() < 1e-12, "{:?} symmetry", metric);
+            assert!(a.distance_with(&b, metric) > 0.0);
+        }
+        assert_eq!(a.distance_with(&b, SignatureMetric::L2), a.distance(&b));
+
+        // Seule l'amplitude change : cosinus et L2 normalisée l'ignorent, pas L2
+        let loud = &a * 10.0;
+        assert!(a.distance_with(&loud, SignatureMetric::Cosine) < 1e-12);
+        assert!(a.distance_with(&loud, SignatureMetric::NormalizedL2) < 1e-9);
+        assert!(a.distance_with(&loud, SignatureMetric::L2) > 1.0);
+        assert!(a.distance_with(&loud, SignatureMetric::L1) > 1.0);
+
+        let (ua, ul) = (
+            UniversalVector { signature: a, gradient: Gradient::zero(), metadata: Metadata::zero() },
+            UniversalVector { signature: loud, gradient: Gradient::zero(), metadata: Metadata::zero() },
+        );
+        assert!((ua.resonance_structural_with_metric(&ul, 0.1, SignatureMetric::Cosine) - 1.0).abs() < 1e-9);
+        assert!(ua.resonance_structural_with_metric(&ul, 0.1, SignatureMetric::L2) < 1e-6);
+    }
+
+    #[test]
+    fn test_blend_policies() {
+        let base = vector_of(&random_increments(8, 2, 90));
+        let target = vector_of(&random_increments(8, 2, 91));
+        let short = vector_of(&random_increments(3, 2, 92));
+
+        // Par défaut : le gradient n'est pas touché
+        let mut v = base.clone();
+        v.blend(&target, 0.5);
+        assert_eq!(v.gradient, base.gradient);
+        assert_eq!(v.signature, Signature::lerp(&base.signature, &target.signature, 0.5));
+
+        let mut full = base.clone();
+        full.blend_with(&target, 0.5, BlendPolicy::Full { gradient_alpha: 1.0 });
+        assert_eq!(full.gradient, target.gradient);
+        assert_eq!(full.signature, v.signature);
+
+        // Longueurs différentes : pas de panique, la durée est interpolée
+        let mut mixed = base.clone();
+        mixed.blend_with(&short, 0.5, BlendPolicy::Full { gradient_alpha: 0.5 });
+        assert_eq!(mixed.gradient.len(), 8);
+        let expected = (base.gradient.duration() + short.gradient.duration()) / 2.0;
+        assert!((mixed.gradient.duration() - expected).abs() < 1e-9);
+
+        let mut resampled = short.clone();
+        resampled.blend_with(&base, 0.25, BlendPolicy::Resampled { n: 5 });
+        assert_eq!(resampled.gradient.len(), 5);
+        for ((a, b), r) in short.gradient.total_displacement().iter()
+            .zip(base.gradient.total_displacement().iter())
+            .zip(resampled.gradient.total_displacement().iter())
+        {
+            assert!((a + (b - a) * 0.25 - r).abs() < 1e-9);
+        }
+
+        let mut empty = UniversalVector { gradient: Gradient::zero(), ..base.clone() };
+        empty.blend_with(&target, 0.5, BlendPolicy::Full { gradient_alpha: 0.5 });
+        assert_eq!(empty.gradient, target.gradient);
+    }
+
+    #[test]
+    fn test_shape_distance_ignores_speed() {
+        // Même rampe x : 0 → amplitude, parcourue en `seconds` (50 échantillons)
+        let ramp = |amplitude: f64, seconds: f64| -> Vec<(f64, Vec<f64>)> {
+            (0..49).map(|_| (seconds / 49.0, vec![amplitude / 49.0])).collect()
+        };
+        let fast = vector_of(&ramp(1.0, 1.0));
+        let slow = vector_of(&ramp(1.0, 3.0));
+        assert!(fast.signature.distance(&slow.signature) > 0.1);
+        assert!(fast.shape_distance(&slow) < 1e-9);
+        // Plus ample : même forme
+        assert!(fast.shape_distance(&vector_of(&ramp(2.5, 1.0))) < 1e-9);
+
+        // Arc de cercle parcouru à vitesse constante puis variable : mêmes points
+        let arc = |dt: &dyn Fn(usize) -> f64| -> Vec<(f64, Vec<f64>)> {
+            (0..40).map(|i| {
+                let (a, b) = (i as f64 * 0.05, (i + 1) as f64 * 0.05);
+                (dt(i), vec![b.cos() - a.cos(), b.sin() - a.sin()])
+            }).collect()
+        };
+        let steady = vector_of(&arc(&|_| 0.02));
+        let jerky = vector_of(&arc(&|i| if i % 3 == 0 { 0.1 } else { 0.005 }));
+        assert!(steady.shape_distance(&jerky) < 1e-9);
+
+        // La forme compte toujours : une rampe descendante reste éloignée
+        assert!(fast.shape_distance(&vector_of(&ramp(-1.0, 1.0))) > 0.5);
+    }
+
+    #[test]
+    fn test_fingerprint_tolerance() {
+        let sig = Signature::from_segment(0.1, &[0.3, -0.7]);
+        let tol = 1e-4;
+
+        let below = &sig + &(&Signature::from_segment(1.0, &[1.0, 1.0]) * 1e-7);
+        assert_eq!(sig.fingerprint(tol), below.fingerprint(tol));
+        assert!(sig.approx_eq(&below, tol));
+
+        let above = &sig + &(&Signature::from_segment(1.0, &[1.0, 1.0]) * 1e-3);
+        assert_ne!(sig.fingerprint(tol), above.fingerprint(tol));
+        assert!(!sig.approx_eq(&above, tol));
+
+        assert_ne!(Signature::zero(3).fingerprint(tol), Signature::zero(2).fingerprint(tol));
+        assert!(!Signature::zero(3).approx_eq(&Signature::zero_with_depth(3, 2), tol));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::perception::adaptive_normalizer::{Normalizer, NormalizerError};
+
+use std::cell::RefCell;
+
+/// Pivot de Cholesky minimal, relatif à la plus grande variance, en deçà duquel la
+/// covariance est jugée quasi singulière.
+const SINGULAR_RATIO: f64 = 1e-9;
+
+/// Normalisation tenant compte des corrélations entre canaux : x ↦ L⁻¹ (x − moyenne),
+/// où L L^T est la covariance courante (Cholesky). Les échantillons blanchis ont une
+/// covariance identité, si bien que deux canaux fortement corrélés ne comptent plus
+/// double dans les distances entre signatures.
+///
+/// La covariance est mise à jour en ligne (Welford multivarié) ; sa factorisation,
+/// en O(d³), n'est recalculée qu'au premier `normalize` suivant une mise à jour. Si la
+/// covariance est quasi singulière, chaque dimension est réduite séparément.
+#[derive(Debug, Clone, Default)]
+pub struct WhiteningNormalizer {
+    count: u64,
+    mean: Vec<f64>,
+    // Co-moments Σ (x_i − mean_i)(x_j − mean_j), matrice d × d en lignes
+    comoment: Vec<f64>,
+    // Facteur de Cholesky de la covariance (None : quasi singulière), calculé à la demande
+    factor: RefCell<Option<Option<Vec<f64>>>>,
+}
+
+impl WhiteningNormalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Covariance courante (d × d en lignes), nulle tant que count < 2.
+    pub fn covariance(&self) -> Vec<f64> {
+        let n = self.count.saturating_sub(1).max(1) as f64;
+        self.comoment.iter().map(|c| if self.count < 2 { 0.0 } else { c / n }).collect()
+    }
+
+    /// Vrai si la dernière covariance factorisée était quasi singulière (repli diagonal).
+    pub fn is_degenerate(&self) -> bool {
+        self.factorized().is_none()
+    }
+
+    fn factorized(&self) -> Option<Vec<f64>> {
+        self.factor.borrow_mut().get_or_insert_with(|| self.cholesky()).clone()
+    }
+
+    /// L triangulaire inférieure telle que L L^T = covariance.
+    fn cholesky(&self) -> Option<Vec<f64>> {
+        let d = self.mean.len();
+        let covariance = self.covariance();
+        let largest = (0..d).map(|i| covariance[i * d + i]).fold(0.0, f64::max);
+        let mut l = vec![0.0; d * d];
+        for i in 0..d {
+            for j in 0..=i {
+                let sum: f64 = (0..j).map(|k| l[i * d + k] * l[j * d + k]).sum();
+                if i == j {
+                    let pivot = covariance[i * d + i] - sum;
+                    if pivot.is_nan() || pivot <= SINGULAR_RATIO * largest || largest <= 0.0 {
+                        return None;
+                    }
+                    l[i * d + i] = pivot.sqrt();
+                } else {
+                    l[i * d + j] = (covariance[i * d + j] - sum) / l[j * d + j];
+                }
+            }
+        }
+        Some(l)
+    }
+}
+
+impl Normalizer for WhiteningNormalizer {
+    fn dim(&self) -> Option<usize> {
+        (self.count > 0).then_some(self.mean.len())
+    }
+
+    /// Un échantillon non fini est refusé : il empoisonnerait la covariance pour de bon.
+    fn try_update_at(&mut self, values: &[f64], _t: f64) -> Result<(), NormalizerError> {
+        if let Some(index) = values.iter().position(|x| !x.is_finite()) {
+            return Err(NormalizerError::NonFinite { index });
+        }
+        if self.count == 0 {
+            self.mean = vec![0.0; values.len()];
+            self.comoment = vec![0.0; values.len() * values.len()];
+        } else if values.len() != self.mean.len() {
+            return Err(NormalizerError::DimensionMismatch { expected: self.mean.len(), got: values.len() });
+        }
+
+        let d = values.len();
+        self.count += 1;
+        let before: Vec<f64> = values.iter().zip(&self.mean).map(|(x, m)| x - m).collect();
+        for (m, delta) in self.mean.iter_mut().zip(&before) {
+            *m += delta / self.count as f64;
+        }
+        let after: Vec<f64> = values.iter().zip(&self.mean).map(|(x, m)| x - m).collect();
+        for (row, b) in self.comoment.chunks_mut(d).zip(&before) {
+            for (c, a) in row.iter_mut().zip(&after) {
+                *c += b * a;
+            }
+        }
+        *self.factor.get_mut() = None;
+        Ok(())
+    }
+
+    /// Brut tant que la covariance n'existe pas, ou pour un vecteur d'une autre dimension.
+    fn normalize(&self, values: &[f64]) -> Vec<f64> {
+        if self.count < 2 || values.len() != self.mean.len() {
+            return values.to_vec();
+        }
+        let d = self.mean.len();
+        let centered: Vec<f64> = values.iter().zip(&self.mean).map(|(x, m)| x - m).collect();
+        match self.factorized() {
+            Some(l) => {
+                // Substitution avant : L y = x − moyenne
+                let mut y = vec![0.0; d];
+                for i in 0..d {
+                    let sum: f64 = (0..i).map(|k| l[i * d + k] * y[k]).sum();
+                    y[i] = (centered[i] - sum) / l[i * d + i];
+                }
+                y
+            }
+            None => {
+                // Repli diagonal
+                let covariance = self.covariance();
+                centered.iter().enumerate().map(|(i, c)| {
+                    let std_dev = covariance[i * d + i].sqrt();
+                    if std_dev > 1e-9 { c / std_dev } else { 0.0 }
+                }).collect()
+            }
+        }
+    }
+
+    fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::universal_scanner::{ScannerConfig, UniversalScanner};
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_whitened_covariance_is_identity() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let mut gaussian = || {
+            let (u, v): (f64, f64) = (rng.gen_range(f64::EPSILON..1.0), rng.gen_range(0.0..1.0));
+            (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+        };
+        // Canaux corrélés à 0.95, d'échelles différentes
+        let samples: Vec<[f64; 2]> = (0..20_000).map(|_| {
+            let (a, b) = (gaussian(), gaussian());
+            [3.0 + 2.0 * a, -1.0 + 0.5 * (0.95 * a + 0.312 * b)]
+        }).collect();
+
+        let mut whitening = WhiteningNormalizer::new();
+        for s in &samples {
+            whitening.try_update_at(s, 0.0).unwrap();
+        }
+        assert!(!whitening.is_degenerate());
+        let white: Vec<Vec<f64>> = samples.iter().map(|s| whitening.normalize(s)).collect();
+        let n = white.len() as f64;
+        let mean: Vec<f64> = (0..2).map(|i| white.iter().map(|w| w[i]).sum::<f64>() / n).collect();
+        for i in 0..2 {
+            assert!(mean[i].abs() < 1e-9);
+            for j in 0..2 {
+                let cov = white.iter().map(|w| (w[i] - mean[i]) * (w[j] - mean[j])).sum::<f64>() / (n - 1.0);
+                assert!((cov - if i == j { 1.0 } else { 0.0 }).abs() < 1e-6, "cov[{}][{}] = {}", i, j, cov);
+            }
+        }
+
+        // Canaux parfaitement corrélés : repli diagonal, valeurs finies
+        let mut degenerate = WhiteningNormalizer::new();
+        for i in 0..100 {
+            let x = (i as f64 * 0.3).sin();
+            degenerate.try_update_at(&[x, 2.0 * x], 0.0).unwrap();
+        }
+        assert!(degenerate.is_degenerate());
+        let z = degenerate.normalize(&[0.5, 1.0]);
+        assert!((z[0] - z[1]).abs() < 1e-9 && z[0].is_finite());
+        assert!(degenerate.try_update_at(&[1.0], 0.0).is_err());
+
+        // Branché dans le scanner à la place de l'AdaptiveNormalizer
+        let mut scanner = UniversalScanner::with_normalizer(ScannerConfig::new(40), WhiteningNormalizer::new());
+        let mut vectors = Vec::new();
+        for (i, s) in samples.iter().take(400).enumerate() {
+            scanner.ingest(&(i as f64 * 0.01, s.to_vec())).unwrap();
+            if scanner.is_ready() {
+                vectors.extend(scanner.process_and_flush());
+            }
+        }
+        assert!(!vectors.is_empty());
+        assert_eq!(scanner.normalizer().count(), 400);
+        scanner.reset_all();
+        assert_eq!(scanner.normalizer().count(), 0);
+    }
+
+    #[test]
+    fn test_rejects_bad_samples() {
+        let mut whitening = WhiteningNormalizer::new();
+        for i in 0..50 {
+            let x = i as f64 * 0.1;
+            whitening.try_update_at(&[x.sin(), x.cos()], 0.0).unwrap();
+        }
+        let before = whitening.normalize(&[0.3, -0.2]);
+
+        assert_eq!(whitening.try_update_at(&[1.0, f64::NAN], 0.0), Err(NormalizerError::NonFinite { index: 1 }));
+        assert_eq!(whitening.try_update_at(&[f64::INFINITY, 0.0], 0.0), Err(NormalizerError::NonFinite { index: 0 }));
+        assert_eq!(whitening.count(), 50);
+        assert_eq!(whitening.normalize(&[0.3, -0.2]), before);
+        assert!(before.iter().all(|z| z.is_finite()));
+
+        // Mauvaise dimension : rendu brut, sans paniquer
+        assert_eq!(whitening.normalize(&[0.3]), vec![0.3]);
+        assert_eq!(whitening.normalize(&[0.3, -0.2, 1.0]), vec![0.3, -0.2, 1.0]);
+
+        whitening.reset();
+        assert_eq!(whitening.count(), 0);
+        assert_eq!(whitening.dim(), None);
+    }
+}
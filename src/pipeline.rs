@@ -0,0 +1,236 @@
+use crate::cortex::error::CortexError;
+use crate::cortex::prototypical_neural_unit::SignatureHandle;
+use crate::cortex::swarm::Swarm;
+use crate::perception::adaptive_normalizer::{AdaptiveNormalizer, Normalizer};
+use crate::perception::error::PerceptionError;
+use crate::perception::source::UniversalSource;
+use crate::perception::universal_scanner::UniversalScanner;
+use crate::perception::universal_vector::UniversalVector;
+
+use std::fmt;
+
+/// Anything that accepts flattened signatures as input (a swarm, a recorder...).
+pub trait InputSink {
+    /// `input` is in weight-vector space; `handle` carries the raw episode.
+    fn present_input(&mut self, input: Box<[f32]>, handle: SignatureHandle) -> Result<(), CortexError>;
+}
+
+/// The swarm is presented the input at the episode's timestamp; the raw
+/// signature in `handle` is what the matched or spawned unit records.
+impl InputSink for Swarm {
+    fn present_input(&mut self, input: Box<[f32]>, handle: SignatureHandle) -> Result<(), CortexError> {
+        let now = handle.timestamp;
+        self.present_with_episode(&input, now, handle).map(|_| ())
+    }
+}
+
+/// Error of the perception → cortex bridge.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineError {
+    Perception(PerceptionError),
+    Cortex(CortexError),
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineError::Perception(e) => write!(f, "Perception: {}", e),
+            PipelineError::Cortex(e) => write!(f, "Cortex: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PipelineError::Perception(e) => Some(e),
+            PipelineError::Cortex(e) => Some(e),
+        }
+    }
+}
+
+impl From<PerceptionError> for PipelineError {
+    fn from(e: PerceptionError) -> Self {
+        PipelineError::Perception(e)
+    }
+}
+
+impl From<CortexError> for PipelineError {
+    fn from(e: CortexError) -> Self {
+        PipelineError::Cortex(e)
+    }
+}
+
+/// Scanner → swarm connector: every produced `UniversalVector` is flattened
+/// (canonical order), optionally projected onto the unit sphere, and presented
+/// to the sink together with its `SignatureHandle`.
+pub struct PerceptionBridge<N: Normalizer = AdaptiveNormalizer> {
+    scanner: UniversalScanner<N>,
+    unit_sphere: bool,
+    scene_context_id: u64,
+    delivered: usize,
+}
+
+impl<N: Normalizer> PerceptionBridge<N> {
+    /// Unit-sphere projection is on by default (PNU prototypes live there).
+    pub fn new(scanner: UniversalScanner<N>) -> Self {
+        PerceptionBridge { scanner, unit_sphere: true, scene_context_id: 0, delivered: 0 }
+    }
+
+    pub fn set_unit_sphere(&mut self, enabled: bool) {
+        self.unit_sphere = enabled;
+    }
+
+    /// Scene id attached to the handles of subsequent inputs.
+    pub fn set_scene(&mut self, scene_context_id: u64) {
+        self.scene_context_id = scene_context_id;
+    }
+
+    pub fn scanner(&self) -> &UniversalScanner<N> {
+        &self.scanner
+    }
+
+    pub fn scanner_mut(&mut self) -> &mut UniversalScanner<N> {
+        &mut self.scanner
+    }
+
+    /// Total inputs presented so far.
+    pub fn delivered(&self) -> usize {
+        self.delivered
+    }
+
+    /// Flattened signature as a weight-space input. A zero signature stays zero.
+    pub fn to_input(&self, v: &UniversalVector) -> Box<[f32]> {
+        let flat = v.signature.flatten();
+        let norm = flat.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let scale = if self.unit_sphere && norm > 0.0 { 1.0 / norm } else { 1.0 };
+        flat.iter().map(|&x| (x * scale) as f32).collect()
+    }
+
+    /// Ingests one sample and presents every vector it completes.
+    /// Returns the number of inputs delivered; the first input the sink refuses
+    /// stops the delivery (the vectors after it are dropped).
+    pub fn ingest<T: UniversalSource, S: InputSink + ?Sized>(&mut self, data: &T, sink: &mut S) -> Result<usize, PipelineError> {
+        self.scanner.ingest(data)?;
+        let vectors = self.scanner.process_and_flush();
+        Ok(self.deliver(&vectors, sink)?)
+    }
+
+    /// Processes what the scanner still holds (end of stream).
+    pub fn drain<S: InputSink + ?Sized>(&mut self, sink: &mut S) -> Result<usize, CortexError> {
+        let vectors = self.scanner.drain();
+        self.deliver(&vectors, sink)
+    }
+
+    fn deliver<S: InputSink + ?Sized>(&mut self, vectors: &[UniversalVector], sink: &mut S) -> Result<usize, CortexError> {
+        for v in vectors {
+            let handle = SignatureHandle::new(&v.signature, v.metadata.timestamp, self.scene_context_id);
+            sink.present_input(self.to_input(v), handle)?;
+            self.delivered += 1;
+        }
+        Ok(vectors.len())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perception::source::Sample;
+
+    // Records what the bridge delivers, refusing inputs past `limit`
+    struct Recorder {
+        inputs: Vec<Box<[f32]>>,
+        handles: Vec<SignatureHandle>,
+        limit: usize,
+    }
+
+    impl InputSink for Recorder {
+        fn present_input(&mut self, input: Box<[f32]>, handle: SignatureHandle) -> Result<(), CortexError> {
+            if self.inputs.len() == self.limit {
+                return Err(CortexError::DimensionMismatch { expected: 0, got: input.len() });
+            }
+            self.inputs.push(input);
+            self.handles.push(handle);
+            Ok(())
+        }
+    }
+
+    fn samples() -> Vec<Sample> {
+        (0..400)
+            .map(|i| {
+                let t = i as f64 * 0.01;
+                Sample::new(t, vec![(t * 5.0).sin(), (t * 3.0).cos()])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bridge_end_to_end() {
+        let mut bridge = PerceptionBridge::new(UniversalScanner::new(64));
+        bridge.set_scene(7);
+        let mut sink = Recorder { inputs: Vec::new(), handles: Vec::new(), limit: usize::MAX };
+        for s in &samples() {
+            bridge.ingest(s, &mut sink).unwrap();
+        }
+        bridge.drain(&mut sink).unwrap();
+
+        assert!(!sink.inputs.is_empty());
+        assert_eq!(bridge.delivered(), sink.inputs.len());
+        let len = sink.inputs[0].len();
+        for (input, handle) in sink.inputs.iter().zip(&sink.handles) {
+            assert_eq!(input.len(), len);
+            let norm = input.iter().map(|x| x * x).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-5, "norm = {}", norm);
+            assert_eq!(handle.signature_segment.len(), len);
+            assert_eq!(handle.scene_context_id, 7);
+        }
+    }
+
+    #[test]
+    fn test_bridge_feeds_swarm() {
+        let mut bridge = PerceptionBridge::new(UniversalScanner::new(64));
+        bridge.set_scene(7);
+        let mut swarm = Swarm::default();
+        for s in &samples() {
+            bridge.ingest(s, &mut swarm).unwrap();
+        }
+        bridge.drain(&mut swarm).unwrap();
+
+        assert!(!swarm.is_empty());
+        assert_eq!(swarm.stats().presented, bridge.delivered());
+        for pnu in swarm.units() {
+            let norm = pnu.weight_vector.iter().map(|w| w * w).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-4);
+            let episode = pnu.most_recent_episode().unwrap();
+            assert_eq!(episode.signature_segment.len(), pnu.weight_vector.len());
+            assert_eq!(episode.scene_context_id, 7);
+        }
+
+        // A prototype length the scanner does not produce: the swarm refuses
+        let mut mismatched = Swarm::default();
+        mismatched.present_input(&[1.0, 0.0], 0.0).unwrap();
+        let mut bridge = PerceptionBridge::new(UniversalScanner::new(64));
+        let error = samples().iter()
+            .find_map(|s| bridge.ingest(s, &mut mismatched).err())
+            .unwrap();
+        assert!(matches!(error, PipelineError::Cortex(CortexError::DimensionMismatch { expected: 2, .. })));
+        assert_eq!(bridge.delivered(), 0);
+    }
+
+    #[test]
+    fn test_refused_input_stops_delivery() {
+        let mut bridge = PerceptionBridge::new(UniversalScanner::new(64));
+        let mut sink = Recorder { inputs: Vec::new(), handles: Vec::new(), limit: 1 };
+        let mut refused = None;
+        for s in &samples() {
+            if let Err(e) = bridge.ingest(s, &mut sink) {
+                refused = Some(e);
+                break;
+            }
+        }
+        assert!(matches!(refused, Some(PipelineError::Cortex(_))));
+        assert_eq!(bridge.delivered(), 1);
+        assert_eq!(sink.inputs.len(), 1);
+    }
+}